@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS feed_schedules (
+    feed_id INTEGER PRIMARY KEY,
+    interval_secs INTEGER NOT NULL,
+    next_due_at TEXT NOT NULL,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_schedules_next_due
+    ON feed_schedules(next_due_at);
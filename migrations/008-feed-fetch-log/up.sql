@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS feed_fetch_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    feed_id INTEGER NOT NULL,
+    fetched_at TEXT NOT NULL,
+    status_code INTEGER,
+    error_kind TEXT,
+    error_message TEXT,
+    latency_ms INTEGER NOT NULL DEFAULT 0,
+    bytes INTEGER,
+    final_url TEXT,
+    FOREIGN KEY (feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_fetch_log_feed
+    ON feed_fetch_log(feed_id, id DESC);
@@ -0,0 +1,6 @@
+-- URL the feed was subscribed under before a permanent redirect moved it.
+-- Item IDs stay keyed on this so a move doesn't duplicate every entry.
+ALTER TABLE feeds ADD COLUMN original_url TEXT;
+
+-- Cleared when the server answers 410 Gone; inactive feeds are not fetched.
+ALTER TABLE feeds ADD COLUMN is_active INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS enclosures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    mime_type TEXT,
+    length INTEGER,
+    duration_secs INTEGER,
+    UNIQUE (item_id, url),
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE
+);
+
+-- Categories as published by the feed (<category>, Atom term/label).
+CREATE TABLE IF NOT EXISTS item_categories (
+    item_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (item_id, name),
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_categories_name ON item_categories(name);
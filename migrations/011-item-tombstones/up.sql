@@ -0,0 +1,10 @@
+-- IDs of items deleted by pruning, so a later fetch doesn't bring them back.
+-- Removing the feed drops its tombstones along with its items.
+CREATE TABLE IF NOT EXISTS item_tombstones (
+    item_id TEXT PRIMARY KEY,
+    feed_id INTEGER NOT NULL,
+    deleted_at TEXT NOT NULL,
+    FOREIGN KEY (feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_tombstones_deleted_at ON item_tombstones(deleted_at);
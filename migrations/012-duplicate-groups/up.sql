@@ -0,0 +1,37 @@
+-- Duplicate detection keys, filled in by the store on insert.
+ALTER TABLE items ADD COLUMN canonical_url TEXT;
+ALTER TABLE items ADD COLUMN fingerprint TEXT;
+-- Copies of one article in different feeds share a group_id: the ID of the
+-- first copy seen. Items without duplicates point at themselves.
+ALTER TABLE items ADD COLUMN group_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_items_canonical_url ON items(canonical_url);
+CREATE INDEX IF NOT EXISTS idx_items_fingerprint ON items(fingerprint);
+CREATE INDEX IF NOT EXISTS idx_items_group_id ON items(group_id);
+
+-- Reading one copy reads them all; marking one unread does the same.
+CREATE TRIGGER IF NOT EXISTS item_state_group_read_insert
+AFTER INSERT ON item_state
+WHEN NEW.is_read = 1
+BEGIN
+    INSERT INTO item_state (item_id, is_read, read_at)
+    SELECT m.id, 1, NEW.read_at
+    FROM items src
+    JOIN items m ON m.group_id = src.group_id AND m.id != src.id
+    WHERE src.id = NEW.item_id
+    ON CONFLICT(item_id) DO UPDATE SET is_read = 1, read_at = excluded.read_at
+    WHERE is_read = 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS item_state_group_read_update
+AFTER UPDATE OF is_read ON item_state
+WHEN NEW.is_read != OLD.is_read
+BEGIN
+    INSERT INTO item_state (item_id, is_read, read_at)
+    SELECT m.id, NEW.is_read, NEW.read_at
+    FROM items src
+    JOIN items m ON m.group_id = src.group_id AND m.id != src.id
+    WHERE src.id = NEW.item_id
+    ON CONFLICT(item_id) DO UPDATE SET is_read = excluded.is_read, read_at = excluded.read_at
+    WHERE is_read != excluded.is_read;
+END;
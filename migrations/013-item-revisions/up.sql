@@ -0,0 +1,19 @@
+-- Feed-reported modification time and a hash of the feed-provided fields,
+-- used to spot entries that changed after they were stored.
+ALTER TABLE items ADD COLUMN updated_at TEXT;
+ALTER TABLE items ADD COLUMN content_hash TEXT;
+
+-- Earlier versions of items, newest last.
+CREATE TABLE IF NOT EXISTS item_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id TEXT NOT NULL,
+    title TEXT,
+    link TEXT,
+    content TEXT,
+    summary TEXT,
+    updated_at TEXT,
+    replaced_at TEXT NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_revisions_item ON item_revisions(item_id, id);
@@ -0,0 +1,4 @@
+-- Hidden items stay stored, so refreshes don't bring them back, but are left
+-- out of every list, count and search.
+ALTER TABLE item_state ADD COLUMN is_hidden INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE item_state ADD COLUMN hidden_at TEXT;
@@ -0,0 +1,50 @@
+-- Free-form labels on items.
+CREATE TABLE IF NOT EXISTS tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS item_tags (
+    item_id TEXT NOT NULL,
+    tag_id INTEGER NOT NULL,
+    tagged_at TEXT NOT NULL,
+    PRIMARY KEY (item_id, tag_id),
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_tags_tag ON item_tags(tag_id);
+
+-- FTS5 tables can't gain columns, so the search index is rebuilt with the
+-- item's tags as an extra column.
+DROP TABLE IF EXISTS item_search;
+
+CREATE VIRTUAL TABLE item_search USING fts5(
+    item_id UNINDEXED,
+    title,
+    author,
+    summary,
+    content,
+    feed_title,
+    link,
+    tags
+);
+
+INSERT INTO item_search (item_id, title, author, summary, content, feed_title, link, tags)
+SELECT
+    i.id,
+    COALESCE(i.title, ''),
+    COALESCE(i.author, ''),
+    COALESCE(i.summary, ''),
+    COALESCE(i.content, ''),
+    COALESCE(f.title, f.url, ''),
+    COALESCE(i.link, ''),
+    COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = i.id
+    ), '')
+FROM items i
+JOIN feeds f ON f.id = i.feed_id;
@@ -0,0 +1,8 @@
+-- Full-text queries saved under a name and shown as smart feeds
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    query TEXT NOT NULL,
+    filter TEXT NOT NULL DEFAULT 'all',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
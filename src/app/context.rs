@@ -6,12 +6,15 @@ use crate::fetcher::http_fetcher::HttpFetcher;
 use crate::fetcher::parallel::{ParallelFetcher, DEFAULT_WORKERS};
 use crate::fetcher::Fetcher;
 use crate::normalizer::Normalizer;
+use crate::rules::RuleSet;
 use crate::scraper::{spawn_background_scraper, BackgroundScraperHandle, ScraperConfig};
 use crate::store::sqlite::SqliteStore;
 
 pub struct AppContext {
     pub store: Arc<SqliteStore>,
     pub fetcher: Arc<dyn Fetcher + Send + Sync>,
+    /// The HTTP client behind `fetcher`, also used to scrape pages
+    pub http_fetcher: HttpFetcher,
     pub parallel_fetcher: ParallelFetcher,
     pub normalizer: Normalizer,
     pub scraper_handle: Option<BackgroundScraperHandle>,
@@ -37,18 +40,20 @@ impl AppContext {
         };
 
         let store = Arc::new(SqliteStore::new(&db_path)?);
-        let fetcher: Arc<dyn Fetcher + Send + Sync> = Arc::new(HttpFetcher::new());
+        let http_fetcher = HttpFetcher::new();
+        let fetcher: Arc<dyn Fetcher + Send + Sync> = Arc::new(http_fetcher.clone());
         let parallel_fetcher = ParallelFetcher::with_workers(fetcher.clone(), workers);
         let normalizer = Normalizer::new();
 
         // Spawn background scraper if enabled
         let scraper_handle = scraper_config
             .filter(|c| c.enabled)
-            .map(|config| spawn_background_scraper(config, store.clone()));
+            .map(|config| spawn_background_scraper(config, http_fetcher.clone(), store.clone()));
 
         Ok(Self {
             store,
             fetcher,
+            http_fetcher,
             parallel_fetcher,
             normalizer,
             scraper_handle,
@@ -63,6 +68,7 @@ impl AppContext {
         Ok(Self {
             store,
             fetcher,
+            http_fetcher: HttpFetcher::new(),
             parallel_fetcher,
             normalizer,
             scraper_handle: None,
@@ -75,19 +81,27 @@ impl AppContext {
 
     pub fn in_memory_with_workers(workers: usize) -> Result<Self> {
         let store = Arc::new(SqliteStore::in_memory()?);
-        let fetcher: Arc<dyn Fetcher + Send + Sync> = Arc::new(HttpFetcher::new());
+        let http_fetcher = HttpFetcher::new();
+        let fetcher: Arc<dyn Fetcher + Send + Sync> = Arc::new(http_fetcher.clone());
         let parallel_fetcher = ParallelFetcher::with_workers(fetcher.clone(), workers);
         let normalizer = Normalizer::new();
 
         Ok(Self {
             store,
             fetcher,
+            http_fetcher,
             parallel_fetcher,
             normalizer,
             scraper_handle: None,
         })
     }
 
+    /// Applies `[[rules]]` to items inserted by refreshes.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.parallel_fetcher = self.parallel_fetcher.with_rules(rules);
+        self
+    }
+
     /// Queue items for background scraping (if scraper is enabled)
     pub async fn queue_for_scraping(&self, items: Vec<crate::domain::Item>) {
         if let Some(ref handle) = self.scraper_handle {
@@ -11,12 +11,18 @@ pub enum RivuletError {
     #[error("Feed parsing error: {0}")]
     FeedParse(String),
 
+    #[error("OPML parsing error: {0}")]
+    Opml(String),
+
     #[error("Invalid URL: {0}")]
     InvalidUrl(#[from] url::ParseError),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
     #[error("Feed not found: {0}")]
     FeedNotFound(String),
 
@@ -26,6 +32,9 @@ pub enum RivuletError {
     #[error("Configuration error: {0}")]
     Config(String),
 
+    #[error("Invalid search query: {0}")]
+    InvalidQuery(String),
+
     #[error("Scraper error: {0}")]
     Scraper(String),
 
@@ -33,6 +42,32 @@ pub enum RivuletError {
     Other(String),
 }
 
+impl RivuletError {
+    /// Short category recorded in the feed fetch log.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RivuletError::Http(e) if e.is_timeout() => "timeout",
+            RivuletError::Http(e) if e.is_connect() => "connect",
+            RivuletError::Http(e) if e.is_redirect() => "redirect",
+            RivuletError::Http(e) if e.is_status() => "http",
+            RivuletError::Http(e) if e.is_decode() || e.is_body() => "body",
+            RivuletError::Http(_) => "network",
+            RivuletError::FeedParse(_) => "parse",
+            RivuletError::InvalidUrl(_) => "url",
+            RivuletError::Database(_) => "database",
+            _ => "other",
+        }
+    }
+
+    /// HTTP status code, if the error came from a non-success response.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            RivuletError::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+}
+
 impl From<std::convert::Infallible> for RivuletError {
     fn from(x: std::convert::Infallible) -> Self {
         match x {}
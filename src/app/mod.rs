@@ -1,5 +1,6 @@
 pub mod context;
 pub mod error;
+pub mod subscribe;
 
 pub use context::AppContext;
 pub use error::{Result, RivuletError};
@@ -0,0 +1,211 @@
+//! Subscribing to a URL, shared by `rivulet add` and the preview's link
+//! picker: find the feed, check that it parses, then store it with its
+//! first fetch as a one-feed refresh run.
+
+use chrono::Utc;
+
+use crate::app::{AppContext, Result};
+use crate::domain::{Feed, FeedUpdate};
+use crate::fetcher::discovery::{self, Discovery, FeedCandidate};
+use crate::fetcher::parallel::store_items;
+use crate::fetcher::schedule::{self, RefreshHints};
+use crate::fetcher::FetchResult;
+use crate::store::{RefreshSource, Store};
+
+/// How subscribing to a URL turned out.
+#[derive(Debug)]
+pub enum Subscription {
+    /// A new feed, with how many items its first fetch stored
+    Added {
+        url: String,
+        title: Option<String>,
+        new_count: usize,
+    },
+    /// The feed was already subscribed; `reactivated` if it had been marked gone
+    Existing { url: String, reactivated: bool },
+    /// The page advertised feeds but none was chosen
+    NoneChosen,
+}
+
+/// Subscribes to `url`, or to the feed `choose` picks from those the page
+/// at `url` advertises. The feed is parsed before anything is stored, so a
+/// candidate that is not a feed leaves no subscription behind. A permanent
+/// redirect on the first fetch subscribes to its target.
+pub async fn subscribe<F>(
+    ctx: &AppContext,
+    url: &str,
+    source: RefreshSource,
+    choose: F,
+) -> Result<Subscription>
+where
+    F: FnOnce(&str, Vec<FeedCandidate>) -> Result<Option<FeedCandidate>>,
+{
+    if let Some(existing) = existing_subscription(ctx, url)? {
+        return Ok(existing);
+    }
+    let (feed_url, result) =
+        match discovery::discover(ctx.fetcher.as_ref(), &ctx.normalizer, url).await? {
+            Discovery::Feed(result) => (url.to_string(), result),
+            Discovery::Candidates(candidates) => {
+                let Some(candidate) = choose(url, candidates)? else {
+                    return Ok(Subscription::NoneChosen);
+                };
+                if let Some(existing) = existing_subscription(ctx, &candidate.url)? {
+                    return Ok(existing);
+                }
+                let result = ctx.fetcher.fetch(&candidate.url, None, None).await?;
+                (candidate.url, result)
+            }
+        };
+    let feed_url = match result.redirect().filter(|r| r.is_permanent()) {
+        Some(redirect) => {
+            if let Some(existing) = existing_subscription(ctx, &redirect.url)? {
+                return Ok(existing);
+            }
+            redirect.url.clone()
+        }
+        None => feed_url,
+    };
+
+    let parsed = match result {
+        FetchResult::Content {
+            ref body,
+            ref etag,
+            ref last_modified,
+            max_age_secs,
+            ..
+        } => {
+            let (meta, items) = ctx.normalizer.normalize(0, &feed_url, body)?;
+            let hints = RefreshHints {
+                posting_gap_secs: schedule::posting_gap_secs(&items),
+                feed_ttl_secs: meta.ttl_secs,
+                http_max_age_secs: max_age_secs,
+            };
+            let update = FeedUpdate {
+                title: meta.title,
+                description: meta.description,
+                etag: etag.clone(),
+                last_modified: last_modified.clone(),
+                last_fetched_at: Some(Utc::now()),
+            };
+            Some((update, hints, items))
+        }
+        FetchResult::NotModified { .. } => None,
+    };
+
+    let mut feed = Feed::new(feed_url.clone());
+    feed.id = ctx.store.add_feed(&feed)?;
+    let run_id = ctx.store.begin_refresh_run(source, 1)?;
+    let mut new_count = 0;
+    if let Some((update, hints, mut items)) = parsed {
+        for item in &mut items {
+            item.feed_id = feed.id;
+        }
+        ctx.store.update_feed(feed.id, &update)?;
+        schedule::record_success(ctx.store.as_ref(), feed.id, Some(&hints), Utc::now())?;
+        feed.title = update.title;
+
+        // Rules run on the first fetch as on any refresh
+        let add_result = store_items(
+            ctx.store.as_ref(),
+            &feed,
+            &items,
+            ctx.parallel_fetcher.rules(),
+        )?;
+        new_count = add_result.count;
+        ctx.store
+            .record_refresh_run_items(run_id, feed.id, &add_result.inserted_ids)?;
+        ctx.queue_for_scraping(items).await;
+    }
+    ctx.store.complete_refresh_run(run_id, new_count, 0)?;
+
+    Ok(Subscription::Added {
+        url: feed_url,
+        title: feed.title,
+        new_count,
+    })
+}
+
+/// `Some` if `url` is already subscribed, reactivating it if it had been
+/// marked gone.
+fn existing_subscription(ctx: &AppContext, url: &str) -> Result<Option<Subscription>> {
+    let Some(existing) = ctx.store.get_feed_by_url(url)? else {
+        return Ok(None);
+    };
+    let reactivated = !existing.is_active;
+    if reactivated {
+        ctx.store.set_feed_active(existing.id, true)?;
+    }
+    Ok(Some(Subscription::Existing {
+        url: existing.url,
+        reactivated,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::testing::MockFetcher;
+    use crate::fetcher::{Redirect, RedirectKind};
+    use std::sync::Arc;
+
+    const FEED: &str = r#"<rss version="2.0"><channel><title>Moved</title>
+<item><guid>a</guid><title>First</title></item></channel></rss>"#;
+
+    fn moved_to(url: &str) -> FetchResult {
+        FetchResult::Content {
+            status: 200,
+            body: FEED.as_bytes().to_vec(),
+            etag: None,
+            last_modified: None,
+            max_age_secs: None,
+            redirect: Some(Redirect {
+                url: url.to_string(),
+                kind: RedirectKind::Permanent,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_permanent_redirect_subscribes_to_its_target() {
+        let mock = Arc::new(MockFetcher::new());
+        mock.set_response(
+            "https://old.example.com/rss",
+            moved_to("https://new.example.com/rss"),
+        );
+        mock.set_response(
+            "https://again.example.com/rss",
+            moved_to("https://new.example.com/rss"),
+        );
+        let ctx = AppContext::in_memory_with_fetcher(mock).unwrap();
+        let no_choice = |_: &str, _: Vec<FeedCandidate>| Ok(None);
+
+        let added = subscribe(
+            &ctx,
+            "https://old.example.com/rss",
+            RefreshSource::Cli,
+            no_choice,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            added,
+            Subscription::Added { ref url, new_count: 1, .. } if url == "https://new.example.com/rss"
+        ));
+        let feeds = ctx.store.get_all_feeds().unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].title.as_deref(), Some("Moved"));
+
+        // Another URL moving to the same feed finds the subscription
+        let again = subscribe(
+            &ctx,
+            "https://again.example.com/rss",
+            RefreshSource::Cli,
+            no_choice,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(again, Subscription::Existing { .. }));
+        assert_eq!(ctx.store.get_all_feeds().unwrap().len(), 1);
+    }
+}
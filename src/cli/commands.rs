@@ -1,13 +1,25 @@
+use std::io::{IsTerminal, Write};
 use std::path::Path;
 use std::path::PathBuf;
 
 use chrono::Utc;
+use serde::Serialize;
 
+use crate::app::subscribe::{subscribe, Subscription};
 use crate::app::{AppContext, Result, RivuletError};
-use crate::domain::{AuthProfile, Feed, FeedUpdate};
-use crate::fetcher::FetchResult;
-use crate::scraper::{ChromeScraper, Scraper, ScraperConfig};
-use crate::store::{ItemListFilter, RefreshSource, Store};
+use crate::cli::{output, OutputFormat, StateFlags};
+use crate::config::{ColorConfig, RetentionConfig};
+use crate::daemon::DaemonConfig;
+use crate::domain::{AuthProfile, Category, Feed, FetchLogEntry, Item, ItemState};
+use crate::fetcher::discovery::{self, FeedCandidate};
+use crate::html::render_html;
+use crate::opml;
+use crate::rules::RuleSet;
+use crate::scraper::{ArticleScraper, ChromeScraper, Scraper, ScraperConfig};
+use crate::store::query::render_snippet;
+use crate::store::{
+    ItemListFilter, ItemSelector, ItemStateChange, PruneReport, RefreshSource, Store,
+};
 
 /// Initialize config file with all options
 pub fn init_config(force: bool) -> Result<()> {
@@ -36,61 +48,80 @@ pub fn init_config(force: bool) -> Result<()> {
 }
 
 pub async fn add_feed(ctx: &AppContext, url: &str) -> Result<()> {
-    // Check if feed already exists
-    if ctx.store.get_feed_by_url(url)?.is_some() {
-        println!("Feed already exists: {}", url);
-        return Ok(());
-    }
-
-    // Create the feed entry
-    let feed = Feed::new(url.to_string());
-    let feed_id = ctx.store.add_feed(&feed)?;
-    println!("Added feed: {}", url);
-
-    // Fetch and store items
-    let run_id = ctx.store.begin_refresh_run(RefreshSource::Cli, 1)?;
-    let result = ctx.fetcher.fetch(url, None, None).await?;
-    let mut total_new = 0;
-
-    match result {
-        FetchResult::Content {
-            body,
-            etag,
-            last_modified,
+    // A site URL is fine too: find the feeds the page advertises
+    match subscribe(ctx, url, RefreshSource::Cli, choose_feed_candidate).await? {
+        Subscription::Added {
+            url,
+            title,
+            new_count,
         } => {
-            let (meta, items) = ctx.normalizer.normalize(feed_id, url, &body)?;
-
-            // Update feed with metadata
-            let update = FeedUpdate {
-                title: meta.title.clone(),
-                description: meta.description,
-                etag,
-                last_modified,
-                last_fetched_at: Some(Utc::now()),
-            };
-            ctx.store.update_feed(feed_id, &update)?;
-
-            // Add items
-            let add_result = ctx.store.add_items_with_report(&items)?;
-            total_new = add_result.count;
-            ctx.store
-                .record_refresh_run_items(run_id, feed_id, &add_result.inserted_ids)?;
-
-            // Queue new items for background scraping
-            ctx.queue_for_scraping(items).await;
-
-            if let Some(title) = meta.title {
+            println!("Added feed: {}", url);
+            if let Some(title) = title {
                 println!("Feed title: {}", title);
             }
-            println!("Fetched {} items", add_result.count);
+            println!("Fetched {} items", new_count);
         }
-        FetchResult::NotModified => {
-            println!("Feed not modified");
+        Subscription::Existing {
+            url,
+            reactivated: false,
+        } => println!("Feed already exists: {}", url),
+        Subscription::Existing {
+            url,
+            reactivated: true,
+        } => println!("Reactivated feed: {}", url),
+        Subscription::NoneChosen => {}
+    }
+    Ok(())
+}
+
+/// Picks one of the feeds discovered on a page. With several candidates the
+/// user chooses interactively; without a terminal they are only listed.
+fn choose_feed_candidate(
+    page_url: &str,
+    mut candidates: Vec<FeedCandidate>,
+) -> Result<Option<FeedCandidate>> {
+    match candidates.len() {
+        0 => {
+            return Err(RivuletError::FeedParse(format!(
+                "{} is not a feed, links to no feeds, and none of {} exist",
+                page_url,
+                discovery::COMMON_FEED_PATHS.join(", ")
+            )))
+        }
+        1 => {
+            let candidate = candidates.remove(0);
+            println!("Found feed: {}", candidate.url);
+            return Ok(Some(candidate));
         }
+        _ => {}
     }
-    ctx.store.complete_refresh_run(run_id, total_new, 0)?;
 
-    Ok(())
+    println!("Found {} feeds on {}:", candidates.len(), page_url);
+    for (index, candidate) in candidates.iter().enumerate() {
+        let label = candidate.title.as_deref().unwrap_or("(untitled)");
+        match candidate.mime_type.as_deref() {
+            Some(mime) => println!("  {}. {} [{}]", index + 1, label, mime),
+            None => println!("  {}. {}", index + 1, label),
+        }
+        println!("     {}", candidate.url);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        println!("Run `rivulet add <URL>` with one of the feeds above");
+        return Ok(None);
+    }
+
+    print!("Subscribe to [1-{}, Enter to cancel]: ", candidates.len());
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    let choice = line
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (1..=candidates.len()).contains(n));
+
+    Ok(choice.map(|n| candidates.swap_remove(n - 1)))
 }
 
 pub async fn remove_feed(ctx: &AppContext, url: &str) -> Result<()> {
@@ -105,7 +136,9 @@ pub async fn remove_feed(ctx: &AppContext, url: &str) -> Result<()> {
 }
 
 pub async fn update_feeds(ctx: &AppContext) -> Result<()> {
-    let feeds = ctx.store.get_all_feeds()?;
+    let mut feeds = ctx.store.get_all_feeds()?;
+    // Feeds that answered 410 Gone stay subscribed but are never fetched.
+    feeds.retain(|feed| feed.is_active);
 
     if feeds.is_empty() {
         println!("No feeds to update");
@@ -178,20 +211,32 @@ pub async fn update_feeds(ctx: &AppContext) -> Result<()> {
     Ok(())
 }
 
-pub fn list_feeds(ctx: &AppContext) -> Result<()> {
+pub fn list_feeds(ctx: &AppContext, format: OutputFormat) -> Result<()> {
     let feeds = ctx.store.get_all_feeds()?;
+    let unread_counts = ctx.store.get_unread_counts()?;
+    let unread = |feed: &Feed| unread_counts.get(&feed.id).copied().unwrap_or(0);
+
+    if format != OutputFormat::Text {
+        let records: Vec<FeedRecord> = feeds
+            .iter()
+            .map(|feed| FeedRecord {
+                unread: unread(feed),
+                feed,
+            })
+            .collect();
+        return output::print_records(format, &records);
+    }
 
     if feeds.is_empty() {
         println!("No feeds");
         return Ok(());
     }
 
-    for feed in feeds {
-        let unread = ctx.store.get_unread_count(feed.id)?;
+    for feed in &feeds {
         println!(
             "{} ({} unread)\n  {}",
             feed.display_title(),
-            unread,
+            unread(feed),
             feed.url
         );
     }
@@ -199,6 +244,137 @@ pub fn list_feeds(ctx: &AppContext) -> Result<()> {
     Ok(())
 }
 
+pub fn feed_health(ctx: &AppContext, stale_days: i64) -> Result<()> {
+    let report = ctx.store.get_feed_health()?;
+    if report.is_empty() {
+        println!("No feeds");
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let stale_after = chrono::Duration::days(stale_days);
+    let broken: Vec<_> = report.iter().filter(|h| h.is_broken()).collect();
+    let redirected: Vec<_> = report
+        .iter()
+        .filter(|h| !h.is_broken() && h.redirected_to().is_some())
+        .collect();
+    let stale: Vec<_> = report
+        .iter()
+        .filter(|h| h.is_stale(now, stale_after))
+        .collect();
+
+    if broken.is_empty() && redirected.is_empty() && stale.is_empty() {
+        println!("All {} feeds healthy", report.len());
+        return Ok(());
+    }
+
+    if !broken.is_empty() {
+        println!("Broken ({})", broken.len());
+        for health in &broken {
+            println!("  {}\n    {}", health.feed.display_title(), health.feed.url);
+            if !health.feed.is_active {
+                println!("    inactive (gone); `rivulet add` the URL again to resume");
+            }
+            let last = health.last_attempt.as_ref();
+            println!(
+                "    {} consecutive failures; last: {}",
+                health.consecutive_failures,
+                last.map(describe_fetch_attempt)
+                    .unwrap_or_else(|| "never fetched".to_string())
+            );
+        }
+    }
+
+    if !redirected.is_empty() {
+        println!("Redirected ({})", redirected.len());
+        for health in &redirected {
+            println!(
+                "  {}\n    {} -> {}",
+                health.feed.display_title(),
+                health.feed.url,
+                health.redirected_to().unwrap_or_default()
+            );
+            if health.is_superseded() {
+                println!(
+                    "    inactive; the new URL is already subscribed, so this feed can be removed"
+                );
+            }
+        }
+    }
+
+    if !stale.is_empty() {
+        println!(
+            "Stale ({}, nothing new in {} days)",
+            stale.len(),
+            stale_days
+        );
+        for health in &stale {
+            let newest = health
+                .newest_item_at
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "no items".to_string());
+            println!(
+                "  {}\n    {}\n    newest item: {}",
+                health.feed.display_title(),
+                health.feed.url,
+                newest
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn feed_fetch_log(ctx: &AppContext, url: &str) -> Result<()> {
+    let feed = ctx
+        .store
+        .get_feed_by_url(url)?
+        .ok_or_else(|| RivuletError::FeedNotFound(url.to_string()))?;
+
+    let log = ctx.store.get_fetch_log(feed.id, 20)?;
+    println!("{}\n  {}", feed.display_title(), feed.url);
+    if let Some(schedule) = ctx.store.get_feed_schedule(feed.id)? {
+        println!(
+            "  next fetch: {} ({} consecutive failures)",
+            schedule
+                .next_due_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            schedule.consecutive_failures
+        );
+    }
+    if log.is_empty() {
+        println!("  No fetch attempts recorded");
+    }
+    for entry in &log {
+        println!("  {}", describe_fetch_attempt(entry));
+    }
+
+    Ok(())
+}
+
+fn describe_fetch_attempt(entry: &FetchLogEntry) -> String {
+    let when = entry
+        .fetched_at
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M");
+    let status = entry
+        .status_code
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "---".to_string());
+    let mut line = format!("{} {} {}ms", when, status, entry.latency_ms);
+    if let Some(bytes) = entry.bytes {
+        line.push_str(&format!(" {}B", bytes));
+    }
+    if let Some(ref kind) = entry.error_kind {
+        line.push_str(&format!(" {}", kind));
+    }
+    if let Some(ref message) = entry.error_message {
+        line.push_str(&format!(": {}", message));
+    }
+    line
+}
+
 pub fn list_filter_from_flags(
     unread: bool,
     starred: bool,
@@ -228,11 +404,185 @@ pub fn list_filter_from_flags(
     }
 }
 
-pub fn list_items(ctx: &AppContext, filter: Option<ItemListFilter>) -> Result<()> {
+pub fn state_change_from_flags(flags: &StateFlags) -> ItemStateChange {
+    let pair = |on: bool, off: bool| match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    ItemStateChange {
+        read: pair(flags.read, flags.unread),
+        starred: pair(flags.star, flags.unstar),
+        queued: pair(flags.queue, flags.unqueue),
+        saved: pair(flags.save, flags.unsave),
+        archived: pair(flags.archive, flags.unarchive),
+        hidden: pair(flags.hide, flags.unhide),
+    }
+}
+
+/// Applies a state change to every item matching the selectors.
+pub fn mark_items(
+    ctx: &AppContext,
+    item_id: Option<String>,
+    feed_url: Option<&str>,
+    search: Option<String>,
+    older_than: Option<&str>,
+    flags: &StateFlags,
+) -> Result<usize> {
+    let change = state_change_from_flags(flags);
+    if change.is_empty() {
+        return Err(RivuletError::Config(
+            "Nothing to change; pass --read, --unread, --star, --queue, --save, --archive, --hide, or an --un* flag"
+                .to_string(),
+        ));
+    }
+    if item_id.is_none() && feed_url.is_none() && search.is_none() && older_than.is_none() {
+        return Err(RivuletError::Config(
+            "Select items with an item ID, --feed, --search, or --older-than".to_string(),
+        ));
+    }
+
+    let feed_id = match feed_url {
+        Some(url) => Some(
+            ctx.store
+                .get_feed_by_url(url)?
+                .ok_or_else(|| RivuletError::FeedNotFound(url.to_string()))?
+                .id,
+        ),
+        None => None,
+    };
+    let older_than = older_than
+        .map(|s| {
+            let secs = DaemonConfig::parse_interval(s).map_err(RivuletError::Config)?;
+            Ok::<_, RivuletError>(Utc::now() - chrono::Duration::seconds(secs as i64))
+        })
+        .transpose()?;
+    if let Some(id) = &item_id {
+        if !ctx.store.item_exists(id)? {
+            return Err(RivuletError::ItemNotFound(id.clone()));
+        }
+    }
+
+    let selector = ItemSelector {
+        item_id,
+        feed_id,
+        search,
+        older_than,
+    };
+    let ids = ctx.store.select_item_ids(&selector)?;
+    let updated = ctx.store.set_item_states(&ids, &change)?;
+    println!(
+        "Marked {} item{}",
+        updated,
+        if updated == 1 { "" } else { "s" }
+    );
+
+    Ok(updated)
+}
+
+/// Deletes items outside the retention policy, or lists them with `dry_run`.
+pub fn prune(ctx: &AppContext, retention: &RetentionConfig, dry_run: bool) -> Result<PruneReport> {
+    let feeds = ctx.store.get_all_feeds()?;
+    let policy = retention.policy(&feeds);
+    let report = ctx.store.prune_items(&policy, dry_run)?;
+
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    let items = report.item_count();
+    println!(
+        "{} {} item{} and {} refresh run{}",
+        verb,
+        items,
+        if items == 1 { "" } else { "s" },
+        report.refresh_runs,
+        if report.refresh_runs == 1 { "" } else { "s" }
+    );
+    for (feed_id, count) in &report.feed_items {
+        let title = feeds
+            .iter()
+            .find(|feed| feed.id == *feed_id)
+            .map(|feed| feed.display_title().to_string())
+            .unwrap_or_else(|| feed_id.to_string());
+        println!("  {:>6}  {}", count, title);
+    }
+    if report.tombstones > 0 {
+        println!(
+            "{} {} expired tombstone{}",
+            if dry_run { "Would drop" } else { "Dropped" },
+            report.tombstones,
+            if report.tombstones == 1 { "" } else { "s" }
+        );
+    }
+
+    if !dry_run && retention.vacuum && !report.is_empty() {
+        println!("Compacting database...");
+        ctx.store.vacuum()?;
+    }
+
+    Ok(report)
+}
+
+/// Dry-runs `rules` against stored items and prints what each would match;
+/// returns the match count per rule.
+pub fn rules_test(ctx: &AppContext, rules: &RuleSet, feed_url: Option<&str>) -> Result<Vec<usize>> {
+    if rules.is_empty() {
+        println!("No [[rules]] in config.toml");
+        return Ok(Vec::new());
+    }
+
+    let feeds = match feed_url {
+        Some(url) => vec![ctx
+            .store
+            .get_feed_by_url(url)?
+            .ok_or_else(|| RivuletError::FeedNotFound(url.to_string()))?],
+        None => ctx.store.get_all_feeds()?,
+    };
+
+    let mut matches: Vec<Vec<(String, Item)>> = vec![Vec::new(); rules.rules().len()];
+    for feed in &feeds {
+        let items = ctx.store.get_items_by_feed(feed.id)?;
+        for (index, ids) in rules
+            .evaluate(ctx.store.as_ref(), feed, &items)?
+            .into_iter()
+            .enumerate()
+        {
+            matches[index].extend(
+                items
+                    .iter()
+                    .filter(|item| ids.contains(&item.id))
+                    .map(|item| (feed.display_title().to_string(), item.clone())),
+            );
+        }
+    }
+
+    for (rule, matched) in rules.rules().iter().zip(&matches) {
+        println!(
+            "{} ({}): {} item{}",
+            rule.name,
+            rule.describe_actions(),
+            matched.len(),
+            if matched.len() == 1 { "" } else { "s" }
+        );
+        for (feed_title, item) in matched {
+            println!("  {}  {}", feed_title, item.display_title());
+        }
+    }
+
+    Ok(matches.iter().map(Vec::len).collect())
+}
+
+pub fn list_items(
+    ctx: &AppContext,
+    filter: Option<ItemListFilter>,
+    format: OutputFormat,
+) -> Result<()> {
     let items = ctx
         .store
         .get_items_by_filter(filter.unwrap_or(ItemListFilter::All))?;
 
+    if format != OutputFormat::Text {
+        return output::print_records(format, &item_records(ctx, items)?);
+    }
+
     if items.is_empty() {
         println!("No items");
         return Ok(());
@@ -250,14 +600,216 @@ pub fn search_items(
     query: &str,
     filter: ItemListFilter,
     limit: usize,
+    format: OutputFormat,
 ) -> Result<()> {
-    let items = ctx.store.search_items(query, filter, limit)?;
+    let hits = ctx.store.search_items(query, filter, limit)?;
+
+    if format != OutputFormat::Text {
+        let (items, snippets): (Vec<_>, Vec<_>) =
+            hits.into_iter().map(|hit| (hit.item, hit.snippet)).unzip();
+        let records: Vec<SearchRecord> = item_records(ctx, items)?
+            .into_iter()
+            .zip(snippets)
+            .map(|(record, snippet)| SearchRecord {
+                record,
+                snippet: snippet.map(|s| render_snippet(&s, "<mark>", "</mark>")),
+            })
+            .collect();
+        return output::print_records(format, &records);
+    }
 
-    if items.is_empty() {
+    if hits.is_empty() {
         println!("No search results");
         return Ok(());
     }
 
+    // Bold yellow hits on a terminal, brackets when piped
+    let (open, close) = if std::io::stdout().is_terminal() {
+        ("\x1b[1;33m", "\x1b[0m")
+    } else {
+        ("[", "]")
+    };
+    for hit in hits {
+        print_item_line(ctx, &hit.item)?;
+        if let Some(snippet) = hit.snippet.filter(|s| !s.is_empty()) {
+            println!("             {}", render_snippet(&snippet, open, close));
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds (or with `remove`, removes) tags on one item.
+pub fn tag_item(ctx: &AppContext, item_id: &str, tags: &[String], remove: bool) -> Result<usize> {
+    let item = ctx
+        .store
+        .get_item(item_id)?
+        .ok_or_else(|| RivuletError::ItemNotFound(item_id.to_string()))?;
+    let ids = [item.id.clone()];
+
+    let mut changed = 0;
+    for tag in tags {
+        changed += if remove {
+            ctx.store.untag_items(&ids, tag)?
+        } else {
+            ctx.store.tag_items(&ids, tag)?
+        };
+    }
+
+    let current = ctx.store.get_item_tags(item_id)?;
+    println!(
+        "{}: {}",
+        item.display_title(),
+        if current.is_empty() {
+            "no tags".to_string()
+        } else {
+            current.join(", ")
+        }
+    );
+
+    Ok(changed)
+}
+
+#[derive(Serialize)]
+struct TagRecord {
+    name: String,
+    items: usize,
+}
+
+/// Lists every tag with its item count, or the items carrying `tag`.
+pub fn list_tags(ctx: &AppContext, tag: Option<&str>, format: OutputFormat) -> Result<()> {
+    if let Some(tag) = tag {
+        let items = ctx.store.get_items_by_tag(tag, ItemListFilter::All)?;
+        if format != OutputFormat::Text {
+            return output::print_records(format, &item_records(ctx, items)?);
+        }
+        if items.is_empty() {
+            println!("No items tagged {}", tag);
+        }
+        for item in items {
+            print_item_line(ctx, &item)?;
+        }
+        return Ok(());
+    }
+
+    let tags = ctx.store.get_tags()?;
+    if format != OutputFormat::Text {
+        let records: Vec<TagRecord> = tags
+            .into_iter()
+            .map(|(name, items)| TagRecord { name, items })
+            .collect();
+        return output::print_records(format, &records);
+    }
+    if tags.is_empty() {
+        println!("No tags");
+    }
+    for (name, count) in tags {
+        println!("{:>6}  {}", count, name);
+    }
+
+    Ok(())
+}
+
+/// Saves a smart feed and reports how many items it currently matches.
+pub fn add_search_feed(
+    ctx: &AppContext,
+    name: &str,
+    query: &str,
+    filter: ItemListFilter,
+) -> Result<()> {
+    if name.trim().is_empty() || query.trim().is_empty() {
+        return Err(RivuletError::Config(
+            "A saved search needs a name and a query".to_string(),
+        ));
+    }
+    ctx.store.save_search(name, query, filter)?;
+    let search = ctx
+        .store
+        .get_saved_search(name)?
+        .ok_or_else(|| RivuletError::Config(format!("Saved search {} vanished", name)))?;
+    let matches = search.items(ctx.store.as_ref())?.len();
+    println!(
+        "Saved search {}: {} {}",
+        search.name,
+        matches,
+        if matches == 1 { "item" } else { "items" }
+    );
+    Ok(())
+}
+
+pub fn remove_search_feed(ctx: &AppContext, name: &str) -> Result<()> {
+    if !ctx.store.delete_saved_search(name)? {
+        return Err(RivuletError::Config(format!(
+            "No saved search named {}",
+            name
+        )));
+    }
+    println!("Removed saved search {}", name.trim());
+    Ok(())
+}
+
+pub fn list_search_feeds(ctx: &AppContext, format: OutputFormat) -> Result<()> {
+    let searches = ctx.store.get_saved_searches()?;
+    let unread = ctx.store.get_saved_search_unread_counts()?;
+
+    if format != OutputFormat::Text {
+        let records: Vec<SavedSearchRecord> = searches
+            .iter()
+            .map(|search| SavedSearchRecord {
+                name: &search.name,
+                query: &search.query,
+                filter: search.filter.as_str(),
+                unread: unread.get(&search.id).copied().unwrap_or(0),
+            })
+            .collect();
+        return output::print_records(format, &records);
+    }
+
+    if searches.is_empty() {
+        println!("No saved searches");
+    }
+    for search in &searches {
+        let filter = match search.filter {
+            ItemListFilter::All => String::new(),
+            filter => format!(" [{}]", filter.as_str()),
+        };
+        println!(
+            "{:>6}  {}: {}{}",
+            unread.get(&search.id).copied().unwrap_or(0),
+            search.name,
+            search.query,
+            filter
+        );
+    }
+
+    Ok(())
+}
+
+/// Items of a saved search. `filter`, if given, replaces the saved filter.
+pub fn list_search_feed(
+    ctx: &AppContext,
+    name: &str,
+    filter: Option<ItemListFilter>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut search = ctx
+        .store
+        .get_saved_search(name)?
+        .ok_or_else(|| RivuletError::Config(format!("No saved search named {}", name)))?;
+    if let Some(filter) = filter {
+        search.filter = filter;
+    }
+    let items = search.items(ctx.store.as_ref())?;
+
+    if format != OutputFormat::Text {
+        return output::print_records(format, &item_records(ctx, items)?);
+    }
+
+    if items.is_empty() {
+        println!("No items");
+        return Ok(());
+    }
+
     for item in items {
         print_item_line(ctx, &item)?;
     }
@@ -265,6 +817,52 @@ pub fn search_items(
     Ok(())
 }
 
+#[derive(Serialize)]
+struct FeedRecord<'a> {
+    #[serde(flatten)]
+    feed: &'a Feed,
+    unread: i64,
+}
+
+#[derive(Serialize)]
+struct SavedSearchRecord<'a> {
+    name: &'a str,
+    query: &'a str,
+    filter: &'static str,
+    unread: i64,
+}
+
+#[derive(Serialize)]
+struct ItemRecord {
+    #[serde(flatten)]
+    item: Item,
+    state: ItemState,
+}
+
+#[derive(Serialize)]
+struct SearchRecord {
+    #[serde(flatten)]
+    record: ItemRecord,
+    /// Hits wrapped in `<mark>`
+    snippet: Option<String>,
+}
+
+/// Items with their state, enclosures, categories, and tags filled in.
+fn item_records(ctx: &AppContext, mut items: Vec<Item>) -> Result<Vec<ItemRecord>> {
+    ctx.store.load_item_media(&mut items)?;
+    let ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+    let mut states = ctx.store.get_item_states(&ids)?;
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let state = states
+                .remove(&item.id)
+                .unwrap_or_else(|| ItemState::new(item.id.clone()));
+            ItemRecord { item, state }
+        })
+        .collect())
+}
+
 fn print_item_line(ctx: &AppContext, item: &crate::domain::Item) -> Result<()> {
     let state = ctx.store.get_item_state(&item.id)?;
     let marker = if let Some(state) = state {
@@ -297,7 +895,29 @@ fn print_item_line(ctx: &AppContext, item: &crate::domain::Item) -> Result<()> {
 /// Import feeds from an OPML file
 pub async fn import_opml(ctx: &AppContext, path: &Path) -> Result<()> {
     let content = std::fs::read_to_string(path)?;
-    let feed_urls = parse_opml(&content)?;
+    let import = opml::parse_feeds(&content)?;
+
+    if !import.skipped.is_empty() {
+        println!("Skipped {} outlines:", import.skipped.len());
+        for skipped in &import.skipped {
+            let label = if skipped.label.is_empty() {
+                "(untitled)"
+            } else {
+                skipped.label.as_str()
+            };
+            if skipped.category_path.is_empty() {
+                println!("  - {}: {}", label, skipped.reason);
+            } else {
+                println!(
+                    "  - {}/{}: {}",
+                    skipped.category_path.join("/"),
+                    label,
+                    skipped.reason
+                );
+            }
+        }
+    }
+    let feed_urls = import.feeds;
 
     if feed_urls.is_empty() {
         println!("No feeds found in OPML file");
@@ -310,20 +930,31 @@ pub async fn import_opml(ctx: &AppContext, path: &Path) -> Result<()> {
     let mut feeds_to_fetch = Vec::new();
     let mut skipped = 0;
 
-    for (title, url) in feed_urls {
-        if ctx.store.get_feed_by_url(&url)?.is_some() {
+    for opml_feed in feed_urls {
+        let category_id = ensure_category_path(ctx, &opml_feed.category_path)?;
+
+        if let Some(existing) = ctx.store.get_feed_by_url(&opml_feed.url)? {
+            // Still file existing feeds under the OPML folder
+            if let Some(category_id) = category_id {
+                ctx.store.add_feed_to_category(existing.id, category_id)?;
+            }
             skipped += 1;
             continue;
         }
 
-        // Create the feed entry
-        let feed = Feed::new(url.clone());
+        // Create the feed entry, keeping the OPML title and description as
+        // fallbacks until the feed itself reports metadata
+        let mut feed = Feed::new(opml_feed.url.clone());
+        // Exports fall back to the URL as outline text for untitled feeds
+        feed.title = Some(opml_feed.title).filter(|title| *title != opml_feed.url);
+        feed.description = opml_feed.description;
         let feed_id = ctx.store.add_feed(&feed)?;
+        if let Some(category_id) = category_id {
+            ctx.store.add_feed_to_category(feed_id, category_id)?;
+        }
 
-        // Store with OPML title as fallback
         let mut feed_with_id = feed;
         feed_with_id.id = feed_id;
-        feed_with_id.title = Some(title);
         feeds_to_fetch.push(feed_with_id);
     }
 
@@ -356,10 +987,7 @@ pub async fn import_opml(ctx: &AppContext, path: &Path) -> Result<()> {
 
     for (feed_id, result) in results {
         let feed = feeds_to_fetch.iter().find(|f| f.id == feed_id);
-        let title = feed
-            .and_then(|f| f.title.as_ref())
-            .map(|s| s.as_str())
-            .unwrap_or("Unknown");
+        let title = feed.map(|f| f.display_title()).unwrap_or("Unknown");
 
         match result {
             Ok(refresh) => {
@@ -406,38 +1034,38 @@ pub async fn import_opml(ctx: &AppContext, path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Parse OPML content and extract feed URLs with titles
-fn parse_opml(content: &str) -> Result<Vec<(String, String)>> {
-    let mut feeds = Vec::new();
+/// Find or create the nested folder for an OPML category path
+fn ensure_category_path(ctx: &AppContext, path: &[String]) -> Result<Option<i64>> {
+    let mut parent_id = None;
+    for name in path {
+        let category = Category::new(name.clone(), parent_id);
+        parent_id = Some(ctx.store.add_category(&category)?);
+    }
+    Ok(parent_id)
+}
 
-    // Simple regex-free parsing: find all outline elements with xmlUrl
-    for line in content.lines() {
-        if line.contains("xmlUrl") {
-            if let (Some(title), Some(url)) = (
-                extract_attr(line, "title").or_else(|| extract_attr(line, "text")),
-                extract_attr(line, "xmlUrl"),
-            ) {
-                feeds.push((title, url));
-            }
+/// Export all feeds, grouped by folder, as an OPML 2.0 document
+pub fn export_opml(ctx: &AppContext, output: Option<&Path>) -> Result<()> {
+    let feeds = ctx.store.get_all_feeds()?;
+    let categories = ctx.store.get_all_categories()?;
+    let links = ctx.store.get_feed_category_links()?;
+    let document = opml::render(&feeds, &categories, &links);
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, document)?;
+            println!("Exported {} feeds to {}", feeds.len(), path.display());
         }
+        None => print!("{}", document),
     }
 
-    Ok(feeds)
-}
-
-/// Extract an attribute value from an XML element string
-fn extract_attr(line: &str, attr: &str) -> Option<String> {
-    let pattern = format!("{}=\"", attr);
-    let start = line.find(&pattern)? + pattern.len();
-    let rest = &line[start..];
-    let end = rest.find('"')?;
-    let value = &rest[..end];
-    Some(html_escape::decode_html_entities(value).to_string())
+    Ok(())
 }
 
 /// Scrape full content for items that only have summaries
 pub async fn scrape_content(
     ctx: &AppContext,
+    base_config: &ScraperConfig,
     feed_url: Option<&str>,
     limit: usize,
     concurrency: usize,
@@ -473,30 +1101,20 @@ pub async fn scrape_content(
         concurrency
     );
 
-    // Create scraper config
-    let user_data_dir = if let Some(name) = auth_profile {
-        let profile = ctx
-            .store
-            .get_auth_profile_by_name(name)?
-            .ok_or_else(|| RivuletError::Config(format!("Auth profile not found: {}", name)))?;
-        Some(PathBuf::from(profile.profile_dir))
-    } else {
-        None
-    };
+    // A visible browser or a logged-in profile means Chrome for every page;
+    // otherwise Chrome is only used where the config routes pages to it
+    let use_chrome = visible || auth_profile.is_some();
+    let config = scrape_config(ctx, base_config, concurrency, visible, auth_profile)?;
 
-    let config = ScraperConfig {
-        headless: !visible,
-        max_concurrency: concurrency,
-        user_data_dir,
-        ..Default::default()
+    let results = if use_chrome {
+        let scraper = ChromeScraper::new(config).await?;
+        scraper.scrape_items(&items_to_scrape, concurrency).await
+    } else {
+        let mut scraper = ArticleScraper::new(config, ctx.http_fetcher.clone())?;
+        scraper.set_feeds(&ctx.store.get_all_feeds()?);
+        scraper.scrape_items(&items_to_scrape, concurrency).await
     };
 
-    // Initialize the scraper
-    let scraper = ChromeScraper::new(config).await?;
-
-    // Scrape all items
-    let results = scraper.scrape_items(&items_to_scrape, concurrency).await;
-
     let mut success = 0;
     let mut errors = 0;
 
@@ -537,6 +1155,87 @@ pub async fn scrape_content(
     Ok(())
 }
 
+/// Scrapes one page without storing it, showing which recipe and scraper
+/// were used and what was extracted.
+pub async fn scrape_test(
+    ctx: &AppContext,
+    base_config: &ScraperConfig,
+    url: &str,
+    visible: bool,
+    auth_profile: Option<&str>,
+) -> Result<()> {
+    let force_chrome = visible || auth_profile.is_some();
+    let config = scrape_config(ctx, base_config, 1, visible, auth_profile)?;
+    let scraper = ArticleScraper::new(config.clone(), ctx.http_fetcher.clone())?;
+
+    match scraper.recipe_for(url) {
+        Some(recipe) => println!("Recipe:  {} (url {})", recipe.name, recipe.url),
+        None => println!("Recipe:  none, using the [scraper] selectors"),
+    }
+    let use_chrome = force_chrome || scraper.uses_chrome(url);
+    println!(
+        "Scraper: {}",
+        if use_chrome {
+            "Chrome"
+        } else {
+            "built-in reader"
+        }
+    );
+
+    let result = if force_chrome {
+        ChromeScraper::new(config).await?.scrape(url).await?
+    } else {
+        scraper.scrape(url).await?
+    };
+    println!(
+        "Extracted {} chars of {}\n",
+        result.content.len(),
+        if result.is_html { "HTML" } else { "text" }
+    );
+
+    if result.is_html {
+        // Printed as plain text, so the colors never show.
+        let colors = ColorConfig::default();
+        for line in render_html(&result.content, Some(url), 100, &colors).lines {
+            let text: String = line
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect();
+            println!("{}", text.trim_end());
+        }
+    } else {
+        println!("{}", result.content);
+    }
+    Ok(())
+}
+
+/// The `[scraper]` config with the command-line overrides applied.
+fn scrape_config(
+    ctx: &AppContext,
+    base_config: &ScraperConfig,
+    concurrency: usize,
+    visible: bool,
+    auth_profile: Option<&str>,
+) -> Result<ScraperConfig> {
+    let user_data_dir = if let Some(name) = auth_profile {
+        let profile = ctx
+            .store
+            .get_auth_profile_by_name(name)?
+            .ok_or_else(|| RivuletError::Config(format!("Auth profile not found: {}", name)))?;
+        Some(PathBuf::from(profile.profile_dir))
+    } else {
+        None
+    };
+
+    Ok(ScraperConfig {
+        headless: !visible,
+        max_concurrency: concurrency,
+        user_data_dir,
+        ..base_config.clone()
+    })
+}
+
 pub async fn auth_add(
     ctx: &AppContext,
     name: &str,
@@ -620,8 +1319,11 @@ pub async fn auth_check(
     }
 }
 
-pub fn auth_list(ctx: &AppContext) -> Result<()> {
+pub fn auth_list(ctx: &AppContext, format: OutputFormat) -> Result<()> {
     let profiles = ctx.store.get_all_auth_profiles()?;
+    if format != OutputFormat::Text {
+        return output::print_records(format, &profiles);
+    }
     if profiles.is_empty() {
         println!("No auth profiles");
         return Ok(());
@@ -674,93 +1376,248 @@ fn sanitize_profile_name(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fetcher::FetchResult;
 
     #[test]
-    fn test_parse_opml_basic() {
-        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
-<opml version="2.0">
-  <body>
-    <outline text="Tech" title="Tech">
-      <outline title="Hacker News" text="HN" xmlUrl="https://news.ycombinator.com/rss" htmlUrl="https://news.ycombinator.com"/>
-      <outline title="Lobsters" text="Lobsters" xmlUrl="https://lobste.rs/rss" htmlUrl="https://lobste.rs"/>
-    </outline>
-  </body>
-</opml>"#;
-        let feeds = parse_opml(opml).unwrap();
-        assert_eq!(feeds.len(), 2);
-        assert_eq!(feeds[0].0, "Hacker News");
-        assert_eq!(feeds[0].1, "https://news.ycombinator.com/rss");
-        assert_eq!(feeds[1].0, "Lobsters");
-        assert_eq!(feeds[1].1, "https://lobste.rs/rss");
-    }
-
-    #[test]
-    fn test_parse_opml_text_fallback() {
-        let opml = r#"<opml version="2.0">
-  <body>
-    <outline text="My Blog" xmlUrl="https://example.com/feed.xml"/>
-  </body>
-</opml>"#;
-        let feeds = parse_opml(opml).unwrap();
-        assert_eq!(feeds.len(), 1);
-        assert_eq!(feeds[0].0, "My Blog");
-    }
-
-    #[test]
-    fn test_parse_opml_html_entities() {
-        let opml = r#"<opml version="2.0">
-  <body>
-    <outline title="Tom &amp; Jerry&apos;s Blog" xmlUrl="https://example.com/feed.xml"/>
-  </body>
-</opml>"#;
-        let feeds = parse_opml(opml).unwrap();
-        assert_eq!(feeds.len(), 1);
-        assert_eq!(feeds[0].0, "Tom & Jerry's Blog");
+    fn test_mark_items_requires_selector_and_change() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = ctx
+            .store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        ctx.store.add_item(&item).unwrap();
+        let read = StateFlags {
+            read: true,
+            ..Default::default()
+        };
+
+        assert!(mark_items(&ctx, None, None, None, None, &read).is_err());
+        assert!(mark_items(
+            &ctx,
+            Some(item.id.clone()),
+            None,
+            None,
+            None,
+            &StateFlags::default()
+        )
+        .is_err());
+        assert!(matches!(
+            mark_items(
+                &ctx,
+                None,
+                Some("https://nope.example.com/"),
+                None,
+                None,
+                &read
+            ),
+            Err(RivuletError::FeedNotFound(_))
+        ));
+
+        let marked = mark_items(
+            &ctx,
+            None,
+            Some("https://example.com/feed.xml"),
+            None,
+            None,
+            &read,
+        )
+        .unwrap();
+        assert_eq!(marked, 1);
+        assert!(ctx.store.get_item_state(&item.id).unwrap().unwrap().is_read);
     }
 
     #[test]
-    fn test_parse_opml_empty() {
-        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
-<opml version="2.0">
-  <body>
-    <outline text="Category" title="Category"/>
-  </body>
-</opml>"#;
-        let feeds = parse_opml(opml).unwrap();
-        assert!(feeds.is_empty());
-    }
+    fn test_prune_uses_feed_overrides_and_respects_dry_run() {
+        let ctx = AppContext::in_memory().unwrap();
+        let url = "https://example.com/feed.xml";
+        let feed_id = ctx.store.add_feed(&Feed::new(url.into())).unwrap();
+        let items: Vec<Item> = (0..3)
+            .map(|i| {
+                let mut item = Item::new(feed_id, url, &format!("e{i}"));
+                item.published_at = Some(Utc::now() - chrono::Duration::days(i));
+                item
+            })
+            .collect();
+        ctx.store.add_items(&items).unwrap();
+
+        let mut retention = RetentionConfig {
+            vacuum: true,
+            ..Default::default()
+        };
+        retention.feeds.insert(
+            url.to_string(),
+            crate::config::FeedRetentionConfig {
+                max_items: Some(1),
+                ..Default::default()
+            },
+        );
 
-    #[test]
-    fn test_parse_opml_no_title_no_text() {
-        let opml = r#"<opml version="2.0">
-  <body>
-    <outline xmlUrl="https://example.com/feed.xml"/>
-  </body>
-</opml>"#;
-        let feeds = parse_opml(opml).unwrap();
-        assert!(feeds.is_empty());
+        assert_eq!(prune(&ctx, &retention, true).unwrap().item_count(), 2);
+        assert_eq!(ctx.store.get_items_by_feed(feed_id).unwrap().len(), 3);
+        assert_eq!(prune(&ctx, &retention, false).unwrap().item_count(), 2);
+        let remaining = ctx.store.get_items_by_feed(feed_id).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, items[0].id);
     }
 
     #[test]
-    fn test_extract_attr_basic() {
-        let line = r#"<outline title="My Feed" xmlUrl="https://example.com/rss"/>"#;
-        assert_eq!(extract_attr(line, "title"), Some("My Feed".to_string()));
+    fn test_item_records_include_state_enclosures_and_categories() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = ctx
+            .store
+            .add_feed(&Feed::new("https://example.com/podcast.xml".into()))
+            .unwrap();
+        let mut item = Item::new(feed_id, "https://example.com/podcast.xml", "ep-1");
+        let mut enclosure = crate::domain::Enclosure::new("https://cdn.example.com/ep1.mp3".into());
+        enclosure.mime_type = Some("audio/mpeg".into());
+        enclosure.duration_secs = Some(60);
+        item.enclosures = vec![enclosure];
+        item.categories = vec!["Rust".into()];
+        ctx.store.add_item(&item).unwrap();
+        ctx.store.set_starred(&item.id, true).unwrap();
+
+        let items = ctx.store.get_items_by_filter(ItemListFilter::All).unwrap();
+        let records = item_records(&ctx, items).unwrap();
+        let json = serde_json::to_value(&records).unwrap();
+
+        assert_eq!(json[0]["id"], item.id.as_str());
+        assert_eq!(json[0]["state"]["is_starred"], true);
         assert_eq!(
-            extract_attr(line, "xmlUrl"),
-            Some("https://example.com/rss".to_string())
+            json[0]["enclosures"][0]["url"],
+            "https://cdn.example.com/ep1.mp3"
         );
+        assert_eq!(json[0]["enclosures"][0]["duration_secs"], 60);
+        assert_eq!(json[0]["categories"][0], "Rust");
     }
 
-    #[test]
-    fn test_extract_attr_missing() {
-        let line = r#"<outline title="My Feed"/>"#;
-        assert_eq!(extract_attr(line, "xmlUrl"), None);
+    #[tokio::test]
+    async fn test_export_opml_round_trips_through_import() {
+        use crate::fetcher::testing::MockFetcher;
+        use std::sync::Arc;
+
+        let source = AppContext::in_memory().unwrap();
+        let mut rust_blog = Feed::new("https://blog.rust-lang.org/feed.xml?a=1&b=2".into());
+        rust_blog.title = Some("Rust \"Blog\" <official>".into());
+        rust_blog.description = Some("Line one\nLine two & more".into());
+        let rust_blog_id = source.store.add_feed(&rust_blog).unwrap();
+        let loose = Feed::new("https://example.com/feed.xml".into());
+        source.store.add_feed(&loose).unwrap();
+
+        let tech = source
+            .store
+            .add_category(&Category::new("Tech & Science".into(), None))
+            .unwrap();
+        let rust = source
+            .store
+            .add_category(&Category::new("Rust".into(), Some(tech)))
+            .unwrap();
+        source
+            .store
+            .add_feed_to_category(rust_blog_id, rust)
+            .unwrap();
+        source
+            .store
+            .add_feed_to_category(rust_blog_id, tech)
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("feeds.opml");
+        export_opml(&source, Some(&path)).unwrap();
+
+        let mock = Arc::new(MockFetcher::new());
+        for url in [&rust_blog.url, &loose.url] {
+            mock.set_response(url.as_str(), FetchResult::NotModified { redirect: None });
+        }
+        let target = AppContext::in_memory_with_fetcher(mock).unwrap();
+        import_opml(&target, &path).await.unwrap();
+
+        let imported = target
+            .store
+            .get_feed_by_url(&rust_blog.url)
+            .unwrap()
+            .unwrap();
+        assert_eq!(imported.title, rust_blog.title);
+        assert_eq!(imported.description, rust_blog.description);
+        let imported_loose = target.store.get_feed_by_url(&loose.url).unwrap().unwrap();
+        assert_eq!(imported_loose.title, loose.title);
+        assert_eq!(imported_loose.description, None);
+
+        let categories = target.store.get_all_categories().unwrap();
+        let tech = categories
+            .iter()
+            .find(|c| c.name == "Tech & Science" && c.parent_id.is_none())
+            .unwrap();
+        let rust = categories
+            .iter()
+            .find(|c| c.name == "Rust" && c.parent_id == Some(tech.id))
+            .unwrap();
+        let mut links = target.store.get_feed_category_links().unwrap();
+        links.sort();
+        let mut expected = vec![(imported.id, tech.id), (imported.id, rust.id)];
+        expected.sort();
+        assert_eq!(links, expected);
     }
 
-    #[test]
-    fn test_extract_attr_html_entities() {
-        let line = r#"<outline title="A &amp; B"/>"#;
-        assert_eq!(extract_attr(line, "title"), Some("A & B".to_string()));
+    #[tokio::test]
+    async fn test_add_feed_discovers_feed_from_site_url() {
+        use crate::fetcher::testing::MockFetcher;
+        use std::sync::Arc;
+
+        let page = |body: &str| FetchResult::Content {
+            status: 200,
+            body: body.as_bytes().to_vec(),
+            etag: None,
+            last_modified: None,
+            max_age_secs: None,
+            redirect: None,
+        };
+        let mock = Arc::new(MockFetcher::new());
+        mock.set_response(
+            "https://example.com/",
+            page(r#"<html><head><link rel="alternate" type="application/rss+xml" href="/posts.rss"></head></html>"#),
+        );
+        mock.set_response(
+            "https://example.com/posts.rss",
+            page(
+                r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Posts</title>
+<item><title>Hello</title><guid>hello</guid></item></channel></rss>"#,
+            ),
+        );
+        mock.set_response("https://example.org/", page("<html><head></head></html>"));
+        mock.set_response(
+            "https://example.net/",
+            page(r#"<html><head><link rel="alternate" type="application/rss+xml" href="/feed"></head></html>"#),
+        );
+        mock.set_response("https://example.net/feed", page("<html>Not a feed</html>"));
+        let rule: crate::config::RuleConfig =
+            toml::from_str("feed = \"Posts\"\ntitle = \"^hello\"\nactions = [\"star\"]").unwrap();
+        let ctx = AppContext::in_memory_with_fetcher(mock)
+            .unwrap()
+            .with_rules(RuleSet::compile(&[rule]).unwrap());
+
+        add_feed(&ctx, "https://example.com/").await.unwrap();
+        let feeds = ctx.store.get_all_feeds().unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].url, "https://example.com/posts.rss");
+        assert_eq!(feeds[0].title.as_deref(), Some("Posts"));
+        let items = ctx.store.get_items_by_feed(feeds[0].id).unwrap();
+        assert_eq!(items.len(), 1);
+        // The first fetch goes through [[rules]] like any refresh.
+        let state = ctx.store.get_item_state(&items[0].id).unwrap().unwrap();
+        assert!(state.is_starred);
+
+        // Adding the site again finds the existing subscription.
+        add_feed(&ctx, "https://example.com/").await.unwrap();
+        assert_eq!(ctx.store.get_all_feeds().unwrap().len(), 1);
+
+        // A page with no feeds anywhere is an error and leaves no feed row.
+        assert!(add_feed(&ctx, "https://example.org/").await.is_err());
+        assert_eq!(ctx.store.get_all_feeds().unwrap().len(), 1);
+
+        // So does a candidate that turns out not to be a feed.
+        assert!(add_feed(&ctx, "https://example.net/").await.is_err());
+        assert_eq!(ctx.store.get_all_feeds().unwrap().len(), 1);
     }
 
     #[test]
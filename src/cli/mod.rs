@@ -1,6 +1,7 @@
 pub mod commands;
+pub mod output;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 use crate::fetcher::parallel::DEFAULT_WORKERS;
 
@@ -16,6 +17,74 @@ pub struct Cli {
     pub command: Commands,
 }
 
+/// How list commands print their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// A JSON array, including item state, enclosures, and categories
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Tab-separated values with a header row
+    Tsv,
+}
+
+/// State changes for `rivulet mark`. Opposite flags cannot be combined.
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct StateFlags {
+    /// Mark as read
+    #[arg(long, conflicts_with = "unread")]
+    pub read: bool,
+
+    /// Mark as unread
+    #[arg(long)]
+    pub unread: bool,
+
+    /// Star
+    #[arg(long, conflicts_with = "unstar")]
+    pub star: bool,
+
+    /// Remove the star
+    #[arg(long)]
+    pub unstar: bool,
+
+    /// Add to the read-later queue
+    #[arg(long, conflicts_with = "unqueue")]
+    pub queue: bool,
+
+    /// Remove from the read-later queue
+    #[arg(long)]
+    pub unqueue: bool,
+
+    /// Mark as saved
+    #[arg(long, conflicts_with = "unsave")]
+    pub save: bool,
+
+    /// Remove from saved
+    #[arg(long)]
+    pub unsave: bool,
+
+    /// Archive
+    #[arg(long, conflicts_with = "unarchive")]
+    pub archive: bool,
+
+    /// Move out of the archive
+    #[arg(long)]
+    pub unarchive: bool,
+
+    /// Hide from every view and count
+    #[arg(long, conflicts_with = "unhide")]
+    pub hide: bool,
+
+    /// Show a hidden item again
+    #[arg(long)]
+    pub unhide: bool,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize config file with all options
@@ -26,7 +95,7 @@ pub enum Commands {
     },
     /// Add a new feed
     Add {
-        /// URL of the feed to add
+        /// Feed URL, or a site URL to discover the feed from
         url: String,
     },
     /// Remove a feed
@@ -39,8 +108,24 @@ pub enum Commands {
         /// Path to the OPML file
         path: std::path::PathBuf,
     },
+    /// Export all feeds (with folders) as OPML 2.0
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
     /// Update all feeds
     Update,
+    /// Report broken, redirected and stale feeds
+    Health {
+        /// Show the recent fetch log for this feed URL instead
+        #[arg(long)]
+        feed: Option<String>,
+
+        /// Consider a feed stale when its newest item is older than this many days
+        #[arg(long, default_value = "90")]
+        stale_days: i64,
+    },
     /// List feeds or items
     List {
         /// Show items instead of feeds
@@ -66,6 +151,14 @@ pub enum Commands {
         /// Show archived items
         #[arg(long)]
         archived: bool,
+
+        /// Show the items of this saved search; a filter flag replaces its saved filter
+        #[arg(long, value_name = "NAME")]
+        search_feed: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
     /// Search locally indexed article titles, summaries, and scraped content
     Search {
@@ -95,6 +188,51 @@ pub enum Commands {
         /// Search archived items
         #[arg(long)]
         archived: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Change read/star/queue/save/archive/hidden state for one or many items
+    Mark {
+        /// Item ID, as printed by `list --items --format json`
+        item_id: Option<String>,
+
+        /// Only items from this feed URL
+        #[arg(long)]
+        feed: Option<String>,
+
+        /// Only items matching this search query
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Only items published longer ago than this (e.g. 30d, 12h)
+        #[arg(long)]
+        older_than: Option<String>,
+
+        #[command(flatten)]
+        state: StateFlags,
+    },
+    /// Delete items outside the `[retention]` policy in config.toml
+    Prune {
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Add, remove, and list item tags
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    /// Save search queries as smart feeds
+    SearchFeed {
+        #[command(subcommand)]
+        action: SearchFeedAction,
+    },
+    /// Work with the `[[rules]]` in config.toml
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
     },
     /// Launch the TUI
     Tui,
@@ -103,7 +241,7 @@ pub enum Commands {
         #[command(subcommand)]
         action: DaemonAction,
     },
-    /// Scrape full content for items using headless Chrome
+    /// Scrape full article content for items that only have a title or summary
     Scrape {
         /// Only scrape items from a specific feed URL
         #[arg(long)]
@@ -113,17 +251,22 @@ pub enum Commands {
         #[arg(short, long, default_value = "10")]
         limit: usize,
 
-        /// Number of concurrent browser pages
+        /// Number of pages fetched at once
         #[arg(short, long, default_value = "3")]
         concurrency: usize,
 
-        /// Run in non-headless mode (show browser)
+        /// Scrape with Chrome in non-headless mode (show browser)
         #[arg(long)]
         visible: bool,
 
-        /// Use a stored authenticated Chrome profile
+        /// Scrape with Chrome using a stored authenticated profile
         #[arg(long)]
         auth_profile: Option<String>,
+
+        /// Scrape one page without saving it, showing the recipe used and
+        /// the extracted article
+        #[arg(long, value_name = "URL", conflicts_with_all = ["feed", "limit", "concurrency"])]
+        test: Option<String>,
     },
     /// Manage authenticated Chrome profiles for paid/private sites
     Auth {
@@ -136,7 +279,8 @@ pub enum Commands {
 pub enum DaemonAction {
     /// Start the background daemon
     Start {
-        /// Update interval (e.g., "1h", "30m", "6h", "1d")
+        /// Longest wait between schedule checks (e.g., "1h", "30m", "6h", "1d");
+        /// each feed is fetched when its own adaptive interval is due
         #[arg(short, long, default_value = "1h")]
         interval: String,
 
@@ -158,6 +302,90 @@ pub enum DaemonAction {
     Status,
 }
 
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// Add tags to an item
+    Add {
+        /// Item ID, as printed by `list --items --format json`
+        item_id: String,
+
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from an item
+    Remove {
+        /// Item ID
+        item_id: String,
+
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// List tags with their item counts, or the items carrying one tag
+    List {
+        /// Show the items with this tag instead
+        tag: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SearchFeedAction {
+    /// Save a query under a name, replacing any saved search with that name
+    Add {
+        /// Name shown in the feed rail
+        name: String,
+
+        /// Search query, as for `rivulet search`
+        query: String,
+
+        /// Only unread items
+        #[arg(long)]
+        unread: bool,
+
+        /// Only starred items
+        #[arg(long)]
+        starred: bool,
+
+        /// Only queued/read-later items
+        #[arg(long)]
+        queued: bool,
+
+        /// Only saved items
+        #[arg(long)]
+        saved: bool,
+
+        /// Only archived items
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Delete a saved search
+    Remove {
+        /// Saved search name
+        name: String,
+    },
+    /// List saved searches with their unread counts
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// Show which stored items each rule would match, without acting on them
+    Test {
+        /// Only test items from this feed URL
+        #[arg(long)]
+        feed: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum AuthAction {
     /// Create/update a profile and open a visible Chrome login session
@@ -187,5 +415,9 @@ pub enum AuthAction {
         visible: bool,
     },
     /// List configured auth profiles
-    List,
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
 }
@@ -0,0 +1,186 @@
+//! Machine-readable output shared by the listing commands.
+
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::app::Result;
+use crate::cli::OutputFormat;
+
+/// Writes `records` to stdout. Human-readable text is the caller's job;
+/// `Text` falls back to JSON here.
+pub fn print_records<T: Serialize>(format: OutputFormat, records: &[T]) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    write_records(&mut out, format, records)?;
+    out.flush()?;
+    Ok(())
+}
+
+pub fn write_records<W: Write, T: Serialize>(
+    out: &mut W,
+    format: OutputFormat,
+    records: &[T],
+) -> Result<()> {
+    match format {
+        OutputFormat::Text | OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Jsonl => {
+            for record in records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let rows = records
+                .iter()
+                .map(|record| {
+                    let mut row = Vec::new();
+                    flatten("", serde_json::to_value(record)?, &mut row);
+                    Ok(row)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let Some(first) = rows.first() else {
+                return Ok(());
+            };
+
+            let (separator, escape): (&str, fn(&str) -> String) = if format == OutputFormat::Csv {
+                (",", csv_field)
+            } else {
+                ("\t", tsv_field)
+            };
+            let header: Vec<String> = first.iter().map(|(key, _)| escape(key)).collect();
+            writeln!(out, "{}", header.join(separator))?;
+            for row in &rows {
+                let fields: Vec<String> = row.iter().map(|(_, value)| escape(value)).collect();
+                writeln!(out, "{}", fields.join(separator))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Nested objects become dotted columns (`state.is_read`); arrays are kept
+/// as compact JSON so enclosures survive the round trip.
+fn flatten(prefix: &str, value: Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => flatten_object(prefix, map, row),
+        Value::Null => row.push((prefix.to_string(), String::new())),
+        Value::String(s) => row.push((prefix.to_string(), s)),
+        other => row.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn flatten_object(prefix: &str, map: Map<String, Value>, row: &mut Vec<(String, String)>) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        flatten(&key, value, row);
+    }
+}
+
+/// RFC 4180 quoting.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// TSV has no quoting, so tabs and line breaks are backslash-escaped.
+fn tsv_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        title: &'static str,
+        note: Option<&'static str>,
+        state: State,
+        tags: Vec<&'static str>,
+    }
+
+    #[derive(Serialize)]
+    struct State {
+        is_read: bool,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                title: "Hello, \"world\"",
+                note: None,
+                state: State { is_read: true },
+                tags: vec!["a", "b"],
+            },
+            Row {
+                title: "Tab\there",
+                note: Some("line\nbreak"),
+                state: State { is_read: false },
+                tags: vec![],
+            },
+        ]
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_records(&mut out, format, &rows()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_flattens_and_quotes() {
+        assert_eq!(
+            render(OutputFormat::Csv),
+            "title,note,state.is_read,tags\n\
+             \"Hello, \"\"world\"\"\",,true,\"[\"\"a\"\",\"\"b\"\"]\"\n\
+             Tab\there,\"line\nbreak\",false,[]\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_escapes_separators() {
+        assert_eq!(
+            render(OutputFormat::Tsv),
+            "title\tnote\tstate.is_read\ttags\n\
+             Hello, \"world\"\t\ttrue\t[\"a\",\"b\"]\n\
+             Tab\\there\tline\\nbreak\tfalse\t[]\n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_writes_one_object_per_line() {
+        let output = render(OutputFormat::Jsonl);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["state"]["is_read"], true);
+    }
+
+    #[test]
+    fn test_empty_records() {
+        let empty: Vec<Row> = Vec::new();
+        let mut out = Vec::new();
+        write_records(&mut out, OutputFormat::Json, &empty).unwrap();
+        assert_eq!(out, b"[]\n");
+
+        let mut out = Vec::new();
+        write_records(&mut out, OutputFormat::Csv, &empty).unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -33,6 +33,20 @@ pub struct ColorConfig {
     pub status_fg: Color,
     #[serde(deserialize_with = "deserialize_color")]
     pub status_bg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub diff_removed: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub diff_added: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub search_match_fg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub search_match_bg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub article_code: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub article_link: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub article_muted: Color,
 }
 
 impl Default for ColorConfig {
@@ -51,6 +65,13 @@ impl Default for ColorConfig {
             metadata_link: Color::Blue,
             status_fg: Color::White,
             status_bg: Color::DarkGray,
+            diff_removed: Color::Red,
+            diff_added: Color::Green,
+            search_match_fg: Color::Black,
+            search_match_bg: Color::Yellow,
+            article_code: Color::LightYellow,
+            article_link: Color::Cyan,
+            article_muted: Color::DarkGray,
         }
     }
 }
@@ -35,10 +35,20 @@ pub struct KeybindingConfig {
     pub view_latest: Vec<String>,
     pub view_reader: Vec<String>,
     pub open_in_browser: Vec<String>,
+    pub follow_link: Vec<String>,
     pub refresh: Vec<String>,
     pub toggle_maximize: Vec<String>,
     pub toggle_feed_panel: Vec<String>,
     pub delete_feed: Vec<String>,
+    pub mark_all_read: Vec<String>,
+    pub mark_older_read: Vec<String>,
+    pub toggle_diff: Vec<String>,
+    pub edit_tags: Vec<String>,
+    pub filter_tag: Vec<String>,
+    pub search: Vec<String>,
+    /// Only while a search is open; otherwise the keys keep their usual action
+    pub next_match: Vec<String>,
+    pub prev_match: Vec<String>,
     pub window_chord: Vec<String>,
 }
 
@@ -71,10 +81,19 @@ impl Default for KeybindingConfig {
             view_latest: vec!["Alt+1".to_string(), "[".to_string()],
             view_reader: vec!["Alt+2".to_string(), "]".to_string()],
             open_in_browser: vec!["o".to_string()],
+            follow_link: vec!["F".to_string()],
             refresh: vec!["R".to_string()],
             toggle_maximize: vec!["m".to_string()],
             toggle_feed_panel: vec!["\\".to_string()],
             delete_feed: vec!["d".to_string(), "Delete".to_string()],
+            mark_all_read: vec!["A".to_string()],
+            mark_older_read: vec!["O".to_string()],
+            toggle_diff: vec!["D".to_string()],
+            edit_tags: vec!["t".to_string()],
+            filter_tag: vec!["T".to_string()],
+            search: vec!["/".to_string()],
+            next_match: vec!["n".to_string()],
+            prev_match: vec!["N".to_string()],
             window_chord: vec!["Ctrl+w".to_string()],
         }
     }
@@ -135,6 +154,8 @@ impl KeybindingConfig {
             Action::ViewReader
         } else if self.matches_key(key, &self.open_in_browser) {
             Action::OpenInBrowser
+        } else if self.matches_key(key, &self.follow_link) {
+            Action::FollowLink
         } else if self.matches_key(key, &self.refresh) {
             Action::Refresh
         } else if self.matches_key(key, &self.toggle_maximize) {
@@ -143,6 +164,18 @@ impl KeybindingConfig {
             Action::ToggleFeedPanel
         } else if self.matches_key(key, &self.delete_feed) {
             Action::DeleteFeed
+        } else if self.matches_key(key, &self.mark_all_read) {
+            Action::MarkAllRead
+        } else if self.matches_key(key, &self.mark_older_read) {
+            Action::MarkOlderRead
+        } else if self.matches_key(key, &self.toggle_diff) {
+            Action::ToggleDiff
+        } else if self.matches_key(key, &self.edit_tags) {
+            Action::EditTags
+        } else if self.matches_key(key, &self.filter_tag) {
+            Action::FilterTag
+        } else if self.matches_key(key, &self.search) {
+            Action::Search
         } else if self.matches_key(key, &self.window_chord) {
             Action::WindowChord
         } else {
@@ -150,6 +183,18 @@ impl KeybindingConfig {
         }
     }
 
+    /// Match navigation, which takes precedence over `get_action` while
+    /// search results are shown.
+    pub fn get_search_action(&self, key: &KeyEvent) -> Option<Action> {
+        if self.matches_key(key, &self.next_match) {
+            Some(Action::NextMatch)
+        } else if self.matches_key(key, &self.prev_match) {
+            Some(Action::PrevMatch)
+        } else {
+            None
+        }
+    }
+
     fn matches_key(&self, key: &KeyEvent, bindings: &[String]) -> bool {
         bindings.iter().any(|binding| {
             if let Ok(parsed) = parse_key_string(binding) {
@@ -360,6 +405,34 @@ mod tests {
         let key = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::NONE);
         assert_eq!(config.get_action(&key), Action::ToggleQueued);
 
+        let key = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_action(&key), Action::MarkAllRead);
+
+        let key = KeyEvent::new(KeyCode::Char('O'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_action(&key), Action::MarkOlderRead);
+
+        let key = KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_action(&key), Action::ToggleDiff);
+
+        let key = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE);
+        assert_eq!(config.get_action(&key), Action::EditTags);
+
+        let key = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_action(&key), Action::FilterTag);
+
+        let key = KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE);
+        assert_eq!(config.get_action(&key), Action::Search);
+
+        let key = KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_action(&key), Action::FollowLink);
+
+        let key = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(config.get_action(&key), Action::NextPage);
+        assert_eq!(config.get_search_action(&key), Some(Action::NextMatch));
+
+        let key = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        assert_eq!(config.get_search_action(&key), Some(Action::PrevMatch));
+
         let key = KeyEvent::new(KeyCode::Char('S'), KeyModifiers::NONE);
         assert_eq!(config.get_action(&key), Action::ToggleSaved);
 
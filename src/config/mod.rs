@@ -9,11 +9,15 @@ pub mod keybindings;
 pub use colors::ColorConfig;
 pub use keybindings::KeybindingConfig;
 
-use crate::scraper::ScraperConfig;
+use crate::domain::Feed;
+use crate::rules::{RuleAction, RuleSet};
+use crate::scraper::{Recipe, ScraperConfig};
+use crate::store::{RetentionLimits, RetentionPolicy};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main configuration struct.
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -23,14 +27,26 @@ pub struct Config {
     pub keybindings: KeybindingConfig,
     pub scraper: ScraperConfig,
     pub ui: UiConfig,
+    pub retention: RetentionConfig,
+    pub rules: Vec<RuleConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct UiConfig {
+    pub show_updates: bool,
     pub latest: LatestConfig,
 }
 
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            show_updates: true,
+            latest: LatestConfig::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct LatestConfig {
@@ -47,6 +63,94 @@ impl Default for LatestConfig {
     }
 }
 
+/// `[retention]`: how long items are kept. `0` means unlimited.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub max_age_days: u32,
+    pub max_items: usize,
+    pub refresh_run_days: u32,
+    pub tombstone_days: u32,
+    pub vacuum: bool,
+    /// Per-feed overrides keyed by feed URL
+    pub feeds: HashMap<String, FeedRetentionConfig>,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: 0,
+            max_items: 0,
+            refresh_run_days: 30,
+            tombstone_days: 365,
+            vacuum: false,
+            feeds: HashMap::new(),
+        }
+    }
+}
+
+/// `[retention.feeds."URL"]`: unset fields fall back to the global limits.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FeedRetentionConfig {
+    pub max_age_days: Option<u32>,
+    pub max_items: Option<usize>,
+}
+
+impl RetentionConfig {
+    /// Resolves per-feed overrides against the subscribed feeds. Overrides
+    /// for URLs that aren't subscribed are ignored.
+    pub fn policy(&self, feeds: &[Feed]) -> RetentionPolicy {
+        let default = RetentionLimits {
+            max_age_days: nonzero(self.max_age_days),
+            max_items: nonzero(self.max_items),
+        };
+        let feeds = feeds
+            .iter()
+            .filter_map(|feed| {
+                let limits = self.feeds.get(&feed.url)?;
+                Some((
+                    feed.id,
+                    RetentionLimits {
+                        max_age_days: limits.max_age_days.map_or(default.max_age_days, nonzero),
+                        max_items: limits.max_items.map_or(default.max_items, nonzero),
+                    },
+                ))
+            })
+            .collect();
+
+        RetentionPolicy {
+            default,
+            feeds,
+            refresh_run_days: nonzero(self.refresh_run_days),
+            tombstone_days: nonzero(self.tombstone_days),
+        }
+    }
+}
+
+/// `[[rules]]`: every matcher that is set must match a new item for the
+/// actions to run. Patterns are case-insensitive regexes.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RuleConfig {
+    pub name: String,
+    /// Feed URL or title
+    pub feed: Option<String>,
+    pub title: Option<String>,
+    /// Matched against the text of the content and summary
+    pub content: Option<String>,
+    pub author: Option<String>,
+    /// Search query, as for `rivulet search`
+    pub search: Option<String>,
+    pub actions: Vec<RuleAction>,
+    /// Tags added by the `tag` action
+    pub tags: Vec<String>,
+}
+
+fn nonzero<T: Default + PartialEq>(value: T) -> Option<T> {
+    (value != T::default()).then_some(value)
+}
+
 impl Config {
     /// Load configuration from the default path.
     ///
@@ -56,25 +160,77 @@ impl Config {
     pub fn load() -> Result<Self, ConfigError> {
         let config_path = Self::default_config_path()?;
 
-        if !config_path.exists() {
+        let mut config = if config_path.exists() {
+            let content = fs::read_to_string(&config_path).map_err(|e| ConfigError::Io {
+                path: config_path.clone(),
+                source: e,
+            })?;
+
+            toml::from_str(&content).map_err(|e| ConfigError::Parse {
+                path: config_path.clone(),
+                source: e,
+            })?
+        } else {
             // Create default config with comments
             Self::create_default_config(&config_path)?;
-            return Ok(Self::default());
-        }
-
-        let content = fs::read_to_string(&config_path).map_err(|e| ConfigError::Io {
-            path: config_path.clone(),
-            source: e,
-        })?;
+            Self::default()
+        };
 
-        let config: Config = toml::from_str(&content).map_err(|e| ConfigError::Parse {
-            path: config_path,
-            source: e,
-        })?;
+        let recipes_dir = config_path.with_file_name("recipes");
+        config
+            .scraper
+            .recipes
+            .extend(Self::load_recipes(&recipes_dir)?);
 
         Ok(config)
     }
 
+    /// Reads the `*.toml` files in `dir`, one recipe each, in file name
+    /// order. A recipe without a `name` is named after its file. A missing
+    /// directory has no recipes.
+    pub fn load_recipes(dir: &Path) -> Result<Vec<Recipe>, ConfigError> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let io_error = |source| ConfigError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut paths = fs::read_dir(dir)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_error)?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let content = fs::read_to_string(&path).map_err(|e| ConfigError::Io {
+                    path: path.clone(),
+                    source: e,
+                })?;
+                let mut recipe: Recipe =
+                    toml::from_str(&content).map_err(|e| ConfigError::Parse {
+                        path: path.clone(),
+                        source: e,
+                    })?;
+                if recipe.name.trim().is_empty() {
+                    if let Some(stem) = path.file_stem() {
+                        recipe.name = stem.to_string_lossy().into_owned();
+                    }
+                }
+                Ok(recipe)
+            })
+            .collect()
+    }
+
+    /// Compiles `[[rules]]`, failing on the first invalid rule.
+    pub fn rule_set(&self) -> Result<RuleSet, ConfigError> {
+        RuleSet::compile(&self.rules)
+    }
+
     /// Get the default config file path: `~/.config/rivulet/config.toml`
     pub fn default_config_path() -> Result<PathBuf, ConfigError> {
         let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
@@ -147,6 +303,19 @@ metadata_link = "Blue"
 status_fg = "White"
 status_bg = "DarkGray"
 
+# Revision diffs in preview
+diff_removed = "Red"
+diff_added = "Green"
+
+# Search matches in preview
+search_match_fg = "Black"
+search_match_bg = "Yellow"
+
+# Article text in preview: code, links, and footnotes, rules and quote bars
+article_code = "LightYellow"
+article_link = "Cyan"
+article_muted = "DarkGray"
+
 [keybindings]
 # Navigation
 quit = ["q", "Ctrl+c"]
@@ -180,10 +349,33 @@ view_archived = ["X"]
 view_latest = ["Alt+1", "["]
 view_reader = ["Alt+2", "]"]
 open_in_browser = ["o"]
+# Pick one of the article's links to open (o), copy (y) or subscribe to (a)
+follow_link = ["F"]
 refresh = ["R"]
 toggle_maximize = ["m"]
 toggle_feed_panel = ["\\"]
 delete_feed = ["d", "Delete"]
+# Bulk mark-read (asks y/n): everything visible / items below the cursor
+mark_all_read = ["A"]
+mark_older_read = ["O"]
+
+# Preview: show what changed in an updated item
+toggle_diff = ["D"]
+
+# Tags: edit the selected item's tags / show only items with a tag
+edit_tags = ["t"]
+filter_tag = ["T"]
+
+# Search as you type; while results are shown, next_match / prev_match jump
+# between matches in the preview instead of paging
+search = ["/"]
+next_match = ["n"]
+prev_match = ["N"]
+
+[ui]
+# Flag items whose feed entry changed after you read them (U) and keep the
+# changes available with D.
+show_updates = true
 
 [ui.latest]
 # Number of days included in the Latest tab.
@@ -192,6 +384,30 @@ days = 7
 # Maximum non-new items shown below the latest refresh batch.
 limit = 200
 
+[retention]
+# Items older than this many days are pruned by `rivulet prune` and after
+# each daemon refresh. Starred, saved and queued items are always kept.
+# 0 keeps items forever.
+max_age_days = 0
+
+# Newest items kept per feed (0 = unlimited).
+max_items = 0
+
+# Refresh history older than this many days is dropped (0 = keep forever).
+refresh_run_days = 30
+
+# Pruned items are remembered this many days so a refresh doesn't re-add
+# them while the feed still lists them (0 = remember forever).
+tombstone_days = 365
+
+# Compact the database file after pruning.
+vacuum = false
+
+# Per-feed overrides; 0 disables that limit for the feed.
+# [retention.feeds."https://example.com/feed.xml"]
+# max_age_days = 30
+# max_items = 100
+
 [scraper]
 # Run browser in headless mode (no visible window)
 headless = true
@@ -202,7 +418,7 @@ timeout_secs = 30
 # Wait time after page load for dynamic content (milliseconds)
 wait_after_load_ms = 1000
 
-# Maximum concurrent browser pages
+# Maximum pages scraped at once
 max_concurrency = 5
 
 # Block images for faster loading
@@ -241,10 +457,59 @@ remove_selectors = [
     "style",
 ]
 
+# Articles split over several pages are followed through the first of
+# these selectors that matches, or the page's <link rel="next">, and
+# stitched together, up to max_pages pages (1 scrapes only the linked page).
+# Next pages must be on the same host.
+# next_page_selectors = [".pagination .next", "a.continue-reading"]
+max_pages = 5
+
+# Pages are fetched over HTTP and the article is extracted without a browser.
+# Sites that build their pages with JavaScript can be scraped with Chrome
+# instead: list their domains (subdomains included) or their feeds (URL or
+# title). The headless, wait, block and user_data_dir options apply to Chrome.
+# chrome_domains = ["app.example.com"]
+# chrome_feeds = ["Example Weekly"]
+
 # Optional: Persistent Chrome profile directory used for authenticated scraping.
 # Prefer `rivulet auth add <name> --site <url>` and `rivulet scrape --auth-profile <name>`
 # for named paid/private-site sessions.
 # user_data_dir = "/Users/you/Library/Application Support/rivulet/auth-profiles/example"
+
+# Per-site recipes, matched by a case-insensitive regex on the page URL; the
+# first match wins. `content` and `next_page` are tried before
+# content_selectors and next_page_selectors, and `remove` adds to
+# remove_selectors. `wait_for` (a selector to wait for) and `click`
+# (consent buttons to press) need Chrome; `javascript = true` scrapes the
+# site with Chrome and `false` with the built-in reader. Recipes can also be
+# kept one per file in the `recipes` directory next to this file, e.g.
+# recipes/example-news.toml with the same keys. Try one with
+# `rivulet scrape --test URL`.
+# [[scraper.recipes]]
+# name = "Example News"
+# url = '^https://(www\.)?example\.com/news/'
+# content = ".story-body"
+# remove = [".newsletter-signup"]
+# next_page = "a.next-page"
+# wait_for = ".story-body p"
+# click = ["#accept-cookies"]
+# javascript = true
+
+# Rules act on new items as they arrive; `rivulet rules test` shows what they
+# would match among stored items. Every matcher that is set must match:
+# feed (URL or title), title / content / author (case-insensitive regexes)
+# and search (a query, as for rivulet search). Actions: mark-read, star,
+# queue, save, archive, hide, tag (adds each name in `tags`).
+# [[rules]]
+# name = "Sponsored posts"
+# title = '^(sponsored|promoted)\b'
+# actions = ["mark-read", "hide"]
+#
+# [[rules]]
+# name = "Mentions of our product"
+# search = "acme"
+# actions = ["queue", "tag"]
+# tags = ["acme"]
 "##
         .to_string()
     }
@@ -267,6 +532,15 @@ pub enum ConfigError {
         path: PathBuf,
         source: toml::de::Error,
     },
+
+    #[error("Invalid rule \"{rule}\": {message}")]
+    InvalidRule { rule: String, message: String },
+
+    #[error("Invalid recipe \"{recipe}\": {message}")]
+    InvalidRecipe { recipe: String, message: String },
+
+    #[error("Invalid [scraper] {setting}: {message}")]
+    InvalidSelector { setting: String, message: String },
 }
 
 #[cfg(test)]
@@ -283,6 +557,104 @@ mod tests {
         assert_eq!(config.keybindings.quit, vec!["q", "Ctrl+c"]);
     }
 
+    #[test]
+    fn test_retention_policy_resolves_feed_overrides() {
+        let content = r##"
+[retention]
+max_age_days = 90
+
+[retention.feeds."https://a.example/feed.xml"]
+max_items = 20
+
+[retention.feeds."https://b.example/feed.xml"]
+max_age_days = 0
+"##;
+        let config: Config = toml::from_str(content).unwrap();
+        let mut a = Feed::new("https://a.example/feed.xml".into());
+        a.id = 1;
+        let mut b = Feed::new("https://b.example/feed.xml".into());
+        b.id = 2;
+        let policy = config.retention.policy(&[a, b]);
+
+        assert_eq!(policy.default.max_age_days, Some(90));
+        assert_eq!(policy.default.max_items, None);
+        assert_eq!(policy.limits_for(1).max_age_days, Some(90));
+        assert_eq!(policy.limits_for(1).max_items, Some(20));
+        assert!(policy.limits_for(2).is_unlimited());
+        assert_eq!(policy.limits_for(3), policy.default);
+        assert_eq!(policy.refresh_run_days, Some(30));
+        assert_eq!(policy.tombstone_days, Some(365));
+    }
+
+    #[test]
+    fn test_rules_parse_and_compile() {
+        let content = r##"
+[[rules]]
+name = "Sponsored"
+title = '^sponsored\b'
+actions = ["mark-read", "hide"]
+
+[[rules]]
+search = "acme"
+actions = ["queue", "tag"]
+tags = ["acme"]
+"##;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(
+            config.rules[0].actions,
+            vec![RuleAction::MarkRead, RuleAction::Hide]
+        );
+
+        let rules = config.rule_set().unwrap();
+        assert_eq!(rules.rules()[0].name, "Sponsored");
+        assert_eq!(rules.rules()[1].name, "rule 2");
+        assert_eq!(rules.rules()[1].describe_actions(), "queue, tag acme");
+
+        let bad: Config = toml::from_str("[[rules]]\nactions = [\"star\"]").unwrap();
+        assert!(matches!(
+            bad.rule_set(),
+            Err(ConfigError::InvalidRule { .. })
+        ));
+    }
+
+    #[test]
+    fn test_recipes_from_config_and_directory() {
+        let content = r##"
+[[scraper.recipes]]
+name = "Example news"
+url = 'example\.com/news/'
+content = ".story"
+click = ["#accept-cookies"]
+"##;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.scraper.recipes.len(), 1);
+        assert_eq!(config.scraper.recipes[0].click, vec!["#accept-cookies"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("b-spa.toml"),
+            "name = \"SPA\"\nurl = 'app\\.test'\njavascript = true\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("a-blog.toml"), "url = 'blog\\.test'\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a recipe").unwrap();
+
+        let recipes = Config::load_recipes(dir.path()).unwrap();
+        let names: Vec<_> = recipes.iter().map(|recipe| recipe.name.as_str()).collect();
+        assert_eq!(names, vec!["a-blog", "SPA"]);
+        assert_eq!(recipes[1].javascript, Some(true));
+        assert!(Config::load_recipes(&dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        fs::write(dir.path().join("c-bad.toml"), "url = [").unwrap();
+        assert!(matches!(
+            Config::load_recipes(dir.path()),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+
     #[test]
     fn test_partial_config() {
         let content = r##"
@@ -10,21 +10,24 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::{Local, Utc};
-use tokio::time::interval;
+use chrono::{DateTime, Local, Utc};
 
 use crate::app::AppContext;
+use crate::config::RetentionConfig;
 use crate::store::{RefreshSource, Store};
 
 /// Daemon configuration
 #[derive(Debug, Clone)]
 pub struct DaemonConfig {
-    /// Update interval in seconds (default: 3600 = 1 hour)
+    /// Longest sleep between schedule checks in seconds (default: 3600 = 1 hour).
+    /// Each feed is fetched on its own adaptive schedule; see `fetcher::schedule`.
     pub update_interval_secs: u64,
     /// Whether to run an update immediately on start
     pub update_on_start: bool,
     /// Log file path (None = stdout)
     pub log_file: Option<PathBuf>,
+    /// Retention policy applied after each refresh
+    pub retention: RetentionConfig,
 }
 
 impl Default for DaemonConfig {
@@ -33,6 +36,7 @@ impl Default for DaemonConfig {
             update_interval_secs: 3600, // 1 hour
             update_on_start: true,
             log_file: None,
+            retention: RetentionConfig::default(),
         }
     }
 }
@@ -80,6 +84,24 @@ impl DaemonConfig {
     }
 }
 
+/// Never wake more often than this, even if a feed is overdue.
+const MIN_WAKE_SECS: i64 = 60;
+
+/// How long to sleep before the next schedule check: until the earliest feed
+/// is due, capped at `max_secs`.
+fn sleep_until_next_due(
+    now: DateTime<Utc>,
+    next_due: Option<DateTime<Utc>>,
+    max_secs: u64,
+) -> Duration {
+    let max_secs = i64::try_from(max_secs).unwrap_or(i64::MAX);
+    let secs = next_due
+        .map(|due| (due - now).num_seconds())
+        .unwrap_or(max_secs)
+        .clamp(MIN_WAKE_SECS.min(max_secs), max_secs);
+    Duration::from_secs(secs as u64)
+}
+
 /// Daemon runner
 pub struct Daemon {
     ctx: Arc<AppContext>,
@@ -221,7 +243,7 @@ impl Daemon {
         }
 
         self.log(&format!(
-            "Rivulet daemon started (update interval: {}, PID: {})",
+            "Rivulet daemon started (max check interval: {}, PID: {})",
             DaemonConfig::format_interval(self.config.update_interval_secs),
             std::process::id()
         ));
@@ -232,12 +254,18 @@ impl Daemon {
             self.run_update().await;
         }
 
-        // Main loop
-        let mut timer = interval(Duration::from_secs(self.config.update_interval_secs));
-        timer.tick().await; // Skip the first immediate tick
-
+        // Main loop: sleep until the next feed is due
         while self.running.load(Ordering::SeqCst) {
-            timer.tick().await;
+            let next_due = self.ctx.store.get_next_due_at().unwrap_or_else(|e| {
+                self.log(&format!("Failed to read feed schedules: {}", e));
+                None
+            });
+            tokio::time::sleep(sleep_until_next_due(
+                Utc::now(),
+                next_due,
+                self.config.update_interval_secs,
+            ))
+            .await;
 
             if !self.running.load(Ordering::SeqCst) {
                 break;
@@ -253,16 +281,17 @@ impl Daemon {
         Ok(())
     }
 
-    /// Run a single update cycle
+    /// Run a single update cycle over the feeds that are due
     async fn run_update(&self) {
         let start = Utc::now();
 
-        match self.ctx.store.get_all_feeds() {
+        match self.ctx.store.get_due_feeds(start) {
             Ok(feeds) => {
                 if feeds.is_empty() {
-                    self.log("No feeds to update");
+                    self.log("No feeds due for update");
                     return;
                 }
+                self.log(&format!("{} feeds due", feeds.len()));
 
                 let run_id = match self
                     .ctx
@@ -333,6 +362,8 @@ impl Daemon {
                     errors,
                     elapsed.num_milliseconds() as f64 / 1000.0
                 ));
+
+                self.run_retention();
             }
             Err(e) => {
                 self.log(&format!("Failed to get feeds: {}", e));
@@ -340,6 +371,32 @@ impl Daemon {
         }
     }
 
+    /// Prune items outside the retention policy
+    fn run_retention(&self) {
+        let retention = &self.config.retention;
+        let result = self
+            .ctx
+            .store
+            .get_all_feeds()
+            .and_then(|feeds| self.ctx.store.prune_items(&retention.policy(&feeds), false));
+        match result {
+            Ok(report) if !report.is_empty() => {
+                self.log(&format!(
+                    "Pruned {} items and {} refresh runs",
+                    report.item_count(),
+                    report.refresh_runs
+                ));
+                if retention.vacuum {
+                    if let Err(e) = self.ctx.store.vacuum() {
+                        self.log(&format!("Failed to compact database: {}", e));
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => self.log(&format!("Failed to prune items: {}", e)),
+        }
+    }
+
     /// Stop the daemon (called externally)
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
@@ -438,4 +495,33 @@ mod tests {
         assert_eq!(DaemonConfig::format_interval(90), "90s");
         assert_eq!(DaemonConfig::format_interval(7200), "2h");
     }
+
+    #[test]
+    fn test_sleep_until_next_due() {
+        let now = Utc::now();
+        let in_ten_minutes = now + chrono::Duration::minutes(10);
+        let in_two_hours = now + chrono::Duration::hours(2);
+        let overdue = now - chrono::Duration::minutes(5);
+
+        assert_eq!(
+            sleep_until_next_due(now, Some(in_ten_minutes), 3600),
+            Duration::from_secs(600)
+        );
+        assert_eq!(
+            sleep_until_next_due(now, Some(in_two_hours), 3600),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            sleep_until_next_due(now, Some(overdue), 3600),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            sleep_until_next_due(now, None, 3600),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            sleep_until_next_due(now, Some(overdue), 30),
+            Duration::from_secs(30)
+        );
+    }
 }
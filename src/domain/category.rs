@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Category {
+    pub fn new(name: String, parent_id: Option<i64>) -> Self {
+        Self {
+            id: 0,
+            name,
+            parent_id,
+            created_at: Utc::now(),
+        }
+    }
+}
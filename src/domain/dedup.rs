@@ -0,0 +1,172 @@
+//! Keys for spotting the same article in several feeds.
+
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::html::strip_tags;
+
+/// Query parameters added by newsletters, ad networks and share buttons.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc",
+    "_hsmi", "mkt_tok", "ref", "ref_src", "ref_url", "spm", "cmpid", "ncid", "sr_share",
+];
+
+/// Title words plus this many words of body text make up a fingerprint.
+const FINGERPRINT_WORDS: usize = 40;
+
+/// Bodies shorter than this are too generic to fingerprint.
+const MIN_FINGERPRINT_WORDS: usize = 10;
+
+/// Normalizes an article URL so copies of one link compare equal: `https`
+/// scheme, lowercase host without `www.`, no fragment, default port, trailing
+/// slash or tracking parameters, and remaining parameters sorted.
+///
+/// Returns `None` for unparseable links and bare site roots, which many feeds
+/// use for every entry.
+pub fn canonical_url(link: &str) -> Option<String> {
+    let mut url = Url::parse(link.trim()).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    let path = url.path().trim_end_matches('/').to_string();
+    if path.is_empty() && params.is_empty() {
+        return None;
+    }
+
+    // `Url` already leaves out the scheme's default port, and switching to
+    // https drops an explicit `:443`; any other port names a different site.
+    let _ = url.set_scheme("https");
+    url.set_host(Some(&host)).ok()?;
+    url.set_fragment(None);
+    url.set_path(&path);
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    Some(url.to_string())
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// Hash of the title and the start of the body text, ignoring markup, case
+/// and punctuation. Catches cross-posts whose links differ.
+pub fn content_fingerprint(title: &str, html: &str) -> Option<String> {
+    let title_words = words(title);
+    let body_words: Vec<String> = words(&strip_tags(html))
+        .into_iter()
+        .take(FINGERPRINT_WORDS)
+        .collect();
+    if title_words.is_empty() || body_words.len() < MIN_FINGERPRINT_WORDS {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(title_words.join(" ").as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body_words.join(" ").as_bytes());
+    Some(hex::encode(hasher.finalize()))
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_url_strips_tracking_and_normalizes() {
+        assert_eq!(
+            canonical_url("http://WWW.Example.com:80/post/1/?utm_source=rss&b=2&a=1&fbclid=x#top")
+                .as_deref(),
+            Some("https://example.com/post/1?a=1&b=2")
+        );
+        assert_eq!(
+            canonical_url("https://example.com/post/1").as_deref(),
+            canonical_url("https://www.example.com/post/1/?utm_medium=email").as_deref()
+        );
+    }
+
+    #[test]
+    fn test_canonical_url_keeps_non_default_ports() {
+        assert_eq!(
+            canonical_url("https://example.com:443/post/1").as_deref(),
+            Some("https://example.com/post/1")
+        );
+        assert_eq!(
+            canonical_url("http://example.com:443/post/1").as_deref(),
+            Some("https://example.com/post/1")
+        );
+        assert_eq!(
+            canonical_url("http://example.com:8080/post/1").as_deref(),
+            Some("https://example.com:8080/post/1")
+        );
+        assert_ne!(
+            canonical_url("https://example.com:8443/post/1"),
+            canonical_url("https://example.com/post/1")
+        );
+    }
+
+    #[test]
+    fn test_canonical_url_rejects_site_roots_and_non_http() {
+        assert_eq!(canonical_url("https://example.com/"), None);
+        assert_eq!(canonical_url("https://example.com/?utm_source=rss"), None);
+        assert_eq!(canonical_url("mailto:me@example.com"), None);
+        assert_eq!(canonical_url("not a url"), None);
+        assert!(canonical_url("https://example.com/?p=42").is_some());
+    }
+
+    #[test]
+    fn test_content_fingerprint_ignores_markup_and_case() {
+        let body = "Rust 1.80 ships with LazyCell, LazyLock and a long list of smaller fixes to the standard library.";
+        let a = content_fingerprint("Rust 1.80 released", &format!("<p>{}</p>", body));
+        let b = content_fingerprint(
+            "RUST 1.80 RELEASED!",
+            &body.replace("LazyCell", "<b>LazyCell</b>"),
+        );
+        assert!(a.is_some());
+        assert_eq!(a, b);
+        assert_ne!(a, content_fingerprint("Rust 1.81 released", body));
+    }
+
+    #[test]
+    fn test_content_fingerprint_ignores_scripts_and_styles() {
+        let body =
+            "<p>Rust 1.80 ships with LazyCell, LazyLock and a long list of smaller fixes.</p>";
+        let plain = content_fingerprint("Rust 1.80 released", body);
+        let embedded = content_fingerprint(
+            "Rust 1.80 released",
+            &format!("<style>p {{ margin: 0 }}</style><script>track('view')</script>{body}"),
+        );
+        assert!(plain.is_some());
+        assert_eq!(plain, embedded);
+    }
+
+    #[test]
+    fn test_content_fingerprint_needs_enough_text() {
+        assert_eq!(content_fingerprint("Weekly links", "<p>Comments</p>"), None);
+        assert_eq!(
+            content_fingerprint("", "one two three four five six seven eight nine ten"),
+            None
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub length: Option<i64>,
+    pub duration_secs: Option<i64>,
+}
+
+impl Enclosure {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            mime_type: None,
+            length: None,
+            duration_secs: None,
+        }
+    }
+
+    pub fn is_audio(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|m| m.starts_with("audio/"))
+    }
+
+    pub fn is_video(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|m| m.starts_with("video/"))
+    }
+
+    /// `1:02:03` or `4:05`
+    pub fn display_duration(&self) -> Option<String> {
+        let secs = self.duration_secs?;
+        let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+        Some(if h > 0 {
+            format!("{}:{:02}:{:02}", h, m, s)
+        } else {
+            format!("{}:{:02}", m, s)
+        })
+    }
+
+    /// `12.3 MB`
+    pub fn display_length(&self) -> Option<String> {
+        let bytes = self.length.filter(|b| *b > 0)? as f64;
+        const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+        let mut value = bytes;
+        let mut unit = 0;
+        while value >= 1000.0 && unit < UNITS.len() - 1 {
+            value /= 1000.0;
+            unit += 1;
+        }
+        Some(if unit == 0 {
+            format!("{} B", bytes as i64)
+        } else {
+            format!("{:.1} {}", value, UNITS[unit])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_helpers() {
+        let mut enclosure = Enclosure::new("https://example.com/ep1.mp3".into());
+        enclosure.mime_type = Some("audio/mpeg".into());
+        enclosure.length = Some(12_345_678);
+        enclosure.duration_secs = Some(3723);
+
+        assert!(enclosure.is_audio());
+        assert!(!enclosure.is_video());
+        assert_eq!(enclosure.display_duration().as_deref(), Some("1:02:03"));
+        assert_eq!(enclosure.display_length().as_deref(), Some("12.3 MB"));
+
+        enclosure.duration_secs = Some(245);
+        enclosure.length = Some(512);
+        assert_eq!(enclosure.display_duration().as_deref(), Some("4:05"));
+        assert_eq!(enclosure.display_length().as_deref(), Some("512 B"));
+    }
+}
@@ -11,6 +11,8 @@ pub struct Feed {
     pub last_modified: Option<String>,
     pub last_fetched_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub original_url: Option<String>,
+    pub is_active: bool,
 }
 
 impl Feed {
@@ -24,12 +26,19 @@ impl Feed {
             last_modified: None,
             last_fetched_at: None,
             created_at: Utc::now(),
+            original_url: None,
+            is_active: true,
         }
     }
 
     pub fn display_title(&self) -> &str {
         self.title.as_deref().unwrap_or(&self.url)
     }
+
+    /// URL that item IDs are derived from; stays fixed when the feed moves.
+    pub fn item_key_url(&self) -> &str {
+        self.original_url.as_deref().unwrap_or(&self.url)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
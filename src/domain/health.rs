@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Feed;
+
+pub const BROKEN_AFTER_FAILURES: i64 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchLogEntry {
+    pub id: i64,
+    pub feed_id: i64,
+    pub fetched_at: DateTime<Utc>,
+    pub status_code: Option<i64>,
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+    pub latency_ms: i64,
+    pub bytes: Option<i64>,
+    pub final_url: Option<String>,
+}
+
+impl FetchLogEntry {
+    pub fn new(feed_id: i64) -> Self {
+        Self {
+            id: 0,
+            feed_id,
+            fetched_at: Utc::now(),
+            status_code: None,
+            error_kind: None,
+            error_message: None,
+            latency_ms: 0,
+            bytes: None,
+            final_url: None,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error_kind.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedHealth {
+    pub feed: Feed,
+    pub consecutive_failures: i64,
+    pub last_attempt: Option<FetchLogEntry>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub newest_item_at: Option<DateTime<Utc>>,
+}
+
+impl FeedHealth {
+    pub fn is_broken(&self) -> bool {
+        if self.is_superseded() {
+            return false;
+        }
+        if !self.feed.is_active || self.consecutive_failures >= BROKEN_AFTER_FAILURES {
+            return true;
+        }
+        matches!(
+            self.last_attempt.as_ref().and_then(|a| a.status_code),
+            Some(404) | Some(410)
+        )
+    }
+
+    pub fn redirected_to(&self) -> Option<&str> {
+        self.last_attempt
+            .as_ref()
+            .filter(|a| a.is_success())
+            .and_then(|a| a.final_url.as_deref())
+            .filter(|url| *url != self.feed.url)
+    }
+
+    /// Deactivated because it moved permanently to a URL that another
+    /// subscription already uses.
+    pub fn is_superseded(&self) -> bool {
+        !self.feed.is_active && self.redirected_to().is_some()
+    }
+
+    pub fn is_stale(&self, now: DateTime<Utc>, stale_after: Duration) -> bool {
+        if self.is_broken() || self.is_superseded() || self.last_success_at.is_none() {
+            return false;
+        }
+        match self.newest_item_at {
+            Some(newest) => now - newest > stale_after,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(failures: i64, last_attempt: Option<FetchLogEntry>) -> FeedHealth {
+        FeedHealth {
+            feed: Feed::new("https://example.com/feed.xml".into()),
+            consecutive_failures: failures,
+            last_attempt,
+            last_success_at: Some(Utc::now()),
+            newest_item_at: Some(Utc::now()),
+        }
+    }
+
+    #[test]
+    fn test_broken_after_repeated_failures_or_gone() {
+        assert!(!health(BROKEN_AFTER_FAILURES - 1, None).is_broken());
+        assert!(health(BROKEN_AFTER_FAILURES, None).is_broken());
+
+        let mut gone = FetchLogEntry::new(1);
+        gone.status_code = Some(410);
+        gone.error_kind = Some("http".into());
+        assert!(health(1, Some(gone)).is_broken());
+    }
+
+    #[test]
+    fn test_redirect_and_staleness() {
+        let mut moved = FetchLogEntry::new(1);
+        moved.status_code = Some(200);
+        moved.final_url = Some("https://new.example.com/feed.xml".into());
+        let mut report = health(0, Some(moved));
+        assert_eq!(
+            report.redirected_to(),
+            Some("https://new.example.com/feed.xml")
+        );
+
+        let now = Utc::now();
+        assert!(!report.is_stale(now, Duration::days(90)));
+        report.newest_item_at = Some(now - Duration::days(120));
+        assert!(report.is_stale(now, Duration::days(90)));
+        report.consecutive_failures = BROKEN_AFTER_FAILURES;
+        assert!(!report.is_stale(now, Duration::days(90)));
+    }
+
+    #[test]
+    fn test_deactivated_redirect_is_superseded_not_broken() {
+        let mut moved = FetchLogEntry::new(1);
+        moved.final_url = Some("https://new.example.com/feed.xml".into());
+        let mut report = health(0, Some(moved));
+        assert!(!report.is_superseded());
+
+        report.feed.is_active = false;
+        assert!(report.is_superseded());
+        assert!(!report.is_broken());
+        assert!(!report.is_stale(Utc::now(), Duration::days(0)));
+
+        // Inactive without a redirect is gone
+        report.last_attempt = None;
+        assert!(!report.is_superseded());
+        assert!(report.is_broken());
+    }
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use super::{dedup, Enclosure};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub id: String,
@@ -13,6 +15,16 @@ pub struct Item {
     pub author: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub fetched_at: DateTime<Utc>,
+    /// The feed's last-modified timestamp for the entry
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub enclosures: Vec<Enclosure>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// User tags, as opposed to the feed's `categories`
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Item {
@@ -28,6 +40,10 @@ impl Item {
             author: None,
             published_at: None,
             fetched_at: Utc::now(),
+            updated_at: None,
+            enclosures: Vec::new(),
+            categories: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -43,6 +59,27 @@ impl Item {
         self.title.as_deref().unwrap_or("(Untitled)")
     }
 
+    /// The link normalized for duplicate detection; see [`dedup::canonical_url`].
+    pub fn canonical_url(&self) -> Option<String> {
+        self.link.as_deref().and_then(dedup::canonical_url)
+    }
+
+    /// Title-and-body hash for duplicate detection.
+    pub fn fingerprint(&self) -> Option<String> {
+        dedup::content_fingerprint(self.title.as_deref()?, self.display_content())
+    }
+
+    /// Hash of the feed-provided fields an author can edit. A change means
+    /// the entry was updated since it was stored.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [&self.title, &self.link, &self.content, &self.summary] {
+            hasher.update(field.as_deref().unwrap_or("").as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(hasher.finalize())
+    }
+
     /// Get the best available content for display
     pub fn display_content(&self) -> &str {
         self.content
@@ -79,6 +116,29 @@ mod tests {
         assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[test]
+    fn test_content_hash_tracks_editable_fields() {
+        let mut item = Item::new(1, "https://example.com/feed.xml", "e1");
+        item.title = Some("Title".into());
+        item.content = Some("Body".into());
+        let original = item.content_hash();
+
+        item.fetched_at = Utc::now() + chrono::Duration::days(1);
+        item.updated_at = Some(Utc::now());
+        assert_eq!(item.content_hash(), original);
+
+        item.content = Some("Body, fixed".into());
+        assert_ne!(item.content_hash(), original);
+
+        // Field boundaries matter
+        let mut a = Item::new(1, "https://example.com/feed.xml", "e1");
+        a.title = Some("ab".into());
+        let mut b = a.clone();
+        b.title = Some("a".into());
+        b.link = Some("b".into());
+        assert_ne!(a.content_hash(), b.content_hash());
+    }
+
     #[test]
     fn test_display_title_with_title() {
         let mut item = Item::new(1, "https://example.com/feed.xml", "e1");
@@ -1,9 +1,20 @@
 pub mod auth;
+pub mod category;
+pub mod dedup;
+pub mod enclosure;
 pub mod feed;
+pub mod health;
 pub mod item;
+pub mod revision;
+pub mod schedule;
 pub mod state;
 
 pub use auth::AuthProfile;
+pub use category::Category;
+pub use enclosure::Enclosure;
 pub use feed::{Feed, FeedUpdate};
+pub use health::{FeedHealth, FetchLogEntry};
 pub use item::Item;
+pub use revision::ItemRevision;
+pub use schedule::FeedSchedule;
 pub use state::ItemState;
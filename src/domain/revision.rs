@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An earlier version of an item, kept when the feed changes the entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemRevision {
+    pub item_id: String,
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub content: Option<String>,
+    pub summary: Option<String>,
+    /// The feed's `updated` timestamp for this version, if it had one
+    pub updated_at: Option<DateTime<Utc>>,
+    /// When the newer version replaced this one
+    pub replaced_at: DateTime<Utc>,
+}
+
+impl ItemRevision {
+    pub fn display_content(&self) -> &str {
+        self.content
+            .as_deref()
+            .or(self.summary.as_deref())
+            .unwrap_or("")
+    }
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedSchedule {
+    pub feed_id: i64,
+    pub interval_secs: i64,
+    pub next_due_at: DateTime<Utc>,
+    pub consecutive_failures: i64,
+}
+
+impl FeedSchedule {
+    pub fn new(feed_id: i64, interval_secs: i64) -> Self {
+        Self {
+            feed_id,
+            interval_secs,
+            next_due_at: Utc::now(),
+            consecutive_failures: 0,
+        }
+    }
+}
@@ -14,6 +14,11 @@ pub struct ItemState {
     pub queued_at: Option<DateTime<Utc>>,
     pub saved_at: Option<DateTime<Utc>>,
     pub archived_at: Option<DateTime<Utc>>,
+    /// Left out of every view, e.g. by a `hide` rule
+    #[serde(default)]
+    pub is_hidden: bool,
+    #[serde(default)]
+    pub hidden_at: Option<DateTime<Utc>>,
 }
 
 impl ItemState {
@@ -30,6 +35,8 @@ impl ItemState {
             queued_at: None,
             saved_at: None,
             archived_at: None,
+            is_hidden: false,
+            hidden_at: None,
         }
     }
 }
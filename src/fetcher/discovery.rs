@@ -0,0 +1,286 @@
+use quick_xml::events::BytesStart;
+use url::Url;
+
+use crate::app::Result;
+use crate::fetcher::{FetchResult, Fetcher};
+use crate::normalizer::Normalizer;
+
+/// Paths tried when a page advertises no feeds in its `<head>`.
+pub const COMMON_FEED_PATHS: &[&str] = &["/feed", "/rss.xml", "/atom.xml", "/index.xml"];
+
+const FEED_MIME_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedCandidate {
+    pub url: String,
+    pub title: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Discovery {
+    /// The URL itself is a feed; the response can be used directly.
+    Feed(FetchResult),
+    /// The URL is a page; these feeds were found on or near it.
+    Candidates(Vec<FeedCandidate>),
+}
+
+/// Fetches `url` and, if it is not a feed, looks for the feeds it links to.
+pub async fn discover(
+    fetcher: &(dyn Fetcher + Send + Sync),
+    normalizer: &Normalizer,
+    url: &str,
+) -> Result<Discovery> {
+    let result = fetcher.fetch(url, None, None).await?;
+    let body = match result {
+        FetchResult::Content { ref body, .. } => body,
+        FetchResult::NotModified { .. } => return Ok(Discovery::Feed(result)),
+    };
+    if normalizer.normalize(0, url, body).is_ok() {
+        return Ok(Discovery::Feed(result));
+    }
+
+    let page_url = result.redirect().map(|r| r.url.as_str()).unwrap_or(url);
+    let base = Url::parse(page_url)?;
+    let candidates = find_feed_links(&String::from_utf8_lossy(body), &base);
+    if !candidates.is_empty() {
+        return Ok(Discovery::Candidates(candidates));
+    }
+
+    Ok(Discovery::Candidates(
+        probe_common_paths(fetcher, normalizer, &base).await,
+    ))
+}
+
+/// Collects `<link rel="alternate">` tags that point at feeds, in page order.
+pub fn find_feed_links(html: &str, base: &Url) -> Vec<FeedCandidate> {
+    let base = find_base_href(html, base).unwrap_or_else(|| base.clone());
+    let mut candidates: Vec<FeedCandidate> = Vec::new();
+
+    for tag in tags_named(html, "link") {
+        let attrs = html_attributes(tag);
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim().to_string())
+        };
+
+        let is_alternate = attr("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("alternate"))
+        });
+        let mime_type = attr("type").map(|t| t.to_ascii_lowercase());
+        let is_feed = mime_type
+            .as_deref()
+            .is_some_and(|t| FEED_MIME_TYPES.contains(&t));
+        let Some(href) = attr("href").filter(|h| !h.is_empty()) else {
+            continue;
+        };
+        if !is_alternate || !is_feed {
+            continue;
+        }
+
+        let Ok(url) = base.join(&href) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        let url = url.to_string();
+        if candidates.iter().any(|c| c.url == url) {
+            continue;
+        }
+        candidates.push(FeedCandidate {
+            url,
+            title: attr("title").filter(|t| !t.is_empty()),
+            mime_type,
+        });
+    }
+
+    candidates
+}
+
+async fn probe_common_paths(
+    fetcher: &(dyn Fetcher + Send + Sync),
+    normalizer: &Normalizer,
+    base: &Url,
+) -> Vec<FeedCandidate> {
+    let mut candidates = Vec::new();
+    for path in COMMON_FEED_PATHS {
+        let Ok(url) = base.join(path) else {
+            continue;
+        };
+        let url = url.to_string();
+        let Ok(FetchResult::Content { body, .. }) = fetcher.fetch(&url, None, None).await else {
+            continue;
+        };
+        if let Ok((meta, _)) = normalizer.normalize(0, &url, &body) {
+            candidates.push(FeedCandidate {
+                url,
+                title: meta.title,
+                mime_type: None,
+            });
+        }
+    }
+    candidates
+}
+
+fn find_base_href(html: &str, page: &Url) -> Option<Url> {
+    let tag = tags_named(html, "base").next()?;
+    let href = html_attributes(tag)
+        .into_iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("href"))?
+        .1;
+    page.join(href.trim()).ok()
+}
+
+/// Yields the inside of each `<name ...>` tag, e.g. `link rel="alternate"`.
+fn tags_named<'a>(html: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let lower = html.to_ascii_lowercase();
+    let open = format!("<{name}");
+    let mut starts = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find(&open) {
+        let start = from + pos;
+        let after = start + open.len();
+        from = after;
+        // `<linked>` is not `<link>`.
+        if lower[after..]
+            .chars()
+            .next()
+            .is_some_and(|c| !c.is_ascii_whitespace() && c != '/' && c != '>')
+        {
+            continue;
+        }
+        if let Some(end) = lower[start..].find('>') {
+            starts.push((start + 1, start + end));
+        }
+    }
+    starts
+        .into_iter()
+        .map(move |(start, end)| html[start..end].trim_end_matches('/').trim_end())
+}
+
+/// HTML attributes may be unquoted or valueless; quick-xml's HTML mode
+/// handles both.
+fn html_attributes(tag: &str) -> Vec<(String, String)> {
+    let name_len = tag
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(tag.len());
+    let element = BytesStart::from_content(tag, name_len);
+    element
+        .html_attributes()
+        .filter_map(|attr| attr.ok())
+        .map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+            let raw = String::from_utf8_lossy(&attr.value);
+            let value = html_escape::decode_html_entities(&raw).into_owned();
+            (key, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::testing::MockFetcher;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head>
+  <title>Example</title>
+  <LINK REL="alternate" TYPE="application/rss+xml" TITLE="Posts &amp; Notes" HREF="/feed.xml">
+  <link rel=alternate type=application/atom+xml href="https://example.com/atom.xml" />
+  <link rel="alternate" type="application/feed+json" href="feed.json">
+  <link rel="alternate" type="text/html" hreflang="de" href="/de/">
+  <link rel="stylesheet" type="text/css" href="/style.css">
+  <linkfoo rel="alternate" type="application/rss+xml" href="/nope.xml">
+  <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+</head><body></body></html>"#;
+
+    const RSS: &[u8] = br#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Probed</title></channel></rss>"#;
+
+    fn html(body: &str) -> FetchResult {
+        FetchResult::Content {
+            status: 200,
+            body: body.as_bytes().to_vec(),
+            etag: None,
+            last_modified: None,
+            max_age_secs: None,
+            redirect: None,
+        }
+    }
+
+    #[test]
+    fn test_find_feed_links() {
+        let base = Url::parse("https://example.com/blog/").unwrap();
+        let candidates = find_feed_links(PAGE, &base);
+        let urls: Vec<&str> = candidates.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/feed.xml",
+                "https://example.com/atom.xml",
+                "https://example.com/blog/feed.json",
+            ]
+        );
+        assert_eq!(candidates[0].title.as_deref(), Some("Posts & Notes"));
+        assert_eq!(
+            candidates[1].mime_type.as_deref(),
+            Some("application/atom+xml")
+        );
+    }
+
+    #[test]
+    fn test_find_feed_links_honors_base_href() {
+        let page = r#"<head><base href="https://cdn.example.com/site/">
+<link rel="alternate" type="application/rss+xml" href="rss"></head>"#;
+        let base = Url::parse("https://example.com/").unwrap();
+        assert_eq!(
+            find_feed_links(page, &base)[0].url,
+            "https://cdn.example.com/site/rss"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discover_returns_feed_directly() {
+        let mock = MockFetcher::new();
+        mock.set_response(
+            "https://example.com/feed.xml",
+            html(std::str::from_utf8(RSS).unwrap()),
+        );
+        let discovery = discover(&mock, &Normalizer::new(), "https://example.com/feed.xml")
+            .await
+            .unwrap();
+        assert!(matches!(discovery, Discovery::Feed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_discover_probes_common_paths_without_links() {
+        let mock = MockFetcher::new();
+        mock.set_response("https://example.com/", html("<html><head></head></html>"));
+        mock.set_response(
+            "https://example.com/index.xml",
+            html(std::str::from_utf8(RSS).unwrap()),
+        );
+        let discovery = discover(&mock, &Normalizer::new(), "https://example.com/")
+            .await
+            .unwrap();
+        let Discovery::Candidates(candidates) = discovery else {
+            panic!("expected candidates");
+        };
+        assert_eq!(
+            candidates,
+            vec![FeedCandidate {
+                url: "https://example.com/index.xml".into(),
+                title: Some("Probed".into()),
+                mime_type: None,
+            }]
+        );
+    }
+}
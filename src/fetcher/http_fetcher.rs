@@ -1,12 +1,21 @@
 use std::time::Duration;
 
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
-use reqwest::{Client, StatusCode};
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, HeaderValue, CACHE_CONTROL, EXPIRES, IF_MODIFIED_SINCE, IF_NONE_MATCH, LOCATION,
+};
+use reqwest::{redirect, Client, Response, StatusCode};
+use url::Url;
 
-use crate::app::Result;
-use crate::fetcher::{FetchResult, Fetcher};
+use crate::app::{Result, RivuletError};
+use crate::fetcher::{FetchResult, Fetcher, Redirect, RedirectKind};
 
+/// Same limit reqwest applies when it follows redirects itself.
+const MAX_REDIRECTS: usize = 10;
+
+/// Fetches over one shared `Client`; clones share its connection pool.
+#[derive(Clone)]
 pub struct HttpFetcher {
     client: Client,
 }
@@ -18,11 +27,63 @@ impl HttpFetcher {
             .gzip(true)
             .brotli(true)
             .user_agent("rivulet/0.1.0")
+            // Redirects are followed by hand so we can tell 301/308 from 302/307.
+            .redirect(redirect::Policy::none())
             .build()
             .expect("Failed to build HTTP client");
 
         Self { client }
     }
+
+    /// GETs `url`, following up to `MAX_REDIRECTS` redirects, and returns
+    /// the final response with where the redirects led, if anywhere.
+    /// `timeout` replaces the client's 10 seconds for this request.
+    pub async fn get(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        timeout: Option<Duration>,
+    ) -> Result<(Response, Option<Redirect>)> {
+        let mut current = Url::parse(url)?;
+        let mut chain_kind: Option<RedirectKind> = None;
+        let mut hops = 0;
+        let response = loop {
+            let mut request = self.client.get(current.clone()).headers(headers.clone());
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+            let response = request.send().await?;
+            let Some(hop_kind) = redirect_kind(response.status()) else {
+                break response;
+            };
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+            else {
+                break response;
+            };
+
+            hops += 1;
+            if hops > MAX_REDIRECTS {
+                return Err(RivuletError::Other(format!(
+                    "Too many redirects fetching {}",
+                    url
+                )));
+            }
+            current = current.join(location)?;
+            chain_kind = Some(match chain_kind {
+                Some(RedirectKind::Temporary) => RedirectKind::Temporary,
+                _ => hop_kind,
+            });
+        };
+
+        let redirect = chain_kind.map(|kind| Redirect {
+            url: current.to_string(),
+            kind,
+        });
+        Ok((response, redirect))
+    }
 }
 
 impl Default for HttpFetcher {
@@ -53,10 +114,10 @@ impl Fetcher for HttpFetcher {
             }
         }
 
-        let response = self.client.get(url).headers(headers).send().await?;
+        let (response, redirect) = self.get(url, headers, None).await?;
 
         if response.status() == StatusCode::NOT_MODIFIED {
-            return Ok(FetchResult::NotModified);
+            return Ok(FetchResult::NotModified { redirect });
         }
 
         response.error_for_status_ref()?;
@@ -73,12 +134,167 @@ impl Fetcher for HttpFetcher {
             .and_then(|v| v.to_str().ok())
             .map(String::from);
 
+        let max_age_secs = freshness_lifetime(response.headers(), Utc::now());
+        let status = response.status().as_u16();
+
         let body = response.bytes().await?.to_vec();
 
         Ok(FetchResult::Content {
+            status,
             body,
             etag,
             last_modified,
+            max_age_secs,
+            redirect,
         })
     }
 }
+
+fn redirect_kind(status: StatusCode) -> Option<RedirectKind> {
+    match status {
+        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT => {
+            Some(RedirectKind::Permanent)
+        }
+        StatusCode::FOUND | StatusCode::SEE_OTHER | StatusCode::TEMPORARY_REDIRECT => {
+            Some(RedirectKind::Temporary)
+        }
+        _ => None,
+    }
+}
+
+/// How long the server says the response stays fresh. `Cache-Control`
+/// takes precedence over `Expires`, as in RFC 9111.
+fn freshness_lifetime(headers: &HeaderMap, now: DateTime<Utc>) -> Option<u64> {
+    if let Some(cache_control) = headers.get(CACHE_CONTROL).and_then(|v| v.to_str().ok()) {
+        let mut max_age = None;
+        for directive in cache_control.split(',').map(str::trim) {
+            let lower = directive.to_ascii_lowercase();
+            if lower == "no-cache" || lower == "no-store" {
+                return None;
+            }
+            if let Some(value) = lower.strip_prefix("max-age=") {
+                max_age = value.trim_matches('"').parse::<u64>().ok();
+            }
+        }
+        if max_age.is_some() {
+            return max_age;
+        }
+    }
+
+    let expires = headers.get(EXPIRES).and_then(|v| v.to_str().ok())?;
+    let expires = DateTime::parse_from_rfc2822(expires).ok()?;
+    let secs = (expires.with_timezone(&Utc) - now).num_seconds();
+    (secs > 0).then_some(secs as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_redirect_kind() {
+        assert_eq!(
+            redirect_kind(StatusCode::MOVED_PERMANENTLY),
+            Some(RedirectKind::Permanent)
+        );
+        assert_eq!(
+            redirect_kind(StatusCode::PERMANENT_REDIRECT),
+            Some(RedirectKind::Permanent)
+        );
+        assert_eq!(
+            redirect_kind(StatusCode::TEMPORARY_REDIRECT),
+            Some(RedirectKind::Temporary)
+        );
+        assert_eq!(redirect_kind(StatusCode::NOT_MODIFIED), None);
+        assert_eq!(redirect_kind(StatusCode::OK), None);
+    }
+
+    #[test]
+    fn test_freshness_lifetime_prefers_cache_control() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let map = headers(&[
+            ("cache-control", "public, max-age=1800"),
+            ("expires", "Mon, 01 Jan 2024 02:00:00 GMT"),
+        ]);
+        assert_eq!(freshness_lifetime(&map, now), Some(1800));
+
+        let map = headers(&[("expires", "Mon, 01 Jan 2024 02:00:00 GMT")]);
+        assert_eq!(freshness_lifetime(&map, now), Some(7200));
+
+        let map = headers(&[("expires", "Sun, 31 Dec 2023 23:00:00 GMT")]);
+        assert_eq!(freshness_lifetime(&map, now), None);
+
+        let map = headers(&[("cache-control", "no-cache, max-age=600")]);
+        assert_eq!(freshness_lifetime(&map, now), None);
+
+        assert_eq!(freshness_lifetime(&HeaderMap::new(), now), None);
+    }
+
+    fn redirect_to(status: &str, location: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\nlocation: {location}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+        )
+    }
+
+    const FEED_OK: &str = "HTTP/1.1 200 OK\r\ncontent-length: 4\r\nconnection: close\r\n\r\nfeed";
+
+    #[tokio::test]
+    async fn test_fetch_reports_redirect_chain_kind() {
+        let base = crate::fetcher::testing::serve_http(vec![
+            ("/old", redirect_to("301 Moved Permanently", "/mid")),
+            ("/mid", redirect_to("308 Permanent Redirect", "/feed")),
+            ("/temp", redirect_to("302 Found", "/old")),
+            ("/feed", FEED_OK.to_string()),
+            (
+                "/gone",
+                "HTTP/1.1 410 Gone\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string(),
+            ),
+        ])
+        .await;
+        let fetcher = HttpFetcher::new();
+
+        let result = fetcher
+            .fetch(&format!("{base}/old"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.redirect(),
+            Some(&Redirect {
+                url: format!("{base}/feed"),
+                kind: RedirectKind::Permanent,
+            })
+        );
+
+        let result = fetcher
+            .fetch(&format!("{base}/temp"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.redirect().map(|r| r.kind),
+            Some(RedirectKind::Temporary)
+        );
+
+        let result = fetcher
+            .fetch(&format!("{base}/feed"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(result.redirect(), None);
+
+        let err = fetcher
+            .fetch(&format!("{base}/gone"), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.http_status(), Some(410));
+    }
+}
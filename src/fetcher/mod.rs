@@ -1,20 +1,58 @@
+pub mod discovery;
 pub mod http_fetcher;
 pub mod parallel;
+pub mod schedule;
 
 use async_trait::async_trait;
 
 use crate::app::Result;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectKind {
+    /// Every hop was a 301 or 308
+    Permanent,
+    /// At least one hop was a 302, 303 or 307
+    Temporary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// URL the redirect chain ended at
+    pub url: String,
+    pub kind: RedirectKind,
+}
+
+impl Redirect {
+    pub fn is_permanent(&self) -> bool {
+        self.kind == RedirectKind::Permanent
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FetchResult {
     /// New content fetched successfully
     Content {
+        /// HTTP status of the final response (a 2xx)
+        status: u16,
         body: Vec<u8>,
         etag: Option<String>,
         last_modified: Option<String>,
+        /// Freshness lifetime from `Cache-Control: max-age` or `Expires`
+        max_age_secs: Option<u64>,
+        redirect: Option<Redirect>,
     },
     /// Content not modified (HTTP 304)
-    NotModified,
+    NotModified { redirect: Option<Redirect> },
+}
+
+impl FetchResult {
+    pub fn redirect(&self) -> Option<&Redirect> {
+        match self {
+            FetchResult::Content { redirect, .. } | FetchResult::NotModified { redirect } => {
+                redirect.as_ref()
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -64,6 +102,35 @@ pub mod testing {
         }
     }
 
+    /// Serves canned raw HTTP responses keyed by request path on a loopback
+    /// port, for exercising `HttpFetcher` end to end. Returns the base URL.
+    pub async fn serve_http(routes: Vec<(&'static str, String)>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind loopback");
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes: HashMap<&'static str, String> = routes.into_iter().collect();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = routes.get(path).cloned().unwrap_or_else(|| {
+                    "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string()
+                });
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        base
+    }
+
     impl Default for MockFetcher {
         fn default() -> Self {
             Self::new()
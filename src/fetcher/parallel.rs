@@ -1,18 +1,23 @@
 use std::sync::Arc;
+use std::time::Instant;
 
+use chrono::Utc;
 use tokio::sync::Semaphore;
 
 use crate::app::Result;
-use crate::domain::{Feed, FeedUpdate};
+use crate::domain::{Feed, FeedUpdate, FetchLogEntry, Item};
+use crate::fetcher::schedule::{self, RefreshHints};
 use crate::fetcher::{FetchResult, Fetcher};
 use crate::normalizer::Normalizer;
-use crate::store::{FeedRefreshResult, Store};
+use crate::rules::RuleSet;
+use crate::store::{AddItemsResult, FeedRefreshResult, Store};
 
 pub const DEFAULT_WORKERS: usize = 30;
 
 pub struct ParallelFetcher {
     fetcher: Arc<dyn Fetcher + Send + Sync>,
     semaphore: Arc<Semaphore>,
+    rules: Arc<RuleSet>,
 }
 
 impl ParallelFetcher {
@@ -24,9 +29,21 @@ impl ParallelFetcher {
         Self {
             fetcher,
             semaphore: Arc::new(Semaphore::new(workers)),
+            rules: Arc::new(RuleSet::default()),
         }
     }
 
+    /// Runs `rules` on the items each fetch inserts.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.rules = Arc::new(rules);
+        self
+    }
+
+    /// The rules run on inserted items.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
     pub async fn fetch_all<S: Store + Send + Sync + 'static>(
         &self,
         feeds: Vec<Feed>,
@@ -42,11 +59,12 @@ impl ParallelFetcher {
             let semaphore = self.semaphore.clone();
             let store = store.clone();
             let normalizer = normalizer.clone();
+            let rules = self.rules.clone();
 
             let handle = tokio::spawn(async move {
                 let _permit = semaphore.acquire().await.expect("Semaphore closed");
 
-                let result = fetch_single_feed(&fetcher, &feed, &store, &normalizer).await;
+                let result = fetch_single_feed(&fetcher, &feed, &store, &normalizer, &rules).await;
                 (feed.id, result)
             });
 
@@ -78,23 +96,77 @@ impl ParallelFetcher {
     }
 }
 
+/// Fetches one feed and records the attempt in the fetch log and schedule.
 async fn fetch_single_feed<S: Store>(
     fetcher: &Arc<dyn Fetcher + Send + Sync>,
     feed: &Feed,
     store: &Arc<S>,
     normalizer: &Normalizer,
+    rules: &RuleSet,
+) -> Result<FeedRefreshResult> {
+    let mut log = FetchLogEntry::new(feed.id);
+    let result = refresh_feed(fetcher, feed, store, normalizer, rules, &mut log).await;
+
+    if let Err(ref e) = result {
+        log.error_kind = Some(e.kind().to_string());
+        log.error_message = Some(e.to_string());
+        log.status_code = e.http_status().map(i64::from).or(log.status_code);
+        if e.http_status() == Some(410) {
+            tracing::info!("Feed {} is gone (410), marking inactive", feed.url);
+            if let Err(e) = store.set_feed_active(feed.id, false) {
+                tracing::warn!("Failed to deactivate {}: {}", feed.url, e);
+            }
+        }
+        if let Err(e) = schedule::record_failure(store.as_ref(), feed.id, Utc::now()) {
+            tracing::warn!("Failed to record schedule for {}: {}", feed.url, e);
+        }
+    }
+    if let Err(e) = store.record_fetch_attempt(&log) {
+        tracing::warn!("Failed to record fetch log for {}: {}", feed.url, e);
+    }
+
+    result
+}
+
+async fn refresh_feed<S: Store>(
+    fetcher: &Arc<dyn Fetcher + Send + Sync>,
+    feed: &Feed,
+    store: &Arc<S>,
+    normalizer: &Normalizer,
+    rules: &RuleSet,
+    log: &mut FetchLogEntry,
 ) -> Result<FeedRefreshResult> {
+    let started = Instant::now();
     let result = fetcher
         .fetch(
             &feed.url,
             feed.etag.as_deref(),
             feed.last_modified.as_deref(),
         )
-        .await?;
+        .await;
+    log.latency_ms = i64::try_from(started.elapsed().as_millis()).unwrap_or(i64::MAX);
+    let result = result?;
+
+    if let Some(redirect) = result.redirect() {
+        log.final_url = Some(redirect.url.clone());
+        if redirect.is_permanent()
+            && follow_permanent_redirect(store.as_ref(), feed, &redirect.url)?
+                == RedirectOutcome::Deactivated
+        {
+            // The subscription at the new URL stores these items
+            return Ok(FeedRefreshResult {
+                feed_id: feed.id,
+                new_count: 0,
+                inserted_item_ids: Vec::new(),
+            });
+        }
+    }
 
     match result {
-        FetchResult::NotModified => {
+        FetchResult::NotModified { .. } => {
             tracing::debug!("Feed {} not modified", feed.url);
+            log.status_code = Some(304);
+            schedule::record_success(store.as_ref(), feed.id, None, Utc::now())?;
             Ok(FeedRefreshResult {
                 feed_id: feed.id,
                 new_count: 0,
@@ -102,11 +174,22 @@ async fn fetch_single_feed<S: Store>(
             })
         }
         FetchResult::Content {
+            status,
             body,
             etag,
             last_modified,
+            max_age_secs,
+            ..
         } => {
-            let (feed_meta, items) = normalizer.normalize(feed.id, &feed.url, &body)?;
+            log.status_code = Some(i64::from(status));
+            log.bytes = i64::try_from(body.len()).ok();
+
+            let (feed_meta, items) = normalizer.normalize(feed.id, feed.item_key_url(), &body)?;
+            let hints = RefreshHints {
+                posting_gap_secs: schedule::posting_gap_secs(&items),
+                feed_ttl_secs: feed_meta.ttl_secs,
+                http_max_age_secs: max_age_secs,
+            };
 
             // Update feed metadata
             let mut update = FeedUpdate::default();
@@ -118,13 +201,12 @@ async fn fetch_single_feed<S: Store>(
             }
             update.etag = etag;
             update.last_modified = last_modified;
-            update.last_fetched_at = Some(chrono::Utc::now());
+            update.last_fetched_at = Some(Utc::now());
 
             store.update_feed(feed.id, &update)?;
+            schedule::record_success(store.as_ref(), feed.id, Some(&hints), Utc::now())?;
 
-            // Add new items
-            let add_result = store.add_items_with_report(&items)?;
-            tracing::info!("Added {} new items from {}", add_result.count, feed.url);
+            let add_result = store_items(store.as_ref(), feed, &items, rules)?;
 
             Ok(FeedRefreshResult {
                 feed_id: feed.id,
@@ -134,3 +216,263 @@ async fn fetch_single_feed<S: Store>(
         }
     }
 }
+
+/// Stores a feed's fetched items and runs `rules` on the ones inserted.
+/// A failing rule (e.g. bad search syntax) is logged rather than failing the
+/// refresh.
+pub fn store_items<S: Store + ?Sized>(
+    store: &S,
+    feed: &Feed,
+    items: &[Item],
+    rules: &RuleSet,
+) -> Result<AddItemsResult> {
+    let add_result = store.add_items_with_report(items)?;
+    tracing::info!(
+        "Added {} new items from {} ({} updated)",
+        add_result.count,
+        feed.url,
+        add_result.updated_ids.len()
+    );
+
+    let inserted: Vec<Item> = items
+        .iter()
+        .filter(|item| add_result.inserted_ids.contains(&item.id))
+        .cloned()
+        .collect();
+    match rules.apply(store, feed, &inserted) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Rules acted on {} items from {}", count, feed.url),
+        Err(e) => tracing::warn!("Rules failed for {}: {}", feed.url, e),
+    }
+
+    Ok(add_result)
+}
+
+/// What following a permanent redirect did to a feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectOutcome {
+    /// The redirect pointed back at the feed's own URL
+    Unchanged,
+    /// The feed now uses the new URL
+    Moved,
+    /// Another subscription already uses the new URL, so this feed was
+    /// deactivated rather than fetching the same items twice
+    Deactivated,
+}
+
+/// Points a feed at the target of a 301/308. If another subscription already
+/// uses that URL the feed is deactivated instead; `rivulet health` lists it
+/// as redirected so the leftover copy can be removed.
+pub fn follow_permanent_redirect<S: Store + ?Sized>(
+    store: &S,
+    feed: &Feed,
+    new_url: &str,
+) -> Result<RedirectOutcome> {
+    if new_url == feed.url {
+        return Ok(RedirectOutcome::Unchanged);
+    }
+    if store.update_feed_url(feed.id, new_url)? {
+        tracing::info!("Feed {} moved permanently to {}", feed.url, new_url);
+        return Ok(RedirectOutcome::Moved);
+    }
+    tracing::warn!(
+        "Feed {} moved to {}, which is already subscribed; deactivating it",
+        feed.url,
+        new_url
+    );
+    store.set_feed_active(feed.id, false)?;
+    Ok(RedirectOutcome::Deactivated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::http_fetcher::HttpFetcher;
+    use crate::fetcher::testing::{serve_http, MockFetcher};
+    use crate::fetcher::{Redirect, RedirectKind};
+    use crate::store::SqliteStore;
+
+    const RSS: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Moved</title>
+<item><title>One</title><guid>one</guid></item>
+</channel></rss>"#;
+
+    #[tokio::test]
+    async fn test_permanent_redirect_moves_feed_without_duplicating_items() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let feed_id = store
+            .add_feed(&Feed::new("http://old.example.com/feed.xml".into()))
+            .unwrap();
+        let mock = Arc::new(MockFetcher::new());
+        let content = |redirect| FetchResult::Content {
+            status: 200,
+            body: RSS.to_vec(),
+            etag: None,
+            last_modified: None,
+            max_age_secs: None,
+            redirect,
+        };
+        mock.set_response(
+            "http://old.example.com/feed.xml",
+            content(Some(Redirect {
+                url: "https://new.example.com/feed.xml".into(),
+                kind: RedirectKind::Permanent,
+            })),
+        );
+        mock.set_response("https://new.example.com/feed.xml", content(None));
+        let fetcher = ParallelFetcher::new(mock);
+        let normalizer = Normalizer::new();
+
+        let feed = store.get_feed(feed_id).unwrap().unwrap();
+        fetcher
+            .fetch_all(vec![feed], store.clone(), &normalizer, None)
+            .await;
+        let feed = store.get_feed(feed_id).unwrap().unwrap();
+        assert_eq!(feed.url, "https://new.example.com/feed.xml");
+        assert_eq!(store.get_items_by_feed(feed_id).unwrap().len(), 1);
+
+        let results = fetcher
+            .fetch_all(vec![feed], store.clone(), &normalizer, None)
+            .await;
+        assert_eq!(results[0].1.as_ref().unwrap().new_count, 0);
+        assert_eq!(store.get_items_by_feed(feed_id).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_subscribed_url_deactivates_feed() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let old_id = store
+            .add_feed(&Feed::new("http://old.example.com/feed.xml".into()))
+            .unwrap();
+        let new_id = store
+            .add_feed(&Feed::new("https://new.example.com/feed.xml".into()))
+            .unwrap();
+        let mock = Arc::new(MockFetcher::new());
+        mock.set_response(
+            "http://old.example.com/feed.xml",
+            FetchResult::Content {
+                status: 200,
+                body: RSS.to_vec(),
+                etag: None,
+                last_modified: None,
+                max_age_secs: None,
+                redirect: Some(Redirect {
+                    url: "https://new.example.com/feed.xml".into(),
+                    kind: RedirectKind::Permanent,
+                }),
+            },
+        );
+        let fetcher = ParallelFetcher::new(mock);
+
+        let feed = store.get_feed(old_id).unwrap().unwrap();
+        let results = fetcher
+            .fetch_all(vec![feed], store.clone(), &Normalizer::new(), None)
+            .await;
+        assert_eq!(results[0].1.as_ref().unwrap().new_count, 0);
+        let old = store.get_feed(old_id).unwrap().unwrap();
+        assert!(!old.is_active);
+        assert_eq!(old.url, "http://old.example.com/feed.xml");
+        assert!(store.get_items_by_feed(old_id).unwrap().is_empty());
+        assert!(store.get_feed(new_id).unwrap().unwrap().is_active);
+
+        let health = store.get_feed_health().unwrap();
+        let old = health.iter().find(|h| h.feed.id == old_id).unwrap();
+        assert!(old.is_superseded());
+        assert_eq!(
+            old.redirected_to(),
+            Some("https://new.example.com/feed.xml")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gone_feed_is_marked_inactive() {
+        let base = serve_http(vec![(
+            "/gone.xml",
+            "HTTP/1.1 410 Gone\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string(),
+        )])
+        .await;
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let feed_id = store
+            .add_feed(&Feed::new(format!("{base}/gone.xml")))
+            .unwrap();
+        let fetcher = ParallelFetcher::new(Arc::new(HttpFetcher::new()));
+
+        let feed = store.get_feed(feed_id).unwrap().unwrap();
+        let results = fetcher
+            .fetch_all(vec![feed], store.clone(), &Normalizer::new(), None)
+            .await;
+        assert!(results[0].1.is_err());
+        assert!(!store.get_feed(feed_id).unwrap().unwrap().is_active);
+        assert_eq!(
+            store.get_fetch_log(feed_id, 1).unwrap()[0].status_code,
+            Some(410)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_log_records_the_response_status() {
+        let body = String::from_utf8(RSS.to_vec()).unwrap();
+        let base = serve_http(vec![(
+            "/feed.xml",
+            format!(
+                "HTTP/1.1 203 Non-Authoritative Information\r\ncontent-type: application/rss+xml\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+        )])
+        .await;
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let feed_id = store
+            .add_feed(&Feed::new(format!("{base}/feed.xml")))
+            .unwrap();
+        let fetcher = ParallelFetcher::new(Arc::new(HttpFetcher::new()));
+
+        let feed = store.get_feed(feed_id).unwrap().unwrap();
+        let results = fetcher
+            .fetch_all(vec![feed], store.clone(), &Normalizer::new(), None)
+            .await;
+        assert!(results[0].1.is_ok());
+        assert_eq!(
+            store.get_fetch_log(feed_id, 1).unwrap()[0].status_code,
+            Some(203)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rules_act_on_inserted_items_only() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let mock = Arc::new(MockFetcher::new());
+        mock.set_response(
+            "https://example.com/feed.xml",
+            FetchResult::Content {
+                status: 200,
+                body: RSS.to_vec(),
+                etag: None,
+                last_modified: None,
+                max_age_secs: None,
+                redirect: None,
+            },
+        );
+        let rule: crate::config::RuleConfig =
+            toml::from_str("title = \"^one$\"\nactions = [\"star\"]").unwrap();
+        let fetcher = ParallelFetcher::new(mock).with_rules(RuleSet::compile(&[rule]).unwrap());
+        let normalizer = Normalizer::new();
+
+        let feed = store.get_feed(feed_id).unwrap().unwrap();
+        fetcher
+            .fetch_all(vec![feed.clone()], store.clone(), &normalizer, None)
+            .await;
+        let item = store.get_items_by_feed(feed_id).unwrap().remove(0);
+        assert!(store.get_item_state(&item.id).unwrap().unwrap().is_starred);
+
+        // Unstarring sticks: the next refresh inserts nothing new
+        store.set_starred(&item.id, false).unwrap();
+        fetcher
+            .fetch_all(vec![feed], store.clone(), &normalizer, None)
+            .await;
+        assert!(!store.get_item_state(&item.id).unwrap().unwrap().is_starred);
+    }
+}
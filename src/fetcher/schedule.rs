@@ -0,0 +1,235 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::app::Result;
+use crate::domain::{FeedSchedule, Item};
+use crate::store::Store;
+
+pub const MIN_INTERVAL_SECS: i64 = 15 * 60;
+pub const DEFAULT_INTERVAL_SECS: i64 = 60 * 60;
+pub const MAX_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// Each 304 stretches a feed's interval by this percentage.
+const QUIET_GROWTH_PERCENT: i64 = 125;
+
+/// Failures stop doubling the delay after this many attempts.
+const MAX_BACKOFF_EXPONENT: i64 = 6;
+
+/// Signals gathered from one successful fetch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshHints {
+    /// Median gap between entry dates in the fetched document
+    pub posting_gap_secs: Option<i64>,
+    /// Publisher hint from `<ttl>` or `sy:updatePeriod`
+    pub feed_ttl_secs: Option<u64>,
+    /// Server hint from `Cache-Control: max-age` or `Expires`
+    pub http_max_age_secs: Option<u64>,
+}
+
+impl RefreshHints {
+    /// Publisher and server hints say "don't come back sooner than this";
+    /// they never shorten the interval below what posting frequency suggests.
+    fn floor_secs(&self) -> i64 {
+        let ttl = self.feed_ttl_secs.map(saturating_secs).unwrap_or(0);
+        let max_age = self.http_max_age_secs.map(saturating_secs).unwrap_or(0);
+        MIN_INTERVAL_SECS
+            .max(ttl)
+            .max(max_age)
+            .min(MAX_INTERVAL_SECS)
+    }
+}
+
+fn saturating_secs(secs: u64) -> i64 {
+    i64::try_from(secs).unwrap_or(i64::MAX)
+}
+
+/// Median gap between distinct entry dates, or `None` with fewer than two.
+pub fn posting_gap_secs(items: &[Item]) -> Option<i64> {
+    let mut dates: Vec<DateTime<Utc>> = items.iter().filter_map(|i| i.published_at).collect();
+    dates.sort();
+    dates.dedup();
+
+    let mut gaps: Vec<i64> = dates
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_seconds())
+        .filter(|gap| *gap > 0)
+        .collect();
+    if gaps.is_empty() {
+        return None;
+    }
+
+    gaps.sort_unstable();
+    Some(gaps[gaps.len() / 2])
+}
+
+/// Interval after a fetch that returned content. Polling at half the typical
+/// posting gap keeps new entries at most half a gap stale.
+pub fn interval_after_content(previous: Option<&FeedSchedule>, hints: &RefreshHints) -> i64 {
+    let base = match hints.posting_gap_secs {
+        Some(gap) => gap / 2,
+        None => previous
+            .map(|s| s.interval_secs)
+            .unwrap_or(DEFAULT_INTERVAL_SECS),
+    };
+    base.clamp(hints.floor_secs(), MAX_INTERVAL_SECS)
+}
+
+/// Interval after a 304: nothing changed, so back off gently.
+pub fn interval_after_not_modified(previous: Option<&FeedSchedule>) -> i64 {
+    let interval = previous
+        .map(|s| s.interval_secs)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    (interval * QUIET_GROWTH_PERCENT / 100).clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS)
+}
+
+/// Delay before retrying a feed that has failed `failures` times in a row.
+pub fn backoff_delay_secs(interval_secs: i64, failures: i64) -> i64 {
+    let exponent = failures.clamp(0, MAX_BACKOFF_EXPONENT) as u32;
+    interval_secs
+        .saturating_mul(1 << exponent)
+        .min(MAX_INTERVAL_SECS)
+}
+
+/// Records a successful fetch. `hints` is `None` for a 304 response.
+pub fn record_success<S: Store + ?Sized>(
+    store: &S,
+    feed_id: i64,
+    hints: Option<&RefreshHints>,
+    now: DateTime<Utc>,
+) -> Result<FeedSchedule> {
+    let previous = store.get_feed_schedule(feed_id)?;
+    let interval_secs = match hints {
+        Some(hints) => interval_after_content(previous.as_ref(), hints),
+        None => interval_after_not_modified(previous.as_ref()),
+    };
+
+    let schedule = FeedSchedule {
+        feed_id,
+        interval_secs,
+        next_due_at: now + Duration::seconds(interval_secs),
+        consecutive_failures: 0,
+    };
+    store.upsert_feed_schedule(&schedule)?;
+    Ok(schedule)
+}
+
+/// Records a failed fetch and pushes the next attempt out exponentially.
+pub fn record_failure<S: Store + ?Sized>(
+    store: &S,
+    feed_id: i64,
+    now: DateTime<Utc>,
+) -> Result<FeedSchedule> {
+    let previous = store.get_feed_schedule(feed_id)?;
+    let interval_secs = previous
+        .as_ref()
+        .map(|s| s.interval_secs)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    let consecutive_failures = previous.map(|s| s.consecutive_failures).unwrap_or(0) + 1;
+
+    let schedule = FeedSchedule {
+        feed_id,
+        interval_secs,
+        next_due_at: now
+            + Duration::seconds(backoff_delay_secs(interval_secs, consecutive_failures)),
+        consecutive_failures,
+    };
+    store.upsert_feed_schedule(&schedule)?;
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Feed;
+    use crate::store::SqliteStore;
+
+    fn item_at(hour: i64) -> Item {
+        let mut item = Item::new(1, "https://example.com/feed.xml", &format!("entry-{hour}"));
+        item.published_at = Some(
+            DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc)
+                + Duration::hours(hour),
+        );
+        item
+    }
+
+    #[test]
+    fn test_posting_gap_uses_median() {
+        let items = vec![item_at(0), item_at(4), item_at(8), item_at(30)];
+        assert_eq!(posting_gap_secs(&items), Some(4 * 3600));
+        assert_eq!(posting_gap_secs(&[item_at(0)]), None);
+        assert_eq!(posting_gap_secs(&[]), None);
+    }
+
+    #[test]
+    fn test_interval_follows_posting_gap_within_bounds() {
+        let hints = RefreshHints {
+            posting_gap_secs: Some(4 * 3600),
+            ..Default::default()
+        };
+        assert_eq!(interval_after_content(None, &hints), 2 * 3600);
+
+        let busy = RefreshHints {
+            posting_gap_secs: Some(60),
+            ..Default::default()
+        };
+        assert_eq!(interval_after_content(None, &busy), MIN_INTERVAL_SECS);
+
+        let dormant = RefreshHints {
+            posting_gap_secs: Some(30 * 24 * 3600),
+            ..Default::default()
+        };
+        assert_eq!(interval_after_content(None, &dormant), MAX_INTERVAL_SECS);
+
+        assert_eq!(
+            interval_after_content(None, &RefreshHints::default()),
+            DEFAULT_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn test_publisher_and_http_hints_are_a_floor() {
+        let hints = RefreshHints {
+            posting_gap_secs: Some(3600),
+            feed_ttl_secs: Some(3 * 3600),
+            http_max_age_secs: Some(2 * 3600),
+        };
+        assert_eq!(interval_after_content(None, &hints), 3 * 3600);
+
+        let hints = RefreshHints {
+            posting_gap_secs: Some(8 * 3600),
+            http_max_age_secs: Some(600),
+            ..Default::default()
+        };
+        assert_eq!(interval_after_content(None, &hints), 4 * 3600);
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay_secs(3600, 1), 2 * 3600);
+        assert_eq!(backoff_delay_secs(3600, 3), 8 * 3600);
+        assert_eq!(backoff_delay_secs(3600, 40), MAX_INTERVAL_SECS);
+    }
+
+    #[test]
+    fn test_record_failure_then_success_resets_backoff() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let now = Utc::now();
+
+        record_failure(&store, feed_id, now).unwrap();
+        let failed = record_failure(&store, feed_id, now).unwrap();
+        assert_eq!(failed.consecutive_failures, 2);
+        assert_eq!(
+            failed.next_due_at,
+            now + Duration::seconds(4 * DEFAULT_INTERVAL_SECS)
+        );
+
+        let ok = record_success(&store, feed_id, None, now).unwrap();
+        assert_eq!(ok.consecutive_failures, 0);
+        assert_eq!(ok.interval_secs, DEFAULT_INTERVAL_SECS * 5 / 4);
+        assert_eq!(store.get_feed_schedule(feed_id).unwrap(), Some(ok));
+    }
+}
@@ -0,0 +1,186 @@
+//! A small tolerant HTML tokenizer and the article renderer built on it,
+//! shared by the preview pane and `rivulet scrape --test`, plus the plain
+//! text that rules, search snippets and duplicate detection match on. The
+//! tokenizer does not build a tree; callers track nesting.
+
+mod render;
+
+pub use render::{article_links, html_to_text, render_html, strip_tags, RenderedHtml};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token<'a> {
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+    },
+    End(String),
+    /// Raw text; character references are not decoded
+    Text(&'a str),
+}
+
+/// Splits HTML into tags and text. Comments, doctypes and the bodies of
+/// `<script>` and `<style>` are dropped; stray `<` is kept as text.
+pub fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let bytes = html.as_bytes();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+            continue;
+        }
+        let rest = &html[pos..];
+        let next = bytes.get(pos + 1).copied().unwrap_or(b' ');
+        let tag_end = if rest.starts_with("<!--") {
+            Some(rest.find("-->").map_or(html.len(), |end| pos + end + 3))
+        } else if next == b'!' || next == b'?' || next == b'/' || next.is_ascii_alphabetic() {
+            Some(rest.find('>').map_or(html.len(), |end| pos + end + 1))
+        } else {
+            None
+        };
+        let Some(mut tag_end) = tag_end else {
+            pos += 1;
+            continue;
+        };
+
+        if text_start < pos {
+            tokens.push(Token::Text(&html[text_start..pos]));
+        }
+        let inner = html[pos + 1..tag_end].trim_end_matches('>');
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::End(tag_name(name)));
+        } else if next.is_ascii_alphabetic() {
+            let name = tag_name(inner);
+            let attrs = parse_attrs(&inner[name.len().min(inner.len())..]);
+            if name == "script" || name == "style" {
+                let close = format!("</{}", name);
+                tag_end = find_ignore_case(&html[tag_end..], &close)
+                    .map(|offset| tag_end + offset)
+                    .and_then(|close| html[close..].find('>').map(|end| close + end + 1))
+                    .unwrap_or(html.len());
+            } else {
+                tokens.push(Token::Start { name, attrs });
+            }
+        }
+        pos = tag_end;
+        text_start = tag_end;
+    }
+    if text_start < html.len() {
+        tokens.push(Token::Text(&html[text_start..]));
+    }
+    tokens
+}
+
+fn tag_name(inner: &str) -> String {
+    inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+/// `name="value" flag other='x'` → pairs with lowercase names and decoded
+/// values.
+fn parse_attrs(source: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() || c == '/' {
+            chars.next();
+            continue;
+        }
+        let mut end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == '/' {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        let name = source[start..end].to_ascii_lowercase();
+        while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut value = String::new();
+        if chars.peek().is_some_and(|(_, c)| *c == '=') {
+            chars.next();
+            while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                chars.next();
+            }
+            match chars.peek().map(|(_, c)| *c) {
+                Some(quote @ ('"' | '\'')) => {
+                    chars.next();
+                    for (_, c) in chars.by_ref() {
+                        if c == quote {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                _ => {
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        value.push(c);
+                        chars.next();
+                    }
+                }
+            }
+        }
+        if !name.is_empty() {
+            attrs.push((name, decode(&value)));
+        }
+    }
+    attrs
+}
+
+/// Decodes character references such as `&amp;`.
+pub fn decode(text: &str) -> String {
+    html_escape::decode_html_entities(text).into_owned()
+}
+
+/// The value of the attribute `name` (lowercase), if present.
+pub fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_tags_attributes_and_text() {
+        let tokens = tokenize(
+            "<!-- note --><P Class=\"x\" data-n=1 hidden>a &amp; b<script>if (a<b) {}</script>\
+             </p> 1 < 2",
+        );
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Start {
+                    name: "p".into(),
+                    attrs: vec![
+                        ("class".into(), "x".into()),
+                        ("data-n".into(), "1".into()),
+                        ("hidden".into(), String::new()),
+                    ],
+                },
+                Token::Text("a &amp; b"),
+                Token::End("p".into()),
+                Token::Text(" 1 < 2"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,911 @@
+//! Renders article HTML as styled, wrapped text for the preview pane and
+//! the terminal.
+//!
+//! A small tolerant tokenizer feeds a block renderer: paragraphs, headings,
+//! emphasis, inline code, `<pre>` blocks, lists, quotes and simple tables
+//! keep their shape, and links are numbered as footnotes listed at the end.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthStr;
+use url::Url;
+
+use crate::config::ColorConfig;
+use crate::html::{attr, decode, tokenize, Token};
+
+/// Elements whose content is never shown.
+const HIDDEN_ELEMENTS: &[&str] = &[
+    "head", "title", "script", "style", "noscript", "template", "svg", "iframe", "button", "form",
+    "select",
+];
+
+/// Elements that break the flow without a blank line around them.
+const BREAK_ELEMENTS: &[&str] = &[
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "nav",
+    "aside",
+    "address",
+    "details",
+    "summary",
+    "dl",
+    "dt",
+    "dd",
+    "figcaption",
+];
+
+/// Elements separated from their neighbours by a blank line.
+const PARAGRAPH_ELEMENTS: &[&str] = &["p", "figure"];
+
+/// An article rendered for a given width.
+#[derive(Debug, Clone, Default)]
+pub struct RenderedHtml {
+    pub lines: Vec<Line<'static>>,
+    /// Link targets; footnote `[n]` is `links[n - 1]`
+    pub links: Vec<String>,
+}
+
+/// Renders `html` wrapped to `width` columns. Relative links are resolved
+/// against `base_url` (the item's link).
+pub fn render_html(
+    html: &str,
+    base_url: Option<&str>,
+    width: usize,
+    colors: &ColorConfig,
+) -> RenderedHtml {
+    let mut renderer = Renderer::new(width, base_url, true, colors);
+    renderer.render(html);
+    renderer.finish()
+}
+
+/// The links `render_html` numbers, in footnote order.
+pub fn article_links(html: &str, base_url: Option<&str>) -> Vec<String> {
+    render_html(html, base_url, usize::MAX, &ColorConfig::default()).links
+}
+
+/// The text of `html` with paragraphs on their own lines, for diffing.
+pub fn html_to_text(html: &str) -> String {
+    let mut renderer = Renderer::new(usize::MAX, None, false, &ColorConfig::default());
+    renderer.render(html);
+    renderer
+        .finish()
+        .lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The text of `html` with every tag read as a space and the elements the
+/// preview hides left out. For matching and fingerprints, where layout does
+/// not matter.
+pub fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut hidden: Vec<String> = Vec::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Start { name, .. } if HIDDEN_ELEMENTS.contains(&name.as_str()) => {
+                hidden.push(name)
+            }
+            Token::End(name) => {
+                if let Some(index) = hidden.iter().rposition(|open| *open == name) {
+                    hidden.truncate(index);
+                }
+                text.push(' ');
+            }
+            Token::Start { .. } => text.push(' '),
+            Token::Text(raw) if hidden.is_empty() => text.push_str(&decode(raw)),
+            Token::Text(_) => {}
+        }
+    }
+    text
+}
+
+#[derive(Debug)]
+struct List {
+    ordered: bool,
+    next: u64,
+    /// Width of the current item's marker; wrapped lines indent by it
+    indent: usize,
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    rows: Vec<Vec<(String, bool)>>,
+    /// Text of the cell being read, and whether it is a header cell
+    cell: Option<(String, bool)>,
+    /// Tables inside cells are read as plain text
+    nested: usize,
+}
+
+struct Renderer {
+    width: usize,
+    /// Number links and list them at the end
+    footnotes: bool,
+    base_url: Option<Url>,
+    lines: Vec<Line<'static>>,
+    line: Vec<Span<'static>>,
+    line_width: usize,
+    /// Words on the current line, after its prefix
+    has_content: bool,
+    space_pending: bool,
+    /// A blank line is due before the next line; it carries the quote bars
+    /// both lines share
+    blank_pending: Option<usize>,
+    bold: usize,
+    italic: usize,
+    underline: usize,
+    strike: usize,
+    code: usize,
+    heading: Option<u8>,
+    /// Footnote numbers of the open links (`None` for anchors we skip)
+    link_stack: Vec<Option<usize>>,
+    links: Vec<String>,
+    pre: usize,
+    /// Drops the newline that directly follows `<pre>`
+    pre_start: bool,
+    quote: usize,
+    lists: Vec<List>,
+    marker: Option<String>,
+    table: Option<Table>,
+    hidden: Vec<String>,
+    code_color: Color,
+    link_color: Color,
+    /// Footnote markers, rules and quote bars
+    muted_color: Color,
+}
+
+impl Renderer {
+    fn new(width: usize, base_url: Option<&str>, footnotes: bool, colors: &ColorConfig) -> Self {
+        Self {
+            width: width.max(1),
+            footnotes,
+            base_url: base_url.and_then(|url| Url::parse(url).ok()),
+            lines: Vec::new(),
+            line: Vec::new(),
+            line_width: 0,
+            has_content: false,
+            space_pending: false,
+            blank_pending: None,
+            bold: 0,
+            italic: 0,
+            underline: 0,
+            strike: 0,
+            code: 0,
+            heading: None,
+            link_stack: Vec::new(),
+            links: Vec::new(),
+            pre: 0,
+            pre_start: false,
+            quote: 0,
+            lists: Vec::new(),
+            marker: None,
+            table: None,
+            hidden: Vec::new(),
+            code_color: colors.article_code,
+            link_color: colors.article_link,
+            muted_color: colors.article_muted,
+        }
+    }
+
+    fn render(&mut self, html: &str) {
+        // Plain-text entries: keep their line breaks
+        if !html.contains('<') {
+            for line in decode(html).lines() {
+                if line.trim().is_empty() {
+                    self.paragraph_break();
+                } else {
+                    self.text(line);
+                    self.block_break();
+                }
+            }
+            return;
+        }
+
+        for token in tokenize(html) {
+            match token {
+                Token::Start { name, attrs } => self.start(&name, &attrs),
+                Token::End(name) => self.end(&name),
+                Token::Text(text) => {
+                    if self.hidden.is_empty() {
+                        self.text(&decode(text));
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> RenderedHtml {
+        if let Some(table) = self.table.take() {
+            self.render_table(table);
+        }
+        self.block_break();
+        if self.footnotes && !self.links.is_empty() {
+            self.lines.push(Line::from(""));
+            self.lines.push(Line::from(Span::styled(
+                "Links:",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            for (index, link) in self.links.iter().enumerate() {
+                self.lines.push(Line::from(vec![
+                    Span::styled(format!("[{}] ", index + 1), self.muted_style()),
+                    Span::styled(link.clone(), self.link_style()),
+                ]));
+            }
+        }
+        while self
+            .lines
+            .last()
+            .is_some_and(|line| line.spans.iter().all(|s| s.content.trim().is_empty()))
+        {
+            self.lines.pop();
+        }
+        RenderedHtml {
+            lines: self.lines,
+            links: self.links,
+        }
+    }
+
+    fn start(&mut self, name: &str, attrs: &[(String, String)]) {
+        if HIDDEN_ELEMENTS.contains(&name) {
+            self.hidden.push(name.to_string());
+            return;
+        }
+        if !self.hidden.is_empty() {
+            return;
+        }
+        if let Some(table) = self.table.as_mut() {
+            match name {
+                "table" => table.nested += 1,
+                "tr" if table.nested == 0 => {
+                    Self::end_cell(table);
+                    table.rows.push(Vec::new());
+                }
+                "td" | "th" if table.nested == 0 => {
+                    Self::end_cell(table);
+                    if table.rows.is_empty() {
+                        table.rows.push(Vec::new());
+                    }
+                    table.cell = Some((String::new(), name == "th"));
+                }
+                "br" => self.text(" "),
+                "a" => self.start_link(attrs),
+                _ => {}
+            }
+            return;
+        }
+
+        match name {
+            _ if PARAGRAPH_ELEMENTS.contains(&name) => self.paragraph_break(),
+            _ if BREAK_ELEMENTS.contains(&name) => self.block_break(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.paragraph_break();
+                self.heading = name[1..].parse().ok();
+            }
+            "br" => self.line_break(),
+            "hr" => {
+                self.paragraph_break();
+                let rule = "─".repeat(self.width.saturating_sub(self.prefix_width()).min(40));
+                self.word(&rule, self.muted_style());
+                self.paragraph_break();
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.paragraph_break();
+                } else {
+                    self.block_break();
+                }
+                let next = attr(attrs, "start")
+                    .and_then(|start| start.trim().parse().ok())
+                    .unwrap_or(1);
+                self.lists.push(List {
+                    ordered: name == "ol",
+                    next,
+                    indent: 0,
+                });
+            }
+            "li" => {
+                self.block_break();
+                let depth = self.lists.len();
+                if let Some(list) = self.lists.last_mut() {
+                    let marker = if list.ordered {
+                        let number = list.next;
+                        list.next = list.next.saturating_add(1);
+                        format!("{}. ", number)
+                    } else {
+                        let bullet = ["•", "◦", "▪"][(depth - 1) % 3];
+                        format!("{} ", bullet)
+                    };
+                    list.indent = marker.width();
+                    self.marker = Some(marker);
+                }
+            }
+            "blockquote" => {
+                self.paragraph_break();
+                self.quote += 1;
+            }
+            "pre" => {
+                self.paragraph_break();
+                self.pre += 1;
+                self.pre_start = true;
+            }
+            "table" => {
+                self.paragraph_break();
+                self.table = Some(Table::default());
+            }
+            "strong" | "b" => self.bold += 1,
+            "em" | "i" | "cite" | "var" => self.italic += 1,
+            "u" | "ins" => self.underline += 1,
+            "s" | "del" | "strike" => self.strike += 1,
+            "code" | "kbd" | "samp" | "tt" => self.code += 1,
+            "a" => self.start_link(attrs),
+            "img" => {
+                if let Some(alt) = attr(attrs, "alt")
+                    .map(str::trim)
+                    .filter(|alt| !alt.is_empty())
+                {
+                    self.word(&format!("[image: {}]", alt), self.muted_style());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if let Some(index) = self.hidden.iter().rposition(|open| open == name) {
+            self.hidden.truncate(index);
+            return;
+        }
+        if !self.hidden.is_empty() {
+            return;
+        }
+        if let Some(table) = self.table.as_mut() {
+            match name {
+                "table" if table.nested > 0 => table.nested -= 1,
+                "table" => {
+                    let table = self.table.take().unwrap_or_default();
+                    self.render_table(table);
+                    self.paragraph_break();
+                }
+                "td" | "th" if table.nested == 0 => Self::end_cell(table),
+                "a" => self.end_link(),
+                _ => {}
+            }
+            return;
+        }
+
+        match name {
+            _ if PARAGRAPH_ELEMENTS.contains(&name) => self.paragraph_break(),
+            _ if BREAK_ELEMENTS.contains(&name) => self.block_break(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.paragraph_break();
+                self.heading = None;
+            }
+            "ul" | "ol" => {
+                self.block_break();
+                self.lists.pop();
+                self.marker = None;
+                if self.lists.is_empty() {
+                    self.request_blank();
+                }
+            }
+            "li" => {
+                self.block_break();
+                self.marker = None;
+            }
+            "blockquote" => {
+                self.paragraph_break();
+                self.quote = self.quote.saturating_sub(1);
+            }
+            "pre" => {
+                self.block_break();
+                self.pre = self.pre.saturating_sub(1);
+                self.paragraph_break();
+            }
+            "strong" | "b" => self.bold = self.bold.saturating_sub(1),
+            "em" | "i" | "cite" | "var" => self.italic = self.italic.saturating_sub(1),
+            "u" | "ins" => self.underline = self.underline.saturating_sub(1),
+            "s" | "del" | "strike" => self.strike = self.strike.saturating_sub(1),
+            "code" | "kbd" | "samp" | "tt" => self.code = self.code.saturating_sub(1),
+            "a" => self.end_link(),
+            _ => {}
+        }
+    }
+
+    fn start_link(&mut self, attrs: &[(String, String)]) {
+        if !self.footnotes {
+            self.link_stack.push(None);
+            return;
+        }
+        let target = attr(attrs, "href")
+            .map(str::trim)
+            .filter(|href| !href.is_empty() && !href.starts_with('#'))
+            .and_then(|href| match &self.base_url {
+                Some(base) => base.join(href).ok().map(String::from),
+                None => Some(href.to_string()),
+            })
+            .filter(|href| !href.to_ascii_lowercase().starts_with("javascript:"));
+        let number =
+            target.map(
+                |target| match self.links.iter().position(|link| *link == target) {
+                    Some(index) => index + 1,
+                    None => {
+                        self.links.push(target);
+                        self.links.len()
+                    }
+                },
+            );
+        self.link_stack.push(number);
+    }
+
+    fn end_link(&mut self) {
+        if let Some(Some(number)) = self.link_stack.pop() {
+            let marker = format!("[{}]", number);
+            match self.table.as_mut().and_then(|table| table.cell.as_mut()) {
+                Some((cell, _)) => cell.push_str(&marker),
+                None => self.glued(&marker, self.muted_style()),
+            }
+        }
+    }
+
+    fn end_cell(table: &mut Table) {
+        if let Some((text, header)) = table.cell.take() {
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            match table.rows.last_mut() {
+                Some(row) => row.push((text, header)),
+                None => table.rows.push(vec![(text, header)]),
+            }
+        }
+    }
+
+    fn style(&self) -> Style {
+        let mut style = Style::default();
+        if self.code > 0 || self.pre > 0 {
+            style = style.fg(self.code_color);
+        }
+        if self.link_stack.iter().any(Option::is_some) {
+            style = self.link_style();
+        }
+        if self.bold > 0 || self.heading.is_some() {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.italic > 0 || self.quote > 0 {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if self.underline > 0 || self.heading.is_some_and(|level| level <= 2) {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        if self.strike > 0 {
+            style = style.add_modifier(Modifier::CROSSED_OUT);
+        }
+        style
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(cell) = self.table.as_mut().and_then(|table| table.cell.as_mut()) {
+            cell.0.push_str(text);
+            return;
+        }
+        if self.table.is_some() {
+            // Text between cells
+            return;
+        }
+        if self.pre > 0 {
+            return self.preformatted(text);
+        }
+
+        let style = self.style();
+        if text.starts_with(|c: char| c.is_ascii_whitespace()) {
+            self.space_pending = true;
+        }
+        for (index, word) in text.split_ascii_whitespace().enumerate() {
+            if index > 0 {
+                self.space_pending = true;
+            }
+            self.word(word, style);
+        }
+        if text.ends_with(|c: char| c.is_ascii_whitespace()) {
+            self.space_pending = true;
+        }
+    }
+
+    fn preformatted(&mut self, text: &str) {
+        let style = self.style();
+        let mut text = text;
+        if std::mem::take(&mut self.pre_start) {
+            text = text.strip_prefix("\r\n").unwrap_or(text);
+            text = text.strip_prefix('\n').unwrap_or(text);
+        }
+        for (index, segment) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.force_newline();
+            }
+            let segment = segment.trim_end_matches('\r').replace('\t', "    ");
+            if !segment.is_empty() {
+                self.start_line();
+                self.line_width += segment.width();
+                self.line.push(Span::styled(segment, style));
+                self.has_content = true;
+            }
+        }
+    }
+
+    /// Adds a word, wrapping first if it would not fit.
+    fn word(&mut self, word: &str, style: Style) {
+        let word = word.replace('\u{a0}', " ");
+        let word_width = word.width();
+        let space = self.has_content && self.space_pending;
+        if self.has_content && self.line_width + usize::from(space) + word_width > self.width {
+            self.flush_line();
+        }
+        self.start_line();
+        if self.has_content && self.space_pending {
+            // Keep underlines continuous between words of one run
+            let space_style = match self.line.last() {
+                Some(previous) if previous.style == style => style,
+                _ => Style::default(),
+            };
+            self.line.push(Span::styled(" ", space_style));
+            self.line_width += 1;
+        }
+        self.space_pending = false;
+        self.line_width += word_width;
+        self.line.push(Span::styled(word, style));
+        self.has_content = true;
+    }
+
+    /// Appends text to the previous word, e.g. a footnote marker.
+    fn glued(&mut self, text: &str, style: Style) {
+        let pending = std::mem::take(&mut self.space_pending);
+        self.word(text, style);
+        self.space_pending = pending;
+    }
+
+    fn prefix_width(&self) -> usize {
+        self.quote * 2 + self.lists.iter().map(|list| list.indent).sum::<usize>()
+    }
+
+    /// Writes the quote bars and list indent at the start of a line.
+    fn start_line(&mut self) {
+        if !self.line.is_empty() {
+            return;
+        }
+        if let Some(depth) = self.blank_pending.take().filter(|_| !self.at_blank_line()) {
+            let mut blank = Vec::new();
+            for _ in 0..depth.min(self.quote) {
+                blank.push(Span::styled("│ ", self.muted_style()));
+            }
+            self.lines.push(Line::from(blank));
+        }
+        for _ in 0..self.quote {
+            self.line.push(Span::styled("│ ", self.muted_style()));
+        }
+        let depth = self.lists.len();
+        for (index, list) in self.lists.iter().enumerate() {
+            let text = match &self.marker {
+                Some(marker) if index + 1 == depth => marker.clone(),
+                _ => " ".repeat(list.indent),
+            };
+            if !text.is_empty() {
+                self.line.push(Span::styled(text, self.muted_style()));
+            }
+        }
+        self.marker = None;
+        self.line_width = self.prefix_width();
+    }
+
+    fn at_blank_line(&self) -> bool {
+        self.lines.last().is_none_or(|line| {
+            line.spans
+                .iter()
+                .all(|s| s.content.trim_matches(['│', ' ']).is_empty())
+        })
+    }
+
+    fn flush_line(&mut self) {
+        if !self.line.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.line)));
+        }
+        self.line_width = 0;
+        self.has_content = false;
+        self.space_pending = false;
+    }
+
+    fn block_break(&mut self) {
+        if self.has_content {
+            self.flush_line();
+        }
+        self.space_pending = false;
+    }
+
+    fn paragraph_break(&mut self) {
+        self.block_break();
+        self.request_blank();
+    }
+
+    fn request_blank(&mut self) {
+        if !self.lines.is_empty() {
+            let depth = self.blank_pending.map_or(self.quote, |d| d.min(self.quote));
+            self.blank_pending = Some(depth);
+        }
+    }
+
+    /// `<br>`: ends the line, leaving an empty one if it already was.
+    fn line_break(&mut self) {
+        if self.has_content {
+            self.flush_line();
+        } else if !self.lines.is_empty() {
+            self.force_newline();
+        }
+    }
+
+    fn force_newline(&mut self) {
+        self.start_line();
+        self.lines.push(Line::from(std::mem::take(&mut self.line)));
+        self.line_width = 0;
+        self.has_content = false;
+    }
+
+    /// Lays rows out in padded columns, or as one line per row when the
+    /// columns would not fit.
+    fn render_table(&mut self, mut table: Table) {
+        Self::end_cell(&mut table);
+        let rows: Vec<_> = table
+            .rows
+            .into_iter()
+            .filter(|row| !row.is_empty())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let mut widths = vec![0; columns];
+        for row in &rows {
+            for (index, (text, _)) in row.iter().enumerate() {
+                widths[index] = widths[index].max(text.width());
+            }
+        }
+        let separator = " │ ";
+        let total = widths.iter().sum::<usize>() + separator.width() * (columns - 1);
+        let fits = self.prefix_width() + total <= self.width;
+
+        self.block_break();
+        for (row_index, row) in rows.iter().enumerate() {
+            let is_header = row.iter().all(|(_, header)| *header);
+            let cell_style = if is_header {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            if fits {
+                self.start_line();
+                for (index, width) in widths.iter().enumerate() {
+                    if index > 0 {
+                        self.line.push(Span::styled(separator, self.muted_style()));
+                    }
+                    let text = row.get(index).map_or("", |(text, _)| text.as_str());
+                    let padding = width - text.width();
+                    self.line.push(Span::styled(
+                        format!("{}{}", text, " ".repeat(padding)),
+                        cell_style,
+                    ));
+                }
+                self.has_content = true;
+                self.flush_line();
+                if is_header && row_index + 1 < rows.len() {
+                    let rule: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+                    self.start_line();
+                    self.line
+                        .push(Span::styled(rule.join("─┼─"), self.muted_style()));
+                    self.has_content = true;
+                    self.flush_line();
+                }
+            } else {
+                for (index, (text, _)) in row.iter().enumerate() {
+                    if index > 0 {
+                        self.space_pending = true;
+                        self.word("│", self.muted_style());
+                    }
+                    for word in text.split_whitespace() {
+                        self.space_pending = true;
+                        self.word(word, cell_style);
+                    }
+                }
+                self.block_break();
+            }
+        }
+    }
+
+    fn link_style(&self) -> Style {
+        Style::default()
+            .fg(self.link_color)
+            .add_modifier(Modifier::UNDERLINED)
+    }
+
+    fn muted_style(&self) -> Style {
+        Style::default().fg(self.muted_color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_lines(rendered: &RenderedHtml) -> Vec<String> {
+        rendered
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_paragraphs_headings_and_wrapping() {
+        let rendered = render_html(
+            "<h2>Release &amp; notes</h2><p>The quick brown fox jumps over the lazy dog.</p>\
+             <p>Second&nbsp;para</p>",
+            None,
+            20,
+            &ColorConfig::default(),
+        );
+        assert_eq!(
+            text_lines(&rendered),
+            vec![
+                "Release & notes",
+                "",
+                "The quick brown fox",
+                "jumps over the lazy",
+                "dog.",
+                "",
+                "Second para",
+            ]
+        );
+        let heading = &rendered.lines[0].spans[0];
+        assert!(heading.style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_lists_quotes_and_pre_keep_their_shape() {
+        let rendered = render_html(
+            "<ul><li>one item that wraps</li><li>two<ol start=\"3\"><li>nested</li></ol></li></ul>\
+             <blockquote><p>quoted</p></blockquote>\
+             <pre><code>fn main() {\n    println!();\n}</code></pre><p>after <code>x</code></p>",
+            None,
+            16,
+            &ColorConfig::default(),
+        );
+        assert_eq!(
+            text_lines(&rendered),
+            vec![
+                "• one item that",
+                "  wraps",
+                "• two",
+                "  3. nested",
+                "",
+                "│ quoted",
+                "",
+                "fn main() {",
+                "    println!();",
+                "}",
+                "",
+                "after x",
+            ]
+        );
+        let code = rendered.lines[11].spans.last().unwrap();
+        assert_eq!(code.style.fg, Some(Color::LightYellow));
+    }
+
+    #[test]
+    fn test_colors_come_from_the_config() {
+        let colors = ColorConfig {
+            article_code: Color::Magenta,
+            article_link: Color::Green,
+            ..Default::default()
+        };
+        let rendered = render_html(
+            "<p><code>x</code> <a href=\"/a\">y</a></p>",
+            None,
+            80,
+            &colors,
+        );
+        let spans = &rendered.lines[0].spans;
+        assert_eq!(spans[0].style.fg, Some(Color::Magenta));
+        let link = spans.iter().find(|span| span.content == "y").unwrap();
+        assert_eq!(link.style.fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn test_links_become_numbered_footnotes() {
+        let rendered = render_html(
+            "<p>See <a href=\"/docs\">the docs</a>, <a href=\"#top\">top</a> and \
+             <a href='https://example.org/'>this</a> or <a href=\"/docs\">again</a>.</p>\
+             <script>var x = '<p>no</p>';</script><!-- <p>hidden</p> -->",
+            Some("https://example.com/post/1"),
+            80,
+            &ColorConfig::default(),
+        );
+        assert_eq!(
+            rendered.links,
+            vec!["https://example.com/docs", "https://example.org/"]
+        );
+        assert_eq!(
+            text_lines(&rendered),
+            vec![
+                "See the docs[1], top and this[2] or again[1].",
+                "",
+                "Links:",
+                "[1] https://example.com/docs",
+                "[2] https://example.org/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_list_start_does_not_overflow() {
+        let html = format!("<ol start=\"{}\"><li>a</li><li>b</li></ol>", u64::MAX);
+        assert_eq!(
+            text_lines(&render_html(&html, None, 80, &ColorConfig::default())),
+            vec![format!("{}. a", u64::MAX), format!("{}. b", u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn test_tables_align_columns_or_fall_back_to_rows() {
+        let html = "<table><tr><th>Name</th><th>Size</th></tr>\
+                    <tr><td>alpha</td><td>1</td></tr><tr><td>b</td><td>200</td></tr></table>";
+        assert_eq!(
+            text_lines(&render_html(html, None, 40, &ColorConfig::default())),
+            vec!["Name  │ Size", "──────┼─────", "alpha │ 1", "b     │ 200"]
+        );
+        assert_eq!(
+            text_lines(&render_html(html, None, 8, &ColorConfig::default())),
+            vec!["Name │", "Size", "alpha │", "1", "b │ 200"]
+        );
+    }
+
+    #[test]
+    fn test_plain_text_and_diff_text_keep_line_breaks() {
+        assert_eq!(
+            text_lines(&render_html(
+                "first line\nsecond\n\nthird",
+                None,
+                80,
+                &ColorConfig::default()
+            )),
+            vec!["first line", "second", "", "third"]
+        );
+        assert_eq!(
+            html_to_text("<p>One.</p><p>Two <a href=\"x\">link</a></p>"),
+            "One.\n\nTwo link"
+        );
+    }
+
+    #[test]
+    fn test_strip_tags_skips_scripts_styles_and_hidden_elements() {
+        assert_eq!(
+            strip_tags(
+                "<style>p { color: red }</style><p>Fish &amp; chips</p>\
+                 <script>track(\"<b>\")</script><noscript>Enable JS</noscript><b>x</b>"
+            )
+            .split_whitespace()
+            .collect::<Vec<_>>(),
+            vec!["Fish", "&", "chips", "x"]
+        );
+    }
+}
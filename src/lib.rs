@@ -92,6 +92,25 @@ pub mod fetcher;
 /// into unified [`Item`](domain::Item) structs.
 pub mod normalizer;
 
+/// Tolerant HTML tokenizer and the article renderer used by the preview
+/// pane and `rivulet scrape --test`.
+pub mod html;
+
+/// OPML subscription lists.
+///
+/// - [`parse`](opml::parse): quick-xml based reader returning the outline tree
+/// - [`OpmlDocument::feeds`](opml::OpmlDocument::feeds): flattened feeds plus
+///   a report of skipped outlines
+/// - [`render`](opml::render): OPML 2.0 writer used by `rivulet export`
+pub mod opml;
+
+/// Keyword rules that act on incoming items.
+///
+/// - [`RuleSet`](rules::RuleSet): compiled `[[rules]]` from config.toml
+/// - [`RuleSet::apply`](rules::RuleSet::apply): runs matching actions after a
+///   feed refresh inserts items
+pub mod rules;
+
 /// SQLite persistence layer.
 ///
 /// - [`Store`](store::Store): Trait defining storage operations
@@ -111,9 +130,13 @@ pub mod tui;
 
 /// Web scraping module for full article content extraction.
 ///
-/// Uses headless Chrome via chromiumoxide to fetch full article content
-/// from web pages when RSS feeds only provide summaries.
+/// Fetches full article content from web pages when RSS feeds only provide
+/// summaries. Pages are fetched over HTTP and the article is found by a
+/// Readability-style extractor; headless Chrome (via chromiumoxide) is used
+/// only for sites configured to need JavaScript.
 ///
+/// - [`ArticleScraper`](scraper::ArticleScraper): Reader with Chrome fallback
+/// - [`HttpScraper`](scraper::HttpScraper): Browser-free reader-mode scraper
 /// - [`ChromeScraper`](scraper::ChromeScraper): Chrome-based scraper
 /// - [`ScraperConfig`](scraper::ScraperConfig): Configuration options
 /// - [`Scraper`](scraper::Scraper): Async trait for scraping implementations
@@ -4,9 +4,12 @@ use clap::Parser;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 use rivulet::app::AppContext;
-use rivulet::cli::{commands, AuthAction, Cli, Commands, DaemonAction};
+use rivulet::cli::{
+    commands, AuthAction, Cli, Commands, DaemonAction, RulesAction, SearchFeedAction, TagAction,
+};
 use rivulet::config::Config;
 use rivulet::daemon::{Daemon, DaemonConfig};
+use rivulet::rules::RuleSet;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -19,8 +22,14 @@ async fn main() -> anyhow::Result<()> {
         Config::default()
     });
 
+    let rules = config.rule_set().unwrap_or_else(|e| {
+        tracing::warn!("Ignoring [[rules]]: {}", e);
+        RuleSet::default()
+    });
+
     // Create app context with scraper enabled based on config
-    let ctx = AppContext::with_scraper_config(None, cli.workers, Some(config.scraper.clone()))?;
+    let ctx = AppContext::with_scraper_config(None, cli.workers, Some(config.scraper.clone()))?
+        .with_rules(rules);
 
     match cli.command {
         Commands::Init { force } => {
@@ -36,6 +45,13 @@ async fn main() -> anyhow::Result<()> {
         Commands::Import { path } => {
             commands::import_opml(&ctx, &path).await?;
         }
+        Commands::Export { output } => {
+            commands::export_opml(&ctx, output.as_deref())?;
+        }
+        Commands::Health { feed, stale_days } => match feed {
+            Some(url) => commands::feed_fetch_log(&ctx, &url)?,
+            None => commands::feed_health(&ctx, stale_days)?,
+        },
         Commands::Update => {
             commands::update_feeds(&ctx).await?;
         }
@@ -46,13 +62,17 @@ async fn main() -> anyhow::Result<()> {
             queued,
             saved,
             archived,
+            search_feed,
+            format,
         } => {
             let filter =
                 commands::list_filter_from_flags(unread, starred, queued, saved, archived)?;
-            if items || filter.is_some() {
-                commands::list_items(&ctx, filter)?;
+            if let Some(name) = search_feed {
+                commands::list_search_feed(&ctx, &name, filter, format)?;
+            } else if items || filter.is_some() {
+                commands::list_items(&ctx, filter, format)?;
             } else {
-                commands::list_feeds(&ctx)?;
+                commands::list_feeds(&ctx, format)?;
             }
         }
         Commands::Search {
@@ -63,12 +83,70 @@ async fn main() -> anyhow::Result<()> {
             queued,
             saved,
             archived,
+            format,
         } => {
             let filter =
                 commands::list_filter_from_flags(unread, starred, queued, saved, archived)?
                     .unwrap_or(rivulet::store::ItemListFilter::All);
-            commands::search_items(&ctx, &query, filter, limit)?;
+            commands::search_items(&ctx, &query, filter, limit, format)?;
         }
+        Commands::Mark {
+            item_id,
+            feed,
+            search,
+            older_than,
+            state,
+        } => {
+            commands::mark_items(
+                &ctx,
+                item_id,
+                feed.as_deref(),
+                search,
+                older_than.as_deref(),
+                &state,
+            )?;
+        }
+        Commands::Prune { dry_run } => {
+            commands::prune(&ctx, &config.retention, dry_run)?;
+        }
+        Commands::Tag { action } => match action {
+            TagAction::Add { item_id, tags } => {
+                commands::tag_item(&ctx, &item_id, &tags, false)?;
+            }
+            TagAction::Remove { item_id, tags } => {
+                commands::tag_item(&ctx, &item_id, &tags, true)?;
+            }
+            TagAction::List { tag, format } => {
+                commands::list_tags(&ctx, tag.as_deref(), format)?;
+            }
+        },
+        Commands::SearchFeed { action } => match action {
+            SearchFeedAction::Add {
+                name,
+                query,
+                unread,
+                starred,
+                queued,
+                saved,
+                archived,
+            } => {
+                let filter =
+                    commands::list_filter_from_flags(unread, starred, queued, saved, archived)?
+                        .unwrap_or(rivulet::store::ItemListFilter::All);
+                commands::add_search_feed(&ctx, &name, &query, filter)?;
+            }
+            SearchFeedAction::Remove { name } => {
+                commands::remove_search_feed(&ctx, &name)?;
+            }
+            SearchFeedAction::List { format } => {
+                commands::list_search_feeds(&ctx, format)?;
+            }
+        },
+        Commands::Rules { action } => match action {
+            RulesAction::Test { feed } => {
+                commands::rules_test(&ctx, &config.rule_set()?, feed.as_deref())?;
+            }
+        },
         Commands::Tui => {
             rivulet::tui::run(Arc::new(ctx), Arc::new(config)).await?;
         }
@@ -78,16 +156,29 @@ async fn main() -> anyhow::Result<()> {
             concurrency,
             visible,
             auth_profile,
+            test,
         } => {
-            commands::scrape_content(
-                &ctx,
-                feed.as_deref(),
-                limit,
-                concurrency,
-                visible,
-                auth_profile.as_deref(),
-            )
-            .await?;
+            if let Some(url) = test {
+                commands::scrape_test(
+                    &ctx,
+                    &config.scraper,
+                    &url,
+                    visible,
+                    auth_profile.as_deref(),
+                )
+                .await?;
+            } else {
+                commands::scrape_content(
+                    &ctx,
+                    &config.scraper,
+                    feed.as_deref(),
+                    limit,
+                    concurrency,
+                    visible,
+                    auth_profile.as_deref(),
+                )
+                .await?;
+            }
         }
         Commands::Auth { action } => match action {
             AuthAction::Add {
@@ -100,8 +191,8 @@ async fn main() -> anyhow::Result<()> {
             AuthAction::Check { name, url, visible } => {
                 commands::auth_check(&ctx, &name, url.as_deref(), visible).await?;
             }
-            AuthAction::List => {
-                commands::auth_list(&ctx)?;
+            AuthAction::List { format } => {
+                commands::auth_list(&ctx, format)?;
             }
         },
         Commands::Daemon { action } => {
@@ -119,6 +210,7 @@ async fn main() -> anyhow::Result<()> {
                         update_interval_secs: interval_secs,
                         update_on_start: !no_initial_update,
                         log_file: log.clone(),
+                        retention: config.retention.clone(),
                     };
 
                     if foreground {
@@ -216,8 +308,9 @@ fn init_tracing(command: &Commands) -> anyhow::Result<()> {
     if matches!(command, Commands::Tui) {
         init_tui_tracing()
     } else {
+        // Logs go to stderr so `--format json|csv` output can be piped.
         tracing_subscriber::registry()
-            .with(fmt::layer())
+            .with(fmt::layer().with_writer(std::io::stderr))
             .with(env_filter("rivulet=info"))
             .init();
         Ok(())
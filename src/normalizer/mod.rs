@@ -1,14 +1,17 @@
 use chrono::Utc;
+use feed_rs::model::{Entry, Link};
 use feed_rs::parser;
 use html_escape::decode_html_entities;
 
 use crate::app::{Result, RivuletError};
-use crate::domain::Item;
+use crate::domain::{Enclosure, Item};
 
 #[derive(Debug, Clone)]
 pub struct FeedMeta {
     pub title: Option<String>,
     pub description: Option<String>,
+    /// Publisher's minimum refresh interval, from `<ttl>` or `sy:updatePeriod`.
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -33,7 +36,13 @@ impl Normalizer {
     ) -> Result<(FeedMeta, Vec<Item>)> {
         let feed = parser::parse(body).map_err(|e| RivuletError::FeedParse(e.to_string()))?;
 
+        let ttl_secs = feed
+            .ttl
+            .map(|minutes| u64::from(minutes) * 60)
+            .or_else(|| syndication_interval_secs(body));
+
         let meta = FeedMeta {
+            ttl_secs,
             title: feed
                 .title
                 .map(|t| decode_html_entities(&t.content).to_string()),
@@ -47,7 +56,12 @@ impl Normalizer {
             .into_iter()
             .map(|entry| {
                 let entry_id = entry.id.clone();
-                let link = entry.links.first().map(|l| l.href.clone());
+                let link = entry
+                    .links
+                    .iter()
+                    .find(|l| !is_enclosure_link(l))
+                    .or(entry.links.first())
+                    .map(|l| l.href.clone());
                 let entry_id_for_hash = if entry_id.is_empty() {
                     link.clone().unwrap_or_default()
                 } else {
@@ -55,6 +69,8 @@ impl Normalizer {
                 };
 
                 let mut item = Item::new(feed_id, feed_url, &entry_id_for_hash);
+                item.enclosures = entry_enclosures(&entry);
+                item.categories = entry_categories(&entry);
 
                 item.title = entry
                     .title
@@ -72,6 +88,7 @@ impl Normalizer {
                     .published
                     .or(entry.updated)
                     .map(|dt| dt.with_timezone(&Utc));
+                item.updated_at = entry.updated.map(|dt| dt.with_timezone(&Utc));
 
                 item
             })
@@ -81,6 +98,97 @@ impl Normalizer {
     }
 }
 
+/// RSS `<enclosure>` and Media RSS arrive as media objects; Atom
+/// `rel="enclosure"` links and JSON Feed attachments arrive as links.
+fn entry_enclosures(entry: &Entry) -> Vec<Enclosure> {
+    let mut enclosures: Vec<Enclosure> = Vec::new();
+    let mut push = |enclosure: Enclosure| {
+        if !enclosures.iter().any(|e| e.url == enclosure.url) {
+            enclosures.push(enclosure);
+        }
+    };
+
+    for media in &entry.media {
+        for content in &media.content {
+            let Some(url) = &content.url else {
+                continue;
+            };
+            push(Enclosure {
+                url: url.to_string(),
+                mime_type: content.content_type.as_ref().map(|t| t.to_string()),
+                length: content.size.and_then(|s| i64::try_from(s).ok()),
+                duration_secs: content
+                    .duration
+                    .or(media.duration)
+                    .and_then(|d| i64::try_from(d.as_secs()).ok()),
+            });
+        }
+    }
+
+    for link in entry.links.iter().filter(|l| is_enclosure_link(l)) {
+        push(Enclosure {
+            url: link.href.clone(),
+            mime_type: link.media_type.clone(),
+            length: link.length.and_then(|l| i64::try_from(l).ok()),
+            duration_secs: None,
+        });
+    }
+
+    enclosures
+}
+
+/// JSON Feed attachments have no `rel`, only a MIME type.
+fn is_enclosure_link(link: &Link) -> bool {
+    match link.rel.as_deref() {
+        Some(rel) => rel.eq_ignore_ascii_case("enclosure"),
+        None => link.media_type.as_deref().is_some_and(|t| {
+            let t = t.to_ascii_lowercase();
+            t != "text/html" && t != "application/xhtml+xml"
+        }),
+    }
+}
+
+fn entry_categories(entry: &Entry) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for category in &entry.categories {
+        let name = category.label.as_deref().unwrap_or(&category.term);
+        let name = decode_html_entities(name.trim()).to_string();
+        if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Reads the RSS 1.0 syndication module (`sy:updatePeriod` /
+/// `sy:updateFrequency`), which feed-rs does not expose.
+fn syndication_interval_secs(body: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(body).ok()?;
+    let period = element_text(text, "sy:updatePeriod")?;
+    let period_secs: u64 = match period.to_ascii_lowercase().as_str() {
+        "hourly" => 60 * 60,
+        "daily" => 24 * 60 * 60,
+        "weekly" => 7 * 24 * 60 * 60,
+        "monthly" => 30 * 24 * 60 * 60,
+        "yearly" => 365 * 24 * 60 * 60,
+        _ => return None,
+    };
+    let frequency = element_text(text, "sy:updateFrequency")
+        .and_then(|f| f.parse::<u64>().ok())
+        .filter(|f| *f > 0)
+        .unwrap_or(1);
+
+    Some(period_secs / frequency)
+}
+
+fn element_text<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{name}>");
+    let close = format!("</{name}>");
+    let start = text.find(&open)? + open.len();
+    let end = start + text[start..].find(&close)?;
+    Some(text[start..end].trim())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +367,129 @@ mod tests {
         assert_eq!(items[0].summary, Some("This is item 1".into()));
         assert_eq!(items[1].summary, Some("This is item 2".into()));
     }
+
+    #[test]
+    fn test_normalize_reads_refresh_hints() {
+        let normalizer = Normalizer::new();
+
+        let ttl = RSS_SAMPLE.replace("<channel>", "<channel><ttl>90</ttl>");
+        let (meta, _) = normalizer
+            .normalize(1, "https://example.com/feed.xml", ttl.as_bytes())
+            .unwrap();
+        assert_eq!(meta.ttl_secs, Some(90 * 60));
+
+        let sy = RSS_SAMPLE
+            .replace(
+                "<rss version=\"2.0\">",
+                "<rss version=\"2.0\" xmlns:sy=\"http://purl.org/rss/1.0/modules/syndication/\">",
+            )
+            .replace(
+                "<channel>",
+                "<channel><sy:updatePeriod>daily</sy:updatePeriod><sy:updateFrequency>4</sy:updateFrequency>",
+            );
+        let (meta, _) = normalizer
+            .normalize(1, "https://example.com/feed.xml", sy.as_bytes())
+            .unwrap();
+        assert_eq!(meta.ttl_secs, Some(6 * 60 * 60));
+
+        let (meta, _) = normalizer
+            .normalize(1, "https://example.com/feed.xml", RSS_SAMPLE.as_bytes())
+            .unwrap();
+        assert_eq!(meta.ttl_secs, None);
+    }
+
+    #[test]
+    fn test_normalize_rss_enclosure_and_categories() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Podcast</title>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <link>https://example.com/ep1</link>
+      <category>Rust</category>
+      <category>Interviews</category>
+      <category>rust</category>
+      <itunes:duration>1:02:03</itunes:duration>
+      <enclosure url="https://cdn.example.com/ep1.mp3" length="12345678" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+        let (_, items) = Normalizer::new()
+            .normalize(1, "https://example.com/feed.xml", xml.as_bytes())
+            .unwrap();
+
+        assert_eq!(items[0].link, Some("https://example.com/ep1".into()));
+        assert_eq!(
+            items[0].enclosures,
+            vec![Enclosure {
+                url: "https://cdn.example.com/ep1.mp3".into(),
+                mime_type: Some("audio/mpeg".into()),
+                length: Some(12_345_678),
+                duration_secs: Some(3723),
+            }]
+        );
+        assert_eq!(items[0].categories, vec!["Rust", "Interviews"]);
+    }
+
+    #[test]
+    fn test_normalize_atom_enclosure_link() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Video</title>
+  <entry>
+    <title>Talk</title>
+    <id>talk-1</id>
+    <updated>2024-01-01T00:00:00Z</updated>
+    <link rel="enclosure" type="video/mp4" length="2048" href="https://cdn.example.com/talk.mp4"/>
+    <link rel="alternate" href="https://example.com/talk"/>
+    <category term="conf" label="Conference Talks"/>
+  </entry>
+</feed>"#;
+        let (_, items) = Normalizer::new()
+            .normalize(1, "https://example.com/feed.atom", xml.as_bytes())
+            .unwrap();
+
+        assert_eq!(items[0].link, Some("https://example.com/talk".into()));
+        assert_eq!(items[0].enclosures.len(), 1);
+        assert_eq!(
+            items[0].enclosures[0].url,
+            "https://cdn.example.com/talk.mp4"
+        );
+        assert!(items[0].enclosures[0].is_video());
+        assert_eq!(items[0].enclosures[0].length, Some(2048));
+        assert_eq!(items[0].categories, vec!["Conference Talks"]);
+    }
+
+    #[test]
+    fn test_normalize_json_feed_attachments() {
+        let json = r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "JSON Podcast",
+  "items": [{
+    "id": "1",
+    "url": "https://example.com/1",
+    "content_text": "Show notes",
+    "tags": ["audio"],
+    "attachments": [{
+      "url": "https://cdn.example.com/1.m4a",
+      "mime_type": "audio/x-m4a",
+      "size_in_bytes": 1000
+    }]
+  }]
+}"#;
+        let (_, items) = Normalizer::new()
+            .normalize(1, "https://example.com/feed.json", json.as_bytes())
+            .unwrap();
+
+        assert_eq!(items[0].link, Some("https://example.com/1".into()));
+        assert_eq!(items[0].enclosures.len(), 1);
+        assert_eq!(
+            items[0].enclosures[0].mime_type.as_deref(),
+            Some("audio/x-m4a")
+        );
+        assert_eq!(items[0].enclosures[0].length, Some(1000));
+        assert_eq!(items[0].categories, vec!["audio"]);
+    }
 }
@@ -0,0 +1,593 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use chrono::Utc;
+use quick_xml::encoding::Decoder;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::app::{Result, RivuletError};
+use crate::domain::{Category, Feed};
+
+/// A single `<outline>` element with the attributes Rivulet understands.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpmlOutline {
+    pub text: Option<String>,
+    pub title: Option<String>,
+    pub outline_type: Option<String>,
+    pub xml_url: Option<String>,
+    pub html_url: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub children: Vec<OpmlOutline>,
+}
+
+impl OpmlOutline {
+    /// The human-readable name: `title`, falling back to `text`.
+    pub fn label(&self) -> Option<&str> {
+        self.title
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+            .or_else(|| self.text.as_deref().filter(|s| !s.trim().is_empty()))
+    }
+
+    fn from_element(element: &BytesStart<'_>, decoder: Decoder) -> Self {
+        let mut outline = OpmlOutline::default();
+        for attr in element.attributes().with_checks(false).flatten() {
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_ascii_lowercase();
+            let value = attribute_value(&attr, decoder);
+            match key.as_str() {
+                "text" => outline.text = Some(value),
+                "title" => outline.title = Some(value),
+                "type" => outline.outline_type = Some(value),
+                "xmlurl" => outline.xml_url = Some(value),
+                "htmlurl" => outline.html_url = Some(value),
+                "description" => outline.description = Some(value),
+                "category" => outline.category = Some(value),
+                _ => {}
+            }
+        }
+        outline
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpmlDocument {
+    pub title: Option<String>,
+    pub outlines: Vec<OpmlOutline>,
+}
+
+/// A subscribable feed flattened out of the outline tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpmlFeed {
+    pub title: String,
+    pub url: String,
+    pub html_url: Option<String>,
+    pub description: Option<String>,
+    /// Folder names from the outermost to the innermost enclosing outline.
+    pub category_path: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    MissingTitle,
+    EmptyUrl,
+    InvalidUrl(String),
+    UnsupportedType(String),
+    EmptyFolder,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::MissingTitle => write!(f, "feed has no title or text"),
+            SkipReason::EmptyUrl => write!(f, "xmlUrl is empty"),
+            SkipReason::InvalidUrl(url) => write!(f, "xmlUrl is not an http(s) URL: {}", url),
+            SkipReason::UnsupportedType(kind) => {
+                write!(f, "outline type \"{}\" has no xmlUrl", kind)
+            }
+            SkipReason::EmptyFolder => write!(f, "no xmlUrl and no nested outlines"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedOutline {
+    pub label: String,
+    pub category_path: Vec<String>,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpmlImport {
+    pub feeds: Vec<OpmlFeed>,
+    pub skipped: Vec<SkippedOutline>,
+}
+
+impl OpmlDocument {
+    /// Flatten the outline tree into feeds plus a report of the outlines that
+    /// could not be imported. Outlines without an `xmlUrl` but with children
+    /// are folders. A feed that is not nested in any folder falls back to its
+    /// OPML `category` attribute (first entry, `/`-separated) for its folder.
+    pub fn feeds(&self) -> OpmlImport {
+        let mut import = OpmlImport::default();
+        let mut path = Vec::new();
+        for outline in &self.outlines {
+            collect_feeds(outline, &mut path, &mut import);
+        }
+        import
+    }
+}
+
+fn collect_feeds(outline: &OpmlOutline, path: &mut Vec<String>, import: &mut OpmlImport) {
+    let label = outline.label().map(str::to_string);
+
+    if let Some(url) = &outline.xml_url {
+        let url = url.trim();
+        let skip = |reason| SkippedOutline {
+            label: label.clone().unwrap_or_else(|| url.to_string()),
+            category_path: path.clone(),
+            reason,
+        };
+
+        if url.is_empty() {
+            import.skipped.push(skip(SkipReason::EmptyUrl));
+        } else if !is_http_url(url) {
+            import
+                .skipped
+                .push(skip(SkipReason::InvalidUrl(url.to_string())));
+        } else if let Some(title) = &label {
+            let category_path = if path.is_empty() {
+                category_attr_path(outline.category.as_deref())
+            } else {
+                path.clone()
+            };
+            import.feeds.push(OpmlFeed {
+                title: title.clone(),
+                url: url.to_string(),
+                html_url: outline.html_url.clone(),
+                description: outline.description.clone(),
+                category_path,
+            });
+        } else {
+            import.skipped.push(skip(SkipReason::MissingTitle));
+        }
+
+        // Some readers nest entries under a feed; keep them at the same level.
+        for child in &outline.children {
+            collect_feeds(child, path, import);
+        }
+        return;
+    }
+
+    if outline.children.is_empty() {
+        let reason = match outline.outline_type.as_deref() {
+            Some(kind) if !kind.trim().is_empty() && !is_feed_type(kind) => {
+                SkipReason::UnsupportedType(kind.to_string())
+            }
+            _ => SkipReason::EmptyFolder,
+        };
+        import.skipped.push(SkippedOutline {
+            label: label.unwrap_or_default(),
+            category_path: path.clone(),
+            reason,
+        });
+        return;
+    }
+
+    let pushed = label.is_some();
+    if let Some(name) = label {
+        path.push(name);
+    }
+    for child in &outline.children {
+        collect_feeds(child, path, import);
+    }
+    if pushed {
+        path.pop();
+    }
+}
+
+fn is_feed_type(kind: &str) -> bool {
+    matches!(kind.to_ascii_lowercase().as_str(), "rss" | "atom" | "feed")
+}
+
+fn is_http_url(value: &str) -> bool {
+    url::Url::parse(value)
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false)
+}
+
+fn category_attr_path(category: Option<&str>) -> Vec<String> {
+    category
+        .and_then(|value| value.split(',').next())
+        .map(|first| {
+            first
+                .split('/')
+                .map(str::trim)
+                .filter(|segment| !segment.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Unescape an attribute value. Values that are not valid XML (HTML-only
+/// entities such as `&nbsp;`, bare `&`) are decoded leniently instead of
+/// failing the whole import.
+fn attribute_value(attr: &Attribute<'_>, decoder: Decoder) -> String {
+    match attr.decode_and_unescape_value(decoder) {
+        Ok(value) => value.into_owned(),
+        Err(_) => {
+            let raw = String::from_utf8_lossy(&attr.value);
+            html_escape::decode_html_entities(&raw).into_owned()
+        }
+    }
+}
+
+/// Parse an OPML document into its outline tree.
+pub fn parse(content: &str) -> Result<OpmlDocument> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut document = OpmlDocument::default();
+    let mut stack: Vec<OpmlOutline> = Vec::new();
+    let mut saw_root = false;
+    let mut in_head_title = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => match element.local_name().as_ref() {
+                b"opml" => saw_root = true,
+                b"outline" => stack.push(OpmlOutline::from_element(&element, reader.decoder())),
+                b"title" if stack.is_empty() => in_head_title = true,
+                _ => {}
+            },
+            Ok(Event::Empty(element)) => {
+                if element.local_name().as_ref() == b"outline" {
+                    let outline = OpmlOutline::from_element(&element, reader.decoder());
+                    attach_outline(&mut stack, &mut document, outline);
+                }
+            }
+            Ok(Event::End(element)) => match element.local_name().as_ref() {
+                b"outline" => {
+                    if let Some(outline) = stack.pop() {
+                        attach_outline(&mut stack, &mut document, outline);
+                    }
+                }
+                b"title" => in_head_title = false,
+                _ => {}
+            },
+            Ok(Event::Text(text)) if in_head_title => {
+                let title: Cow<'_, str> = match text.unescape() {
+                    Ok(title) => title,
+                    Err(_) => Cow::Owned(String::from_utf8_lossy(&text).into_owned()),
+                };
+                document.title = Some(title.into_owned());
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                return Err(RivuletError::Opml(format!(
+                    "invalid XML at byte {}: {}",
+                    reader.error_position(),
+                    e
+                )))
+            }
+        }
+    }
+
+    if !saw_root {
+        return Err(RivuletError::Opml("missing <opml> root element".into()));
+    }
+
+    // Tolerate documents that end with unclosed outlines.
+    while let Some(outline) = stack.pop() {
+        attach_outline(&mut stack, &mut document, outline);
+    }
+
+    Ok(document)
+}
+
+fn attach_outline(stack: &mut [OpmlOutline], document: &mut OpmlDocument, outline: OpmlOutline) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(outline),
+        None => document.outlines.push(outline),
+    }
+}
+
+/// Parse OPML content and flatten it into importable feeds.
+pub fn parse_feeds(content: &str) -> Result<OpmlImport> {
+    Ok(parse(content)?.feeds())
+}
+
+/// Render feeds as OPML 2.0, nesting them under their folders. A feed filed
+/// under several folders is written once per folder.
+pub fn render(feeds: &[Feed], categories: &[Category], links: &[(i64, i64)]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<opml version=\"2.0\">\n");
+    out.push_str("  <head>\n");
+    out.push_str("    <title>Rivulet subscriptions</title>\n");
+    out.push_str(&format!(
+        "    <dateCreated>{}</dateCreated>\n",
+        Utc::now().to_rfc2822()
+    ));
+    out.push_str("  </head>\n");
+    out.push_str("  <body>\n");
+
+    for category in categories.iter().filter(|c| c.parent_id.is_none()) {
+        render_category(&mut out, category, feeds, categories, links, 2);
+    }
+    for feed in feeds {
+        if !links.iter().any(|(feed_id, _)| *feed_id == feed.id) {
+            render_feed(&mut out, feed, 2);
+        }
+    }
+
+    out.push_str("  </body>\n");
+    out.push_str("</opml>\n");
+    out
+}
+
+fn render_category(
+    out: &mut String,
+    category: &Category,
+    feeds: &[Feed],
+    categories: &[Category],
+    links: &[(i64, i64)],
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let name = escape_attr(&category.name);
+    out.push_str(&format!(
+        "{}<outline text=\"{}\" title=\"{}\">\n",
+        indent, name, name
+    ));
+    for child in categories
+        .iter()
+        .filter(|c| c.parent_id == Some(category.id))
+    {
+        render_category(out, child, feeds, categories, links, depth + 1);
+    }
+    for feed in feeds {
+        if links.contains(&(feed.id, category.id)) {
+            render_feed(out, feed, depth + 1);
+        }
+    }
+    out.push_str(&format!("{}</outline>\n", indent));
+}
+
+fn render_feed(out: &mut String, feed: &Feed, depth: usize) {
+    out.push_str(&format!(
+        "{}<outline type=\"rss\" text=\"{}\"",
+        "  ".repeat(depth),
+        escape_attr(feed.display_title())
+    ));
+    if let Some(title) = &feed.title {
+        out.push_str(&format!(" title=\"{}\"", escape_attr(title)));
+    }
+    out.push_str(&format!(" xmlUrl=\"{}\"", escape_attr(&feed.url)));
+    if let Some(description) = &feed.description {
+        out.push_str(&format!(" description=\"{}\"", escape_attr(description)));
+    }
+    out.push_str("/>\n");
+}
+
+/// Escape a value for a double-quoted XML attribute. Line breaks become
+/// character references so XML attribute normalization cannot fold them into
+/// spaces on the way back in.
+fn escape_attr(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opml_basic() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <body>
+    <outline text="Tech" title="Tech">
+      <outline title="Hacker News" text="HN" xmlUrl="https://news.ycombinator.com/rss" htmlUrl="https://news.ycombinator.com"/>
+      <outline title="Lobsters" text="Lobsters" xmlUrl="https://lobste.rs/rss" htmlUrl="https://lobste.rs"/>
+    </outline>
+  </body>
+</opml>"#;
+        let feeds = parse_feeds(opml).unwrap().feeds;
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].title, "Hacker News");
+        assert_eq!(feeds[0].url, "https://news.ycombinator.com/rss");
+        assert_eq!(
+            feeds[0].html_url.as_deref(),
+            Some("https://news.ycombinator.com")
+        );
+        assert_eq!(feeds[0].category_path, vec!["Tech".to_string()]);
+        assert_eq!(feeds[1].title, "Lobsters");
+        assert_eq!(feeds[1].url, "https://lobste.rs/rss");
+    }
+
+    #[test]
+    fn test_parse_opml_nested_folders() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline text="Tech">
+      <outline text="Rust">
+        <outline text="Rust Blog" xmlUrl="https://blog.rust-lang.org/feed.xml"/>
+      </outline>
+      <outline text="Lobsters" xmlUrl="https://lobste.rs/rss"/>
+    </outline>
+    <outline text="Loose" xmlUrl="https://example.com/feed.xml"></outline>
+  </body>
+</opml>"#;
+        let feeds = parse_feeds(opml).unwrap().feeds;
+        assert_eq!(feeds.len(), 3);
+        assert_eq!(
+            feeds[0].category_path,
+            vec!["Tech".to_string(), "Rust".to_string()]
+        );
+        assert_eq!(feeds[1].category_path, vec!["Tech".to_string()]);
+        assert!(feeds[2].category_path.is_empty());
+    }
+
+    #[test]
+    fn test_parse_opml_text_fallback() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline text="My Blog" xmlUrl="https://example.com/feed.xml"/>
+  </body>
+</opml>"#;
+        let feeds = parse_feeds(opml).unwrap().feeds;
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].title, "My Blog");
+        assert!(feeds[0].category_path.is_empty());
+    }
+
+    #[test]
+    fn test_parse_opml_html_entities() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline title="Tom &amp; Jerry&apos;s Blog" xmlUrl="https://example.com/feed.xml"/>
+    <outline title="Caf&eacute; &nbsp;AT&T" xmlUrl="https://example.com/cafe.xml"/>
+  </body>
+</opml>"#;
+        let feeds = parse_feeds(opml).unwrap().feeds;
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].title, "Tom & Jerry's Blog");
+        assert_eq!(feeds[1].title, "Café \u{a0}AT&T");
+    }
+
+    #[test]
+    fn test_parse_opml_empty() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <body>
+    <outline text="Category" title="Category"/>
+  </body>
+</opml>"#;
+        let import = parse_feeds(opml).unwrap();
+        assert!(import.feeds.is_empty());
+        assert_eq!(import.skipped.len(), 1);
+        assert_eq!(import.skipped[0].label, "Category");
+        assert_eq!(import.skipped[0].reason, SkipReason::EmptyFolder);
+    }
+
+    #[test]
+    fn test_parse_opml_no_title_no_text() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline xmlUrl="https://example.com/feed.xml"/>
+  </body>
+</opml>"#;
+        let import = parse_feeds(opml).unwrap();
+        assert!(import.feeds.is_empty());
+        assert_eq!(import.skipped[0].label, "https://example.com/feed.xml");
+        assert_eq!(import.skipped[0].reason, SkipReason::MissingTitle);
+    }
+
+    #[test]
+    fn test_parse_opml_multiline_and_single_quoted_outlines() {
+        let opml = r#"<?xml version="1.0"?>
+<opml version='1.0'>
+  <head><title>Feedly export</title></head>
+  <body>
+    <outline text='News' title='News'>
+      <outline
+          type='rss'
+          text='Example'
+          title='Example'
+          xmlUrl='https://example.com/rss'
+          htmlUrl='https://example.com/'
+      />
+    </outline>
+  </body>
+</opml>"#;
+        let document = parse(opml).unwrap();
+        assert_eq!(document.title.as_deref(), Some("Feedly export"));
+        let folder = &document.outlines[0];
+        assert_eq!(folder.label(), Some("News"));
+        assert_eq!(folder.children[0].outline_type.as_deref(), Some("rss"));
+
+        let feeds = document.feeds().feeds;
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].url, "https://example.com/rss");
+        assert_eq!(feeds[0].html_url.as_deref(), Some("https://example.com/"));
+        assert_eq!(feeds[0].category_path, vec!["News".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_opml_category_attribute_and_skip_reasons() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline text="Weather" xmlUrl="https://example.com/weather.xml" category="/Boston/Weather,/Other"/>
+    <outline text="Homepage" type="link" url="https://example.com/"/>
+    <outline text="Broken" xmlUrl="   "/>
+    <outline text="Local" xmlUrl="file:///etc/passwd"/>
+  </body>
+</opml>"#;
+        let import = parse_feeds(opml).unwrap();
+        assert_eq!(import.feeds.len(), 1);
+        assert_eq!(
+            import.feeds[0].category_path,
+            vec!["Boston".to_string(), "Weather".to_string()]
+        );
+
+        let reasons: Vec<_> = import.skipped.iter().map(|s| s.reason.clone()).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                SkipReason::UnsupportedType("link".into()),
+                SkipReason::EmptyUrl,
+                SkipReason::InvalidUrl("file:///etc/passwd".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_opml_rejects_non_opml() {
+        assert!(parse("<rss><channel/></rss>").is_err());
+        assert!(parse("<opml><body><outline></body></opml>").is_err());
+    }
+
+    #[test]
+    fn test_render_round_trips_through_parse() {
+        let mut feed = Feed::new("https://example.com/feed.xml?a=1&b=2".into());
+        feed.id = 1;
+        feed.title = Some("Quotes \"and\" <tags>".into());
+        feed.description = Some("Line one\nLine two".into());
+        let mut untitled = Feed::new("https://example.com/untitled.xml".into());
+        untitled.id = 2;
+
+        let mut tech = Category::new("Tech".into(), None);
+        tech.id = 10;
+        let mut rust = Category::new("Rust".into(), Some(10));
+        rust.id = 11;
+
+        let rendered = render(&[feed.clone(), untitled], &[tech, rust], &[(1, 11)]);
+        let feeds = parse_feeds(&rendered).unwrap().feeds;
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].url, feed.url);
+        assert_eq!(Some(feeds[0].title.clone()), feed.title);
+        assert_eq!(feeds[0].description, feed.description);
+        assert_eq!(
+            feeds[0].category_path,
+            vec!["Tech".to_string(), "Rust".to_string()]
+        );
+        assert_eq!(feeds[1].title, "https://example.com/untitled.xml");
+    }
+}
@@ -0,0 +1,374 @@
+//! Keyword rules from `[[rules]]` in config.toml, applied to items as they
+//! arrive and dry-run by `rivulet rules test`.
+
+use std::collections::HashSet;
+
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+use crate::app::Result;
+use crate::config::{ConfigError, RuleConfig};
+use crate::domain::{Feed, Item};
+use crate::html::strip_tags;
+use crate::store::{ItemSelector, ItemStateChange, Store};
+
+/// What a rule does to the items it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleAction {
+    MarkRead,
+    Star,
+    Queue,
+    Save,
+    Archive,
+    Hide,
+    /// Adds every name in the rule's `tags`
+    Tag,
+}
+
+impl RuleAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RuleAction::MarkRead => "mark-read",
+            RuleAction::Star => "star",
+            RuleAction::Queue => "queue",
+            RuleAction::Save => "save",
+            RuleAction::Archive => "archive",
+            RuleAction::Hide => "hide",
+            RuleAction::Tag => "tag",
+        }
+    }
+}
+
+/// A compiled `[[rules]]` entry. Every matcher that is set must match.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    feed: Option<String>,
+    title: Option<Regex>,
+    content: Option<Regex>,
+    author: Option<Regex>,
+    search: Option<String>,
+    pub actions: Vec<RuleAction>,
+    pub tags: Vec<String>,
+}
+
+impl Rule {
+    fn compile(index: usize, config: &RuleConfig) -> std::result::Result<Self, ConfigError> {
+        let name = match config.name.trim() {
+            "" => format!("rule {}", index + 1),
+            name => name.to_string(),
+        };
+        let invalid = |message: String| ConfigError::InvalidRule {
+            rule: name.clone(),
+            message,
+        };
+        let regex = |field: &str, pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(|pattern| {
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|e| invalid(format!("bad {} pattern: {}", field, e)))
+                })
+                .transpose()
+        };
+
+        let rule = Self {
+            feed: non_empty(&config.feed),
+            title: regex("title", &config.title)?,
+            content: regex("content", &config.content)?,
+            author: regex("author", &config.author)?,
+            search: non_empty(&config.search),
+            actions: config.actions.clone(),
+            tags: config
+                .tags
+                .iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            name: name.clone(),
+        };
+
+        if rule.feed.is_none()
+            && rule.title.is_none()
+            && rule.content.is_none()
+            && rule.author.is_none()
+            && rule.search.is_none()
+        {
+            return Err(invalid(
+                "needs at least one of feed, title, content, author or search".to_string(),
+            ));
+        }
+        if rule.actions.is_empty() {
+            return Err(invalid("has no actions".to_string()));
+        }
+        let tags_action = rule.actions.contains(&RuleAction::Tag);
+        if tags_action && rule.tags.is_empty() {
+            return Err(invalid(
+                "the tag action needs a non-empty `tags` list".to_string(),
+            ));
+        }
+        if !tags_action && !rule.tags.is_empty() {
+            return Err(invalid("has `tags` but no tag action".to_string()));
+        }
+
+        Ok(rule)
+    }
+
+    /// The feed matcher takes a feed URL or its title.
+    fn matches_feed(&self, feed: &Feed) -> bool {
+        self.feed.as_deref().is_none_or(|wanted| {
+            wanted == feed.url
+                || feed
+                    .title
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case(wanted))
+        })
+    }
+
+    /// Checks the regex matchers; the full-text matcher needs the store.
+    fn matches_item(&self, item: &Item) -> bool {
+        let title_ok = self
+            .title
+            .as_ref()
+            .is_none_or(|re| item.title.as_deref().is_some_and(|t| re.is_match(t)));
+        let author_ok = self
+            .author
+            .as_ref()
+            .is_none_or(|re| item.author.as_deref().is_some_and(|a| re.is_match(a)));
+        let content_ok = self.content.as_ref().is_none_or(|re| {
+            [item.content.as_deref(), item.summary.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|html| re.is_match(&strip_tags(html)))
+        });
+        title_ok && author_ok && content_ok
+    }
+
+    /// The flag changes among this rule's actions.
+    pub fn state_change(&self) -> ItemStateChange {
+        let mut change = ItemStateChange::default();
+        for action in &self.actions {
+            match action {
+                RuleAction::MarkRead => change.read = Some(true),
+                RuleAction::Star => change.starred = Some(true),
+                RuleAction::Queue => change.queued = Some(true),
+                RuleAction::Save => change.saved = Some(true),
+                RuleAction::Archive => change.archived = Some(true),
+                RuleAction::Hide => change.hidden = Some(true),
+                RuleAction::Tag => {}
+            }
+        }
+        change
+    }
+
+    /// Actions as written in config, with the tags spelled out.
+    pub fn describe_actions(&self) -> String {
+        self.actions
+            .iter()
+            .map(|action| match action {
+                RuleAction::Tag => format!("tag {}", self.tags.join(", ")),
+                action => action.as_str().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// All configured rules, in config order.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn compile(configs: &[RuleConfig]) -> std::result::Result<Self, ConfigError> {
+        let rules = configs
+            .iter()
+            .enumerate()
+            .map(|(index, config)| Rule::compile(index, config))
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// For each rule, the IDs of the `items` from `feed` it matches. Items
+    /// must already be stored for the `search` matcher to see them.
+    pub fn evaluate<S: Store + ?Sized>(
+        &self,
+        store: &S,
+        feed: &Feed,
+        items: &[Item],
+    ) -> Result<Vec<Vec<String>>> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                if !rule.matches_feed(feed) {
+                    return Ok(Vec::new());
+                }
+                let searched: Option<HashSet<String>> = match &rule.search {
+                    Some(query) => Some(
+                        store
+                            .select_item_ids(&ItemSelector {
+                                feed_id: Some(feed.id),
+                                search: Some(query.clone()),
+                                ..ItemSelector::default()
+                            })?
+                            .into_iter()
+                            .collect(),
+                    ),
+                    None => None,
+                };
+                Ok(items
+                    .iter()
+                    .filter(|item| searched.as_ref().is_none_or(|ids| ids.contains(&item.id)))
+                    .filter(|item| rule.matches_item(item))
+                    .map(|item| item.id.clone())
+                    .collect())
+            })
+            .collect()
+    }
+
+    /// Runs every matching rule's actions on `items`; returns how many items
+    /// at least one rule acted on.
+    pub fn apply<S: Store + ?Sized>(
+        &self,
+        store: &S,
+        feed: &Feed,
+        items: &[Item],
+    ) -> Result<usize> {
+        if self.rules.is_empty() || items.is_empty() {
+            return Ok(0);
+        }
+
+        let mut acted_on = HashSet::new();
+        for (rule, ids) in self.rules.iter().zip(self.evaluate(store, feed, items)?) {
+            if ids.is_empty() {
+                continue;
+            }
+            tracing::debug!(
+                "Rule {} matched {} items in {}",
+                rule.name,
+                ids.len(),
+                feed.url
+            );
+            store.set_item_states(&ids, &rule.state_change())?;
+            if rule.actions.contains(&RuleAction::Tag) {
+                for tag in &rule.tags {
+                    store.tag_items(&ids, tag)?;
+                }
+            }
+            acted_on.extend(ids);
+        }
+
+        Ok(acted_on.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SqliteStore;
+
+    fn rule(toml_src: &str) -> RuleConfig {
+        toml::from_str(toml_src).unwrap()
+    }
+
+    fn stored_items(store: &SqliteStore) -> (Feed, Vec<Item>) {
+        let mut feed = Feed::new("https://example.com/feed.xml".into());
+        feed.title = Some("Example".into());
+        feed.id = store.add_feed(&feed).unwrap();
+
+        let entries = [
+            ("Sponsored: buy widgets", "Widgets are great.", "Ads Team"),
+            (
+                "Release notes",
+                "Acme 2.0 ships today with faster sync.",
+                "Jo",
+            ),
+            ("Weekly links", "Nothing about products here.", "Jo"),
+        ];
+        let items: Vec<Item> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (title, summary, author))| {
+                let mut item = Item::new(feed.id, &feed.url, &format!("entry-{i}"));
+                item.title = Some(title.to_string());
+                item.summary = Some(format!("<p>{summary}</p>"));
+                item.author = Some(author.to_string());
+                item
+            })
+            .collect();
+        store.add_items(&items).unwrap();
+        (feed, items)
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_rules() {
+        let bad_regex = rule("name = \"x\"\ntitle = \"(\"\nactions = [\"hide\"]");
+        let no_matcher = rule("actions = [\"star\"]");
+        let no_actions = rule("title = \"x\"");
+        let tag_without_tags = rule("title = \"x\"\nactions = [\"tag\"]");
+
+        for config in [bad_regex, no_matcher, no_actions, tag_without_tags] {
+            assert!(matches!(
+                RuleSet::compile(&[config]),
+                Err(ConfigError::InvalidRule { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_evaluate_combines_matchers() {
+        let store = SqliteStore::in_memory().unwrap();
+        let (feed, items) = stored_items(&store);
+        let rules = RuleSet::compile(&[
+            rule("title = \"^sponsored\"\nactions = [\"hide\"]"),
+            rule("search = \"acme\"\nauthor = \"jo\"\nactions = [\"queue\"]"),
+            rule("feed = \"https://other.example/feed.xml\"\ntitle = \".\"\nactions = [\"star\"]"),
+            rule("feed = \"example\"\ncontent = \"products\"\nactions = [\"star\"]"),
+        ])
+        .unwrap();
+
+        let matches = rules.evaluate(&store, &feed, &items).unwrap();
+        assert_eq!(matches[0], vec![items[0].id.clone()]);
+        assert_eq!(matches[1], vec![items[1].id.clone()]);
+        assert!(matches[2].is_empty());
+        assert_eq!(matches[3], vec![items[2].id.clone()]);
+    }
+
+    #[test]
+    fn test_apply_changes_state_and_tags() {
+        let store = SqliteStore::in_memory().unwrap();
+        let (feed, items) = stored_items(&store);
+        let rules = RuleSet::compile(&[rule(
+            "title = \"sponsored\"\nactions = [\"mark-read\", \"hide\", \"tag\"]\ntags = [\"ads\"]",
+        )])
+        .unwrap();
+
+        assert_eq!(rules.apply(&store, &feed, &items).unwrap(), 1);
+
+        let state = store.get_item_state(&items[0].id).unwrap().unwrap();
+        assert!(state.is_read);
+        assert!(state.is_hidden);
+        assert_eq!(store.get_unread_count(feed.id).unwrap(), 2);
+        assert_eq!(store.tag_items(&[items[0].id.clone()], "ADS").unwrap(), 0);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+use crate::app::{Result, RivuletError};
+use crate::domain::{Feed, Item};
+use crate::fetcher::http_fetcher::HttpFetcher;
+use crate::scraper::{
+    ChromeScraper, HttpScraper, Recipe, RecipeSet, ScrapeResult, Scraper, ScraperConfig,
+};
+
+/// Scraper that uses `HttpScraper` for most pages and `ChromeScraper` only
+/// for the feeds and domains configured to need it. Chrome is launched the
+/// first time such a page comes up; if it cannot start, those pages fail
+/// and it is not tried again.
+pub struct ArticleScraper {
+    config: ScraperConfig,
+    recipes: RecipeSet,
+    reader: HttpScraper,
+    chrome: OnceCell<std::result::Result<ChromeScraper, String>>,
+    /// Feeds matched by `chrome_feeds`, see `set_feeds`
+    chrome_feed_ids: HashSet<i64>,
+}
+
+impl ArticleScraper {
+    /// `fetcher` is shared with the built-in reader, so pages go through the
+    /// same client as feeds.
+    pub fn new(config: ScraperConfig, fetcher: HttpFetcher) -> Result<Self> {
+        Ok(Self {
+            recipes: config
+                .recipe_set()
+                .map_err(|e| RivuletError::Config(e.to_string()))?,
+            reader: HttpScraper::new(config.clone(), fetcher)?,
+            config,
+            chrome: OnceCell::new(),
+            chrome_feed_ids: HashSet::new(),
+        })
+    }
+
+    /// Resolves `chrome_feeds` against the subscribed feeds, so items can be
+    /// routed by their feed.
+    pub fn set_feeds(&mut self, feeds: &[Feed]) {
+        self.chrome_feed_ids = feeds
+            .iter()
+            .filter(|feed| self.config.wants_chrome_for_feed(feed))
+            .map(|feed| feed.id)
+            .collect();
+    }
+
+    /// The recipe that applies to `url`, if any.
+    pub fn recipe_for(&self, url: &str) -> Option<&Recipe> {
+        self.recipes.find(url)
+    }
+
+    /// Whether `url` is scraped with Chrome: the recipe's `javascript`
+    /// setting if it has one, otherwise `chrome_domains`.
+    pub fn uses_chrome(&self, url: &str) -> bool {
+        self.recipe_for(url)
+            .and_then(|recipe| recipe.javascript)
+            .unwrap_or_else(|| self.config.wants_chrome_for_url(url))
+    }
+
+    fn wants_chrome(&self, item: &Item) -> bool {
+        let Some(link) = item.link.as_deref() else {
+            return self.chrome_feed_ids.contains(&item.feed_id);
+        };
+        self.recipe_for(link)
+            .and_then(|recipe| recipe.javascript)
+            .unwrap_or_else(|| {
+                self.chrome_feed_ids.contains(&item.feed_id)
+                    || self.config.wants_chrome_for_url(link)
+            })
+    }
+
+    async fn chrome(&self) -> Result<&ChromeScraper> {
+        self.launch_chrome()
+            .await
+            .map_err(|e| RivuletError::Scraper(format!("Chrome unavailable: {}", e)))
+    }
+
+    /// The launched browser, or why it could not start.
+    async fn launch_chrome(&self) -> std::result::Result<&ChromeScraper, &str> {
+        let chrome = self
+            .chrome
+            .get_or_init(|| async {
+                match ChromeScraper::new(self.config.clone()).await {
+                    Ok(chrome) => {
+                        info!("Browser initialized successfully");
+                        Ok(chrome)
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Chrome scraping disabled: {}. Install Chrome or Chromium to \
+                             scrape the sites in chrome_domains and chrome_feeds.",
+                            e
+                        );
+                        Err(match e {
+                            RivuletError::Scraper(message) => message,
+                            e => e.to_string(),
+                        })
+                    }
+                }
+            })
+            .await;
+        chrome.as_ref().map_err(String::as_str)
+    }
+}
+
+#[async_trait]
+impl Scraper for ArticleScraper {
+    async fn scrape(&self, url: &str) -> Result<ScrapeResult> {
+        if self.uses_chrome(url) {
+            self.chrome().await?.scrape(url).await
+        } else {
+            self.reader.scrape(url).await
+        }
+    }
+
+    async fn scrape_items(
+        &self,
+        items: &[Item],
+        concurrency: usize,
+    ) -> Vec<(String, Result<ScrapeResult>)> {
+        let (chrome_items, reader_items): (Vec<Item>, Vec<Item>) = items
+            .iter()
+            .filter(|item| Self::needs_scraping(item))
+            .cloned()
+            .partition(|item| self.wants_chrome(item));
+
+        let mut results = self.reader.scrape_items(&reader_items, concurrency).await;
+        if !chrome_items.is_empty() {
+            match self.launch_chrome().await {
+                Ok(chrome) => results.extend(chrome.scrape_items(&chrome_items, concurrency).await),
+                Err(e) => results.extend(chrome_items.into_iter().map(|item| {
+                    (
+                        item.id,
+                        Err(RivuletError::Scraper(format!("Chrome unavailable: {}", e))),
+                    )
+                })),
+            }
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipes_override_chrome_routing() {
+        let recipe = |url: &str, javascript| Recipe {
+            url: url.to_string(),
+            javascript,
+            ..Default::default()
+        };
+        let config = ScraperConfig {
+            chrome_domains: vec!["example.com".to_string()],
+            chrome_feeds: vec!["App Feed".to_string()],
+            recipes: vec![
+                recipe(r"example\.com/static/", Some(false)),
+                recipe(r"app\.test", Some(true)),
+                recipe(r"example\.com/", None),
+            ],
+            ..Default::default()
+        };
+        let mut scraper = ArticleScraper::new(config, HttpFetcher::new()).unwrap();
+        let mut feed = Feed::new("https://feeds.test/app.xml".to_string());
+        feed.id = 7;
+        feed.title = Some("App Feed".to_string());
+        scraper.set_feeds(&[feed]);
+
+        assert!(scraper.uses_chrome("https://example.com/post"));
+        assert!(!scraper.uses_chrome("https://example.com/static/post"));
+        assert!(scraper.uses_chrome("https://app.test/"));
+        assert!(!scraper.uses_chrome("https://other.test/"));
+        assert_eq!(
+            scraper.recipe_for("https://example.com/post").unwrap().name,
+            "recipe 3"
+        );
+
+        let item = |feed_id, link: &str| {
+            let mut item = Item::new(feed_id, "https://feeds.test/app.xml", link);
+            item.link = Some(link.to_string());
+            item
+        };
+        assert!(scraper.wants_chrome(&item(7, "https://other.test/a")));
+        assert!(!scraper.wants_chrome(&item(7, "https://example.com/static/a")));
+        assert!(!scraper.wants_chrome(&item(1, "https://other.test/a")));
+    }
+}
@@ -4,7 +4,8 @@ use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
 use crate::domain::Item;
-use crate::scraper::{ChromeScraper, Scraper, ScraperConfig};
+use crate::fetcher::http_fetcher::HttpFetcher;
+use crate::scraper::{ArticleScraper, Scraper, ScraperConfig};
 use crate::store::Store;
 
 /// Message type for the background scraper
@@ -42,16 +43,26 @@ impl BackgroundScraperHandle {
 /// Background scraper service that processes items asynchronously
 pub struct BackgroundScraper<S: Store + Send + Sync + 'static> {
     config: ScraperConfig,
+    fetcher: HttpFetcher,
     store: Arc<S>,
     rx: mpsc::Receiver<ScrapeMessage>,
 }
 
 impl<S: Store + Send + Sync + 'static> BackgroundScraper<S> {
     /// Create a new background scraper and return a handle to communicate with it
-    pub fn new(config: ScraperConfig, store: Arc<S>) -> (Self, BackgroundScraperHandle) {
+    pub fn new(
+        config: ScraperConfig,
+        fetcher: HttpFetcher,
+        store: Arc<S>,
+    ) -> (Self, BackgroundScraperHandle) {
         let (tx, rx) = mpsc::channel(100);
         let handle = BackgroundScraperHandle { tx };
-        let scraper = Self { config, store, rx };
+        let scraper = Self {
+            config,
+            fetcher,
+            store,
+            rx,
+        };
         (scraper, handle)
     }
 
@@ -59,9 +70,15 @@ impl<S: Store + Send + Sync + 'static> BackgroundScraper<S> {
     pub async fn run(mut self) {
         info!("Background scraper started");
 
-        // Lazy initialization of browser - only when needed
-        let mut scraper: Option<ChromeScraper> = None;
-        let mut init_failed = false;
+        // Chrome is launched by the scraper itself, only for the feeds and
+        // domains configured to need it
+        let mut scraper = match ArticleScraper::new(self.config.clone(), self.fetcher.clone()) {
+            Ok(scraper) => scraper,
+            Err(e) => {
+                warn!("Scraper disabled: {}", e);
+                return;
+            }
+        };
 
         while let Some(msg) = self.rx.recv().await {
             match msg {
@@ -76,57 +93,36 @@ impl<S: Store + Send + Sync + 'static> BackgroundScraper<S> {
                         continue;
                     }
 
-                    // Initialize scraper lazily
-                    if scraper.is_none() {
-                        if init_failed {
-                            // Already failed once, don't retry
-                            continue;
-                        }
-                        match ChromeScraper::new(self.config.clone()).await {
-                            Ok(s) => {
-                                info!("Browser initialized successfully");
-                                scraper = Some(s);
-                            }
-                            Err(e) => {
-                                warn!(
-                                    "Scraper disabled: {}. \
-                                     Install Chrome or Chromium to enable content scraping, \
-                                     or set [scraper] enabled = false in config.",
-                                    e
-                                );
-                                init_failed = true;
-                                continue;
-                            }
-                        }
+                    match self.store.get_all_feeds() {
+                        Ok(feeds) => scraper.set_feeds(&feeds),
+                        Err(e) => warn!("Failed to load feeds for scraping: {}", e),
                     }
 
                     info!("Scraping {} items in background", items_to_scrape.len());
 
-                    if let Some(ref s) = scraper {
-                        let results = s
-                            .scrape_items(&items_to_scrape, self.config.max_concurrency)
-                            .await;
-
-                        for (item_id, result) in results {
-                            match result {
-                                Ok(scrape_result) => {
-                                    if let Err(e) = self
-                                        .store
-                                        .update_item_content(&item_id, &scrape_result.content)
-                                    {
-                                        error!("Failed to update item content: {}", e);
-                                    } else {
-                                        info!(
-                                            "Scraped content for item {} ({} chars)",
-                                            &item_id[..8],
-                                            scrape_result.content.len()
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    warn!("Failed to scrape item {}: {}", &item_id[..8], e);
+                    let results = scraper
+                        .scrape_items(&items_to_scrape, self.config.max_concurrency)
+                        .await;
+
+                    for (item_id, result) in results {
+                        match result {
+                            Ok(scrape_result) => {
+                                if let Err(e) = self
+                                    .store
+                                    .update_item_content(&item_id, &scrape_result.content)
+                                {
+                                    error!("Failed to update item content: {}", e);
+                                } else {
+                                    info!(
+                                        "Scraped content for item {} ({} chars)",
+                                        &item_id[..8],
+                                        scrape_result.content.len()
+                                    );
                                 }
                             }
+                            Err(e) => {
+                                warn!("Failed to scrape item {}: {}", &item_id[..8], e);
+                            }
                         }
                     }
                 }
@@ -173,9 +169,10 @@ impl<S: Store + Send + Sync + 'static> BackgroundScraper<S> {
 /// Spawn the background scraper as a tokio task
 pub fn spawn_background_scraper<S: Store + Send + Sync + 'static>(
     config: ScraperConfig,
+    fetcher: HttpFetcher,
     store: Arc<S>,
 ) -> BackgroundScraperHandle {
-    let (scraper, handle) = BackgroundScraper::new(config, store);
+    let (scraper, handle) = BackgroundScraper::new(config, fetcher, store);
 
     tokio::spawn(async move {
         scraper.run().await;
@@ -1,27 +1,36 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::Page;
 use futures::StreamExt;
+use serde::de::DeserializeOwned;
 use tokio::sync::Semaphore;
+use tracing::warn;
 
 use crate::app::{Result, RivuletError};
 use crate::domain::Item;
 use crate::scraper::config::ScraperConfig;
 use crate::scraper::extractor::ContentExtractor;
-use crate::scraper::{ScrapeResult, Scraper};
+use crate::scraper::{Recipe, RecipeSet, ScrapeResult, Scraper};
 
 /// Chrome-based web scraper using chromiumoxide
+#[derive(Clone)]
 pub struct ChromeScraper {
     browser: Arc<Browser>,
     config: ScraperConfig,
-    extractor: ContentExtractor,
+    recipes: RecipeSet,
     semaphore: Arc<Semaphore>,
 }
 
 impl ChromeScraper {
     /// Create a new Chrome scraper with the given configuration
     pub async fn new(config: ScraperConfig) -> Result<Self> {
+        let recipes = config
+            .recipe_set()
+            .map_err(|e| RivuletError::Config(e.to_string()))?;
         let mut builder = BrowserConfig::builder()
             .arg("--no-sandbox")
             .arg("--disable-gpu")
@@ -55,12 +64,11 @@ impl ChromeScraper {
         });
 
         let semaphore = Arc::new(Semaphore::new(config.max_concurrency));
-        let extractor = ContentExtractor::new(config.clone());
 
         Ok(Self {
             browser: Arc::new(browser),
             config,
-            extractor,
+            recipes,
             semaphore,
         })
     }
@@ -91,14 +99,32 @@ impl ChromeScraper {
         Ok(())
     }
 
-    /// Scrape a single page and extract content
+    /// Scrape a single page and extract content, following the recipe for
+    /// `url` if there is one
     async fn scrape_page(&self, url: &str) -> Result<ScrapeResult> {
+        let recipe = self.recipes.find(url);
         let page = self
             .browser
             .new_page(url)
             .await
             .map_err(|e| RivuletError::Scraper(format!("Failed to create page: {}", e)))?;
 
+        let result = self.extract_pages(&page, url, recipe).await;
+
+        // Close the page
+        let _ = page.close().await;
+        result
+    }
+
+    /// Extracts the loaded page, then follows its next pages up to
+    /// `max_pages` and stitches them onto the article. A next page that
+    /// fails ends the article there.
+    async fn extract_pages(
+        &self,
+        page: &Page,
+        url: &str,
+        recipe: Option<&Recipe>,
+    ) -> Result<ScrapeResult> {
         // Set user agent if configured
         if let Some(ref ua) = self.config.user_agent {
             page.set_user_agent(ua)
@@ -106,47 +132,123 @@ impl ChromeScraper {
                 .map_err(|e| RivuletError::Scraper(format!("Failed to set user agent: {}", e)))?;
         }
 
+        let (mut article, mut next) = self.extract_page(page, recipe).await?;
+        let mut visited = HashSet::from([url.to_string()]);
+        let mut pages = 1;
+
+        while let Some(url) = next.take() {
+            let url = url.split('#').next().unwrap_or_default().to_string();
+            if pages >= self.config.max_pages.max(1)
+                || !article.is_html
+                || !visited.insert(url.clone())
+            {
+                break;
+            }
+            let extracted = match page.goto(url.as_str()).await {
+                Ok(_) => self.extract_page(page, recipe).await,
+                Err(e) => Err(RivuletError::Scraper(format!("Navigation failed: {}", e))),
+            };
+            match extracted {
+                Ok((result, following)) if result.is_html => {
+                    article.content.push('\n');
+                    article.content.push_str(&result.content);
+                    pages += 1;
+                    next = following;
+                }
+                Ok(_) => break,
+                Err(e) => {
+                    warn!("Stopped at page {} of {}: {}", pages + 1, url, e);
+                    break;
+                }
+            }
+        }
+
+        Ok(article)
+    }
+
+    /// Extracts the article from the page being loaded, and the address of
+    /// its next page
+    async fn extract_page(
+        &self,
+        page: &Page,
+        recipe: Option<&Recipe>,
+    ) -> Result<(ScrapeResult, Option<String>)> {
         // Wait for the page to load
         page.wait_for_navigation()
             .await
             .map_err(|e| RivuletError::Scraper(format!("Navigation failed: {}", e)))?;
 
+        if let Some(recipe) = recipe {
+            if !recipe.click.is_empty() {
+                let _: serde_json::Value =
+                    evaluate(page, ContentExtractor::click_script(&recipe.click)).await?;
+            }
+            if let Some(ref selector) = recipe.wait_for {
+                self.wait_for_selector(page, selector).await?;
+            }
+        }
+
         // Additional wait for dynamic content
         tokio::time::sleep(self.config.wait_after_load()).await;
 
         // Execute extraction script
-        let script = self.extractor.extraction_script();
-        let result: serde_json::Value = page
-            .evaluate(script)
-            .await
-            .map_err(|e| RivuletError::Scraper(format!("Script execution failed: {}", e)))?
-            .into_value()
-            .map_err(|e| RivuletError::Scraper(format!("Failed to parse result: {:?}", e)))?;
+        let extractor = ContentExtractor::new(self.config.with_recipe(recipe));
+        let result: serde_json::Value = evaluate(page, extractor.extraction_script()).await?;
 
         // Extract content from result
         let html = result["html"].as_str().unwrap_or("").to_string();
         let text = result["text"].as_str().unwrap_or("").to_string();
-
-        // Close the page
-        page.close()
-            .await
-            .map_err(|e| RivuletError::Scraper(format!("Failed to close page: {}", e)))?;
+        let next = result["next"].as_str().map(str::to_string);
 
         // Prefer HTML if available, fallback to text
         if !html.is_empty() {
-            Ok(ScrapeResult {
-                content: html,
-                is_html: true,
-            })
+            Ok((
+                ScrapeResult {
+                    content: html,
+                    is_html: true,
+                },
+                next,
+            ))
         } else if !text.is_empty() {
-            Ok(ScrapeResult {
-                content: text,
-                is_html: false,
-            })
+            Ok((
+                ScrapeResult {
+                    content: text,
+                    is_html: false,
+                },
+                next,
+            ))
         } else {
             Err(RivuletError::Scraper("No content extracted".to_string()))
         }
     }
+
+    /// Polls until `selector` is on the page, up to the page load timeout
+    async fn wait_for_selector(&self, page: &Page, selector: &str) -> Result<()> {
+        let script = ContentExtractor::presence_script(selector);
+        let deadline = tokio::time::Instant::now() + self.config.timeout();
+        loop {
+            let present: bool = evaluate(page, script.clone()).await?;
+            if present {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(RivuletError::Scraper(format!(
+                    "Timed out waiting for {}",
+                    selector
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+}
+
+/// Runs `script` in the page and parses its result
+async fn evaluate<T: DeserializeOwned>(page: &Page, script: String) -> Result<T> {
+    page.evaluate(script)
+        .await
+        .map_err(|e| RivuletError::Scraper(format!("Script execution failed: {}", e)))?
+        .into_value()
+        .map_err(|e| RivuletError::Scraper(format!("Failed to parse result: {:?}", e)))
 }
 
 #[async_trait]
@@ -181,64 +283,11 @@ impl Scraper for ChromeScraper {
             let item_id = item.id.clone();
             let url = url.clone();
             let sem = semaphore.clone();
-            let browser = self.browser.clone();
-            let config = self.config.clone();
+            let scraper = self.clone();
 
             let handle = tokio::spawn(async move {
                 let _permit = sem.acquire().await;
-                let extractor = ContentExtractor::new(config.clone());
-
-                let result = async {
-                    let page = browser.new_page(&url).await.map_err(|e| {
-                        RivuletError::Scraper(format!("Failed to create page: {}", e))
-                    })?;
-
-                    if let Some(ref ua) = config.user_agent {
-                        page.set_user_agent(ua).await.map_err(|e| {
-                            RivuletError::Scraper(format!("Failed to set user agent: {}", e))
-                        })?;
-                    }
-
-                    page.wait_for_navigation()
-                        .await
-                        .map_err(|e| RivuletError::Scraper(format!("Navigation failed: {}", e)))?;
-
-                    tokio::time::sleep(config.wait_after_load()).await;
-
-                    let script = extractor.extraction_script();
-                    let result: serde_json::Value = page
-                        .evaluate(script)
-                        .await
-                        .map_err(|e| {
-                            RivuletError::Scraper(format!("Script execution failed: {}", e))
-                        })?
-                        .into_value()
-                        .map_err(|e| {
-                            RivuletError::Scraper(format!("Failed to parse result: {:?}", e))
-                        })?;
-
-                    let html = result["html"].as_str().unwrap_or("").to_string();
-                    let text = result["text"].as_str().unwrap_or("").to_string();
-
-                    let _ = page.close().await;
-
-                    if !html.is_empty() {
-                        Ok(ScrapeResult {
-                            content: html,
-                            is_html: true,
-                        })
-                    } else if !text.is_empty() {
-                        Ok(ScrapeResult {
-                            content: text,
-                            is_html: false,
-                        })
-                    } else {
-                        Err(RivuletError::Scraper("No content extracted".to_string()))
-                    }
-                }
-                .await;
-
-                (item_id, result)
+                (item_id, scraper.scrape_page(&url).await)
             });
 
             handles.push(handle);
@@ -2,6 +2,13 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::Duration;
 
+use url::Url;
+
+use crate::config::ConfigError;
+use crate::domain::Feed;
+use crate::scraper::readability::check_selector;
+use crate::scraper::{Recipe, RecipeSet};
+
 /// Configuration for the web scraper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -27,6 +34,14 @@ pub struct ScraperConfig {
     /// CSS selectors for elements to remove (ads, navigation, etc.)
     pub remove_selectors: Vec<String>,
 
+    /// CSS selectors for the link to an article's next page, tried before
+    /// `rel="next"` links
+    pub next_page_selectors: Vec<String>,
+
+    /// Most pages of one article followed and stitched together; 1 scrapes
+    /// only the linked page (default: 5)
+    pub max_pages: usize,
+
     /// Maximum concurrent browser pages (default: 5)
     pub max_concurrency: usize,
 
@@ -41,6 +56,17 @@ pub struct ScraperConfig {
 
     /// Optional persistent Chrome user data directory for authenticated sessions
     pub user_data_dir: Option<PathBuf>,
+
+    /// Sites that need JavaScript, scraped with Chrome instead of the built-in
+    /// reader; subdomains match too
+    pub chrome_domains: Vec<String>,
+
+    /// Feeds (URL or title) whose articles are scraped with Chrome
+    pub chrome_feeds: Vec<String>,
+
+    /// Per-site recipes, `[[scraper.recipes]]` followed by the files in the
+    /// recipes directory
+    pub recipes: Vec<Recipe>,
 }
 
 impl Default for ScraperConfig {
@@ -82,6 +108,8 @@ impl Default for ScraperConfig {
                 "style".to_string(),
                 "noscript".to_string(),
             ],
+            next_page_selectors: Vec::new(),
+            max_pages: 5,
             max_concurrency: 5,
             block_images: true,
             block_stylesheets: true,
@@ -91,6 +119,9 @@ impl Default for ScraperConfig {
                     .to_string(),
             ),
             user_data_dir: None,
+            chrome_domains: Vec::new(),
+            chrome_feeds: Vec::new(),
+            recipes: Vec::new(),
         }
     }
 }
@@ -106,6 +137,71 @@ impl ScraperConfig {
         Duration::from_millis(self.wait_after_load_ms)
     }
 
+    /// Checks the selectors and compiles `recipes`, failing on the first
+    /// invalid selector or recipe.
+    pub fn recipe_set(&self) -> Result<RecipeSet, ConfigError> {
+        for (setting, selectors) in [
+            ("content_selectors", &self.content_selectors),
+            ("remove_selectors", &self.remove_selectors),
+            ("next_page_selectors", &self.next_page_selectors),
+        ] {
+            for selector in selectors {
+                check_selector(selector).map_err(|message| ConfigError::InvalidSelector {
+                    setting: setting.to_string(),
+                    message,
+                })?;
+            }
+        }
+        RecipeSet::compile(&self.recipes)
+    }
+
+    /// This config with `recipe`'s selectors: its content and next-page
+    /// selectors are tried first and its removals are added.
+    pub fn with_recipe(&self, recipe: Option<&Recipe>) -> Self {
+        let mut config = self.clone();
+        if let Some(recipe) = recipe {
+            if let Some(ref content) = recipe.content {
+                config.content_selectors.insert(0, content.clone());
+            }
+            if let Some(ref next_page) = recipe.next_page {
+                config.next_page_selectors.insert(0, next_page.clone());
+            }
+            config
+                .remove_selectors
+                .extend(recipe.remove.iter().cloned());
+        }
+        config
+    }
+
+    /// Whether `url` is on one of the `chrome_domains`.
+    pub fn wants_chrome_for_url(&self, url: &str) -> bool {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        else {
+            return false;
+        };
+        self.chrome_domains.iter().any(|domain| {
+            let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+            !domain.is_empty()
+                && (host == domain
+                    || host
+                        .strip_suffix(&domain)
+                        .is_some_and(|rest| rest.ends_with('.')))
+        })
+    }
+
+    /// Whether `feed` is one of the `chrome_feeds`, by URL or title.
+    pub fn wants_chrome_for_feed(&self, feed: &Feed) -> bool {
+        self.chrome_feeds.iter().map(|f| f.trim()).any(|wanted| {
+            wanted == feed.url
+                || feed
+                    .title
+                    .as_deref()
+                    .is_some_and(|title| title.eq_ignore_ascii_case(wanted))
+        })
+    }
+
     /// Create a config optimized for speed (less accurate)
     pub fn fast() -> Self {
         Self {
@@ -142,12 +238,70 @@ mod tests {
         assert_eq!(config.timeout_secs, 30);
         assert_eq!(config.wait_after_load_ms, 1000);
         assert_eq!(config.max_concurrency, 5);
+        assert_eq!(config.max_pages, 5);
         assert!(config.block_images);
         assert!(config.block_stylesheets);
         assert!(!config.content_selectors.is_empty());
         assert!(!config.remove_selectors.is_empty());
     }
 
+    #[test]
+    fn test_chrome_domains_and_feeds() {
+        let config = ScraperConfig {
+            chrome_domains: vec!["Example.com".to_string(), ".app.test".to_string()],
+            chrome_feeds: vec!["JS Weekly".to_string()],
+            ..Default::default()
+        };
+        assert!(config.wants_chrome_for_url("https://example.com/post"));
+        assert!(config.wants_chrome_for_url("https://blog.EXAMPLE.com/post"));
+        assert!(config.wants_chrome_for_url("http://app.test/"));
+        assert!(!config.wants_chrome_for_url("https://notexample.com/post"));
+        assert!(!config.wants_chrome_for_url("not a url"));
+
+        let mut feed = Feed::new("https://jsweekly.test/rss".to_string());
+        assert!(!config.wants_chrome_for_feed(&feed));
+        feed.title = Some("js weekly".to_string());
+        assert!(config.wants_chrome_for_feed(&feed));
+    }
+
+    #[test]
+    fn test_with_recipe_adds_its_selectors() {
+        let config = ScraperConfig::default();
+        let recipe = Recipe {
+            url: "example".to_string(),
+            content: Some(".story-body".to_string()),
+            remove: vec![".paywall".to_string()],
+            next_page: Some(".pager .next".to_string()),
+            ..Default::default()
+        };
+
+        let merged = config.with_recipe(Some(&recipe));
+        assert_eq!(merged.content_selectors[0], ".story-body");
+        assert_eq!(
+            merged.content_selectors.len(),
+            config.content_selectors.len() + 1
+        );
+        assert_eq!(merged.remove_selectors.last().unwrap(), ".paywall");
+        assert_eq!(merged.next_page_selectors, vec![".pager .next"]);
+        assert_eq!(
+            config.with_recipe(None).content_selectors,
+            config.content_selectors
+        );
+    }
+
+    #[test]
+    fn test_recipe_set_rejects_invalid_selectors() {
+        assert!(ScraperConfig::default().recipe_set().is_ok());
+        let config = ScraperConfig {
+            remove_selectors: vec![".ad".to_string(), "div[".to_string()],
+            ..Default::default()
+        };
+        let err = config.recipe_set().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid [scraper] remove_selectors: bad selector \"div[\""));
+    }
+
     #[test]
     fn test_fast_config() {
         let config = ScraperConfig::fast();
@@ -1,6 +1,7 @@
-use crate::scraper::ScraperConfig;
+use crate::scraper::{readability, ScraperConfig};
 
 /// Content extractor for cleaning and extracting article content from HTML
+#[derive(Debug, Clone)]
 pub struct ContentExtractor {
     config: ScraperConfig,
 }
@@ -15,27 +16,42 @@ impl ContentExtractor {
     /// This JS runs in the browser context and:
     /// 1. Removes unwanted elements (ads, nav, etc.)
     /// 2. Finds the main content using configured selectors
-    /// 3. Returns the cleaned HTML content
+    /// 3. Returns the cleaned HTML content and the next page's address
     pub fn extraction_script(&self) -> String {
-        let remove_selectors = self
-            .config
-            .remove_selectors
-            .iter()
-            .map(|s| format!("'{}'", s.replace('\'', "\\'")))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let content_selectors = self
-            .config
-            .content_selectors
-            .iter()
-            .map(|s| format!("'{}'", s.replace('\'', "\\'")))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let remove_selectors = js_strings(&self.config.remove_selectors);
+        let content_selectors = js_strings(&self.config.content_selectors);
+        let next_page_selectors = js_strings(&self.config.next_page_selectors);
 
         format!(
             r#"
             (() => {{
+                // Find the next page before its links are removed. Only pages
+                // on this host count, and <a rel="next"> is left alone as it
+                // is usually the next post.
+                const samePage = url => url.split('#')[0] === location.href.split('#')[0];
+                const nextPage = link => {{
+                    if (!link) return null;
+                    try {{
+                        const url = new URL(link.href, location.href);
+                        const ok = /^https?:$/.test(url.protocol)
+                            && url.hostname === location.hostname
+                            && !samePage(url.href);
+                        return ok ? url.href : null;
+                    }} catch (e) {{
+                        return null;
+                    }}
+                }};
+                let next = null;
+                const nextPageSelectors = [{next_page_selectors}];
+                for (const selector of nextPageSelectors) {{
+                    const element = document.querySelector(selector);
+                    next = element && nextPage(element.href ? element : element.querySelector('a[href]'));
+                    if (next) break;
+                }}
+                if (!next) {{
+                    next = nextPage(document.querySelector('link[rel~="next" i][href]'));
+                }}
+
                 // Remove unwanted elements
                 const removeSelectors = [{remove_selectors}];
                 for (const selector of removeSelectors) {{
@@ -50,7 +66,8 @@ impl ContentExtractor {
                         return {{
                             html: element.innerHTML,
                             text: element.innerText,
-                            selector: selector
+                            selector: selector,
+                            next: next
                         }};
                     }}
                 }}
@@ -61,16 +78,63 @@ impl ContentExtractor {
                     return {{
                         html: body.innerHTML,
                         text: body.innerText,
-                        selector: 'body'
+                        selector: 'body',
+                        next: next
                     }};
                 }}
 
-                return {{ html: '', text: '', selector: null }};
+                return {{ html: '', text: '', selector: null, next: next }};
             }})()
             "#
         )
     }
 
+    /// Generate JavaScript that clicks every element matching `selectors`,
+    /// such as cookie or consent buttons, and returns how many it clicked
+    pub fn click_script(selectors: &[String]) -> String {
+        let selectors = js_strings(selectors);
+        format!(
+            r#"
+            (() => {{
+                let clicked = 0;
+                for (const selector of [{selectors}]) {{
+                    document.querySelectorAll(selector).forEach(el => {{
+                        el.click();
+                        clicked++;
+                    }});
+                }}
+                return clicked;
+            }})()
+            "#
+        )
+    }
+
+    /// Generate JavaScript that tells whether `selector` matches anything
+    pub fn presence_script(selector: &str) -> String {
+        format!(
+            "document.querySelector({}) !== null",
+            js_strings(&[selector.to_string()])
+        )
+    }
+
+    /// Extract the article from a fetched page without a browser, using the
+    /// same selectors as `extraction_script`. `None` if too little text is
+    /// found.
+    pub fn extract_article(&self, html: &str, page_url: &str) -> Option<String> {
+        readability::extract(
+            html,
+            Some(page_url),
+            &self.config.content_selectors,
+            &self.config.remove_selectors,
+        )
+    }
+
+    /// The page after `page_url` in a multi-page article, found by
+    /// `next_page_selectors` or `<link rel="next">`
+    pub fn next_page_url(&self, html: &str, page_url: &str) -> Option<String> {
+        readability::next_page_url(html, page_url, &self.config.next_page_selectors)
+    }
+
     /// Generate JavaScript to block resources for faster loading
     pub fn resource_blocking_script(&self) -> Option<String> {
         if !self.config.block_images && !self.config.block_stylesheets {
@@ -95,6 +159,15 @@ impl ContentExtractor {
     }
 }
 
+/// Selectors as a comma-separated list of single-quoted JS strings.
+fn js_strings(selectors: &[String]) -> String {
+    selectors
+        .iter()
+        .map(|s| format!("'{}'", s.replace('\'', "\\'")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +183,21 @@ mod tests {
         assert!(script.contains("article"));
     }
 
+    #[test]
+    fn test_click_and_presence_scripts_quote_selectors() {
+        let script = ContentExtractor::click_script(&[
+            "#accept".to_string(),
+            "button[title='OK']".to_string(),
+        ]);
+        assert!(script.contains("['#accept', 'button[title=\\'OK\\']']"));
+        assert!(script.contains("el.click()"));
+
+        assert_eq!(
+            ContentExtractor::presence_script(".story"),
+            "document.querySelector('.story') !== null"
+        );
+    }
+
     #[test]
     fn test_resource_blocking_script_both() {
         let config = ScraperConfig {
@@ -159,6 +247,7 @@ mod tests {
         let config = ScraperConfig {
             content_selectors: vec![".my-custom-content".to_string()],
             remove_selectors: vec![".my-ad".to_string()],
+            next_page_selectors: vec![".my-pager a".to_string()],
             ..Default::default()
         };
         let extractor = ContentExtractor::new(config);
@@ -166,5 +255,7 @@ mod tests {
 
         assert!(script.contains(".my-custom-content"));
         assert!(script.contains(".my-ad"));
+        assert!(script.contains("const nextPageSelectors = ['.my-pager a'];"));
+        assert!(!script.contains("a[rel"));
     }
 }
@@ -0,0 +1,330 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, USER_AGENT};
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::app::{Result, RivuletError};
+use crate::domain::Item;
+use crate::fetcher::http_fetcher::HttpFetcher;
+use crate::scraper::config::ScraperConfig;
+use crate::scraper::extractor::ContentExtractor;
+use crate::scraper::{RecipeSet, ScrapeResult, Scraper};
+
+/// Scraper that fetches pages over plain HTTP and extracts the article in
+/// Rust. It needs no browser but sees only the HTML the server sends, so
+/// pages built by JavaScript need `ChromeScraper`. Pages are fetched with the
+/// feed fetcher's client, with the scraper's `user_agent` and `timeout`.
+#[derive(Clone)]
+pub struct HttpScraper {
+    fetcher: HttpFetcher,
+    config: ScraperConfig,
+    recipes: RecipeSet,
+    semaphore: Arc<Semaphore>,
+}
+
+impl HttpScraper {
+    pub fn new(config: ScraperConfig, fetcher: HttpFetcher) -> Result<Self> {
+        let recipes = config
+            .recipe_set()
+            .map_err(|e| RivuletError::Config(e.to_string()))?;
+        let semaphore = Arc::new(Semaphore::new(config.max_concurrency.max(1)));
+
+        Ok(Self {
+            fetcher,
+            config,
+            recipes,
+            semaphore,
+        })
+    }
+
+    /// Fetches `url` and returns the page and its address after redirects.
+    async fn fetch_page(&self, url: &str) -> Result<(String, String)> {
+        let mut headers = HeaderMap::new();
+        if let Some(user_agent) = &self.config.user_agent {
+            let value = HeaderValue::from_str(user_agent)
+                .map_err(|e| RivuletError::Config(format!("Invalid user_agent: {}", e)))?;
+            headers.insert(USER_AGENT, value);
+        }
+        let (response, redirect) = self
+            .fetcher
+            .get(url, headers, Some(self.config.timeout()))
+            .await
+            .map_err(|e| RivuletError::Scraper(format!("Failed to fetch page: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RivuletError::Scraper(format!(
+                "HTTP {} fetching {}",
+                status, url
+            )));
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("text/html")
+            .to_ascii_lowercase();
+        if !content_type.contains("html") && !content_type.contains("xml") {
+            return Err(RivuletError::Scraper(format!(
+                "Not a web page: {}",
+                content_type
+            )));
+        }
+
+        let page_url = redirect.map_or_else(|| url.to_string(), |redirect| redirect.url);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| RivuletError::Scraper(format!("Failed to read page: {}", e)))?;
+        Ok((page_url, body))
+    }
+
+    /// Fetches and extracts `url` with its recipe's selectors, if a recipe
+    /// matches; `wait_for` and `click` need a browser and are ignored. Next
+    /// pages are followed up to `max_pages` and stitched onto the article;
+    /// one that fails ends the article there.
+    async fn scrape_page(&self, url: &str) -> Result<ScrapeResult> {
+        let extractor = ContentExtractor::new(self.config.with_recipe(self.recipes.find(url)));
+        let mut visited = HashSet::new();
+        let mut pages: Vec<String> = Vec::new();
+        let mut next = Some(url.to_string());
+
+        while let Some(url) = next.take() {
+            if pages.len() >= self.config.max_pages.max(1) || !visited.insert(url.clone()) {
+                break;
+            }
+            let (page_url, body) = match self.fetch_page(&url).await {
+                Ok(page) => page,
+                Err(e) if !pages.is_empty() => {
+                    warn!("Stopped at page {} of {}: {}", pages.len() + 1, url, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            visited.insert(page_url.clone());
+
+            match extractor.extract_article(&body, &page_url) {
+                Some(content) => pages.push(content),
+                None if pages.is_empty() => {
+                    return Err(RivuletError::Scraper(
+                        "No article content found; if the page needs JavaScript, \
+                         add its domain to [scraper] chrome_domains"
+                            .to_string(),
+                    ))
+                }
+                None => break,
+            }
+            next = extractor.next_page_url(&body, &page_url);
+        }
+
+        Ok(ScrapeResult {
+            content: pages.join("\n"),
+            is_html: true,
+        })
+    }
+}
+
+#[async_trait]
+impl Scraper for HttpScraper {
+    async fn scrape(&self, url: &str) -> Result<ScrapeResult> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|e| RivuletError::Scraper(format!("Semaphore error: {}", e)))?;
+
+        self.scrape_page(url).await
+    }
+
+    async fn scrape_items(
+        &self,
+        items: &[Item],
+        concurrency: usize,
+    ) -> Vec<(String, Result<ScrapeResult>)> {
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut handles = Vec::new();
+
+        for item in items {
+            if !Self::needs_scraping(item) {
+                continue;
+            }
+            let Some(ref url) = item.link else {
+                continue;
+            };
+
+            let item_id = item.id.clone();
+            let url = url.clone();
+            let sem = semaphore.clone();
+            let scraper = self.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = sem.acquire().await;
+                (item_id, scraper.scrape_page(&url).await)
+            }));
+        }
+
+        let mut results = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok(result) => results.push(result),
+                Err(e) => tracing::error!("Task join error: {}", e),
+            }
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::testing::serve_http;
+    use crate::scraper::Recipe;
+
+    fn response(content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_scrape_items_extracts_articles_over_http() {
+        let article = format!(
+            "<html><body><nav><a href=\"/\">Home</a></nav><article><p>{}</p>\
+             <p><a href=\"/more\">More</a></p></article></body></html>",
+            "A long enough paragraph of article text, with commas, \
+             to be found by the extractor and kept in the result."
+        );
+        let base = serve_http(vec![
+            ("/post", response("text/html; charset=utf-8", &article)),
+            (
+                "/empty",
+                response("text/html", "<body><div id=\"app\"></div></body>"),
+            ),
+            ("/file.pdf", response("application/pdf", "%PDF")),
+        ])
+        .await;
+
+        let item = |path: &str| {
+            let mut item = Item::new(1, "https://example.com/feed.xml", path);
+            item.link = Some(format!("{}{}", base, path));
+            item
+        };
+        let mut with_content = item("/skipped");
+        with_content.content = Some("x".repeat(200));
+        let items = vec![
+            item("/post"),
+            item("/empty"),
+            item("/file.pdf"),
+            with_content,
+        ];
+
+        let scraper = HttpScraper::new(ScraperConfig::default(), HttpFetcher::new()).unwrap();
+        let results = scraper.scrape_items(&items, 2).await;
+        assert_eq!(results.len(), 3);
+
+        let result = |item: &Item| {
+            results
+                .iter()
+                .find(|(id, _)| *id == item.id)
+                .map(|(_, result)| result.as_ref())
+                .unwrap()
+        };
+        let scraped = result(&items[0]).unwrap();
+        assert!(scraped.is_html);
+        assert!(scraped.content.contains("A long enough paragraph"));
+        assert!(scraped.content.contains(&format!("href=\"{}/more\"", base)));
+        assert!(!scraped.content.contains("Home"));
+        assert!(result(&items[1]).is_err());
+        assert!(result(&items[2]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recipe_selectors_apply_to_matching_pages() {
+        let page = "<html><body><div class=\"story\"><p>The story itself, long enough \
+             to be taken as the article, is found by the content selector of the recipe.</p>\
+             <div class=\"promo\">Subscribe for more stories like this one today</div>\
+             </div><article><p>A teaser paragraph the generic selectors would pick, \
+             with enough text to pass the minimum length check easily.</p></article>\
+             </body></html>";
+        let base = serve_http(vec![
+            ("/news/1", response("text/html", page)),
+            ("/blog/1", response("text/html", page)),
+        ])
+        .await;
+
+        let config = ScraperConfig {
+            recipes: vec![Recipe {
+                name: "news".to_string(),
+                url: "/news/".to_string(),
+                content: Some(".story".to_string()),
+                remove: vec![".promo".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let scraper = HttpScraper::new(config, HttpFetcher::new()).unwrap();
+
+        let news = scraper.scrape(&format!("{}/news/1", base)).await.unwrap();
+        assert!(news.content.contains("The story itself"));
+        assert!(!news.content.contains("Subscribe"));
+        assert!(!news.content.contains("teaser"));
+
+        let blog = scraper.scrape(&format!("{}/blog/1", base)).await.unwrap();
+        assert!(blog.content.contains("teaser"));
+        assert!(!blog.content.contains("The story itself"));
+    }
+
+    #[tokio::test]
+    async fn test_multi_page_articles_are_stitched() {
+        let page = |n: usize, next: &str| {
+            format!(
+                "<html><head><link rel=\"next\" href=\"{next}\"></head><body><article>\
+                 <p>Part {n} of the long story, with enough words in it to be extracted \
+                 as the article on its own, even without the other parts.</p></article>\
+                 <nav><a class=\"more\" href=\"{next}\">Continue reading</a></nav>\
+                 </body></html>"
+            )
+        };
+        let base = serve_http(vec![
+            ("/story", response("text/html", &page(1, "/story?page=2"))),
+            (
+                "/story?page=2",
+                response("text/html", &page(2, "/story?page=3")),
+            ),
+            // Links back to the first page, which is not followed again
+            ("/story?page=3", response("text/html", &page(3, "/story"))),
+            ("/broken", response("text/html", &page(1, "/missing"))),
+        ])
+        .await;
+        let url = format!("{}/story", base);
+
+        let scraper = HttpScraper::new(ScraperConfig::default(), HttpFetcher::new()).unwrap();
+        let story = scraper.scrape(&url).await.unwrap();
+        let parts: Vec<_> = (1..=3)
+            .map(|n| story.content.find(&format!("Part {} of", n)).unwrap())
+            .collect();
+        assert!(parts.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(story.content.matches("Part 1 of").count(), 1);
+
+        let scraper = HttpScraper::new(
+            ScraperConfig {
+                max_pages: 2,
+                ..Default::default()
+            },
+            HttpFetcher::new(),
+        )
+        .unwrap();
+        let story = scraper.scrape(&url).await.unwrap();
+        assert!(story.content.contains("Part 2 of"));
+        assert!(!story.content.contains("Part 3 of"));
+
+        // A next page that fails ends the article without losing the first
+        let story = scraper.scrape(&format!("{}/broken", base)).await.unwrap();
+        assert!(story.content.contains("Part 1 of"));
+    }
+}
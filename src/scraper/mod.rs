@@ -1,7 +1,11 @@
 //! Web scraping module for fetching full article content.
 //!
-//! This module provides browser-based content extraction for RSS feeds
-//! that only include summaries or metadata without full article content.
+//! This module provides content extraction for RSS feeds that only include
+//! summaries or metadata without full article content. `HttpScraper` fetches
+//! pages with reqwest and scores their text to find the article, the way
+//! browser reader modes do. `ChromeScraper` renders pages in headless Chrome
+//! for sites that need JavaScript, and `ArticleScraper` picks between the two
+//! per feed or domain (`chrome_feeds` / `chrome_domains`).
 //!
 //! # Architecture
 //!
@@ -12,10 +16,11 @@
 //! # Usage
 //!
 //! ```rust,ignore
-//! use rivulet::scraper::{ChromeScraper, Scraper, ScraperConfig};
+//! use rivulet::fetcher::http_fetcher::HttpFetcher;
+//! use rivulet::scraper::{ArticleScraper, Scraper, ScraperConfig};
 //!
 //! let config = ScraperConfig::default();
-//! let scraper = ChromeScraper::new(config).await?;
+//! let scraper = ArticleScraper::new(config, HttpFetcher::new())?;
 //!
 //! // Scrape a single URL
 //! let content = scraper.scrape("https://example.com/article").await?;
@@ -29,19 +34,26 @@
 //! ```rust,ignore
 //! use rivulet::scraper::{spawn_background_scraper, ScraperConfig};
 //!
-//! let handle = spawn_background_scraper(config, store);
+//! let handle = spawn_background_scraper(config, HttpFetcher::new(), store);
 //! handle.queue_items(items).await;
 //! ```
 
+mod article;
 mod background;
 mod chrome;
 mod config;
 mod extractor;
+mod http;
+mod readability;
+mod recipe;
 
+pub use article::ArticleScraper;
 pub use background::{spawn_background_scraper, BackgroundScraperHandle};
 pub use chrome::ChromeScraper;
 pub use config::ScraperConfig;
 pub use extractor::ContentExtractor;
+pub use http::HttpScraper;
+pub use recipe::{Recipe, RecipeSet};
 
 use crate::app::Result;
 use crate::domain::Item;
@@ -0,0 +1,578 @@
+//! Reader-mode article extraction in pure Rust, used by `HttpScraper`.
+//!
+//! The page is parsed with html5ever, through the `scraper` crate, and the
+//! `remove_selectors` are cut out. Each paragraph then scores its parent and grandparent by length and
+//! commas; scores are adjusted by tag and class names and damped by link
+//! density, and the best block, with siblings that score close to it, is the
+//! article, as in Readability. A `content_selectors` match with enough text
+//! narrows the search to that part of the page.
+
+use std::collections::HashMap;
+
+use ::scraper::error::SelectorErrorKind;
+use ::scraper::{ElementRef, Html, Node, Selector};
+use ego_tree::{NodeId, NodeRef};
+use url::Url;
+
+/// Text a `content_selectors` match needs before it is used, as in the
+/// Chrome extraction script; also the least an extracted article may have.
+pub const MIN_ARTICLE_TEXT: usize = 100;
+
+/// Paragraphs shorter than this don't score their ancestors.
+const MIN_PARAGRAPH_TEXT: usize = 25;
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Block-level elements; a `div` without any as children scores as a
+/// paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Never part of an article, whatever `remove_selectors` says.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "form", "button", "input",
+    "select", "textarea", "object",
+];
+
+/// Elements that score as paragraphs.
+const PARAGRAPH_ELEMENTS: &[&str] = &["p", "pre", "td"];
+
+/// Containers dropped from the article when their class looks like clutter
+/// or they are mostly links.
+const CLEANED_ELEMENTS: &[&str] = &[
+    "div", "section", "aside", "header", "footer", "nav", "ul", "ol", "table",
+];
+
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "body", "content", "entry", "main", "page", "post", "text", "blog", "story",
+];
+
+const NEGATIVE_HINTS: &[&str] = &[
+    "comment",
+    "footer",
+    "sidebar",
+    "share",
+    "social",
+    "related",
+    "promo",
+    "sponsor",
+    "nav",
+    "menu",
+    "widget",
+    "banner",
+    "advert",
+    "popup",
+    "newsletter",
+    "subscribe",
+];
+
+/// Attributes kept on extracted elements.
+const KEPT_ATTRIBUTES: &[&str] = &["href", "src", "alt", "title", "colspan", "rowspan", "start"];
+
+/// The article in `html` as HTML, or `None` if the page has too little text.
+/// Links and images are resolved against `page_url`.
+pub fn extract(
+    html: &str,
+    page_url: Option<&str>,
+    content_selectors: &[String],
+    remove_selectors: &[String],
+) -> Option<String> {
+    let mut doc = Html::parse_document(html);
+    let mut dropped: Vec<NodeId> = elements(doc.root_element())
+        .filter(|el| DROPPED_ELEMENTS.contains(&name(*el)) || el.attr("hidden").is_some())
+        .map(|el| el.id())
+        .collect();
+    for selector in parse_selectors(remove_selectors) {
+        dropped.extend(doc.root_element().select(&selector).map(|el| el.id()));
+    }
+    for id in dropped {
+        if let Some(mut node) = doc.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+
+    let body = elements(doc.root_element())
+        .find(|el| name(*el) == "body")
+        .unwrap_or(doc.root_element());
+    let root = parse_selectors(content_selectors)
+        .find_map(|selector| {
+            body.select(&selector)
+                .find(|el| text_len(*el) > MIN_ARTICLE_TEXT)
+        })
+        .unwrap_or(body);
+
+    let parts = match best_candidate(root) {
+        Some((top, scores)) => article_parts(root, top, &scores),
+        None => vec![root],
+    };
+    let base = page_url.and_then(|url| Url::parse(url).ok());
+    let mut out = String::new();
+    for part in parts {
+        if part == body {
+            write_children(*part, base.as_ref(), &mut out);
+        } else {
+            write_html(*part, base.as_ref(), &mut out);
+        }
+    }
+
+    (text_len(Html::parse_fragment(&out).root_element()) >= MIN_ARTICLE_TEXT).then_some(out)
+}
+
+/// The address of the article's next page: the first element matching one
+/// of `next_page_selectors` (or the first link inside it), otherwise the
+/// page's `<link rel="next">`. An `<a rel="next">` is not followed, as it
+/// often leads to the next post rather than the next page. Only addresses
+/// on the same host as `page_url`, other than `page_url` itself, are used.
+pub fn next_page_url(html: &str, page_url: &str, next_page_selectors: &[String]) -> Option<String> {
+    let doc = Html::parse_document(html);
+    let base = Url::parse(page_url).ok()?;
+    let href = |el: ElementRef| {
+        let href = el.attr("href")?.trim();
+        let mut url = base.join(href).ok()?;
+        url.set_fragment(None);
+        let current = base.as_str().split('#').next().unwrap_or_default();
+        (matches!(url.scheme(), "http" | "https")
+            && url.host_str() == base.host_str()
+            && url.as_str() != current)
+            .then(|| url.to_string())
+    };
+
+    let selected = parse_selectors(next_page_selectors).find_map(|selector| {
+        doc.root_element().select(&selector).find_map(|el| {
+            el.descendent_elements()
+                .filter(|&link| name(link) == "a" || link == el)
+                .find_map(href)
+        })
+    });
+    selected.or_else(|| {
+        elements(doc.root_element())
+            .filter(|el| name(*el) == "link")
+            .filter(|el| {
+                el.attr("rel").is_some_and(|rel| {
+                    rel.split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+                })
+            })
+            .find_map(href)
+    })
+}
+
+/// Whether `selector` is CSS the reader can match, and if not, why.
+pub fn check_selector(selector: &str) -> Result<(), String> {
+    match Selector::parse(selector) {
+        Ok(_) => Ok(()),
+        // Its message asks for a bug report, which this isn't
+        Err(SelectorErrorKind::UnexpectedSelectorParseError(_)) => {
+            Err(format!("bad selector \"{}\"", selector))
+        }
+        Err(e) => Err(format!("bad selector \"{}\": {}", selector, e)),
+    }
+}
+
+/// Config selectors are checked by `ScraperConfig::recipe_set` before a
+/// scraper is built, so none that fail to parse are expected here.
+fn parse_selectors(selectors: &[String]) -> impl Iterator<Item = Selector> + '_ {
+    selectors.iter().filter_map(|s| Selector::parse(s).ok())
+}
+
+/// The highest-scoring block under `root`, with every scored block's final
+/// score.
+fn best_candidate(root: ElementRef<'_>) -> Option<(ElementRef<'_>, HashMap<NodeId, f64>)> {
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    for el in elements(root) {
+        let is_paragraph =
+            PARAGRAPH_ELEMENTS.contains(&name(el)) || (name(el) == "div" && !has_block_child(el));
+        if !is_paragraph {
+            continue;
+        }
+        let text = text(el);
+        let length = text.chars().count();
+        if length < MIN_PARAGRAPH_TEXT {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (length / 100).min(3) as f64;
+
+        let parent = parent_element(el);
+        let grandparent = parent.and_then(parent_element);
+        for (ancestor, divisor) in [(parent, 1.0), (grandparent, 2.0)] {
+            let Some(ancestor) = ancestor.filter(|a| *a == root || is_inside(*a, root)) else {
+                continue;
+            };
+            *scores
+                .entry(ancestor.id())
+                .or_insert_with(|| initial_score(ancestor)) += score / divisor;
+        }
+    }
+
+    let mut top: Option<(ElementRef, f64)> = None;
+    for el in root.descendent_elements() {
+        let Some(score) = scores.get_mut(&el.id()) else {
+            continue;
+        };
+        *score *= 1.0 - link_density(el);
+        if top.is_none_or(|(_, best)| *score > best) {
+            top = Some((el, *score));
+        }
+    }
+    Some((top?.0, scores))
+}
+
+/// `top` and the siblings that look like more of the same article.
+fn article_parts<'a>(
+    root: ElementRef<'a>,
+    top: ElementRef<'a>,
+    scores: &HashMap<NodeId, f64>,
+) -> Vec<ElementRef<'a>> {
+    let parent = match top.parent() {
+        Some(parent) if top != root => parent,
+        _ => return vec![top],
+    };
+    let threshold = (scores[&top.id()] * 0.2).max(10.0);
+    parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|&sibling| {
+            if sibling == top {
+                return true;
+            }
+            if is_clutter(*sibling) {
+                return false;
+            }
+            if scores
+                .get(&sibling.id())
+                .is_some_and(|&score| score >= threshold)
+            {
+                return true;
+            }
+            name(sibling) == "p" && text_len(sibling) > 80 && link_density(sibling) < 0.25
+        })
+        .collect()
+}
+
+fn initial_score(el: ElementRef) -> f64 {
+    let tag = match name(el) {
+        "div" | "article" | "section" | "main" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag + class_weight(el)
+}
+
+/// +25 or -25 for each of the class and ID that looks like content or like
+/// clutter.
+fn class_weight(el: ElementRef) -> f64 {
+    ["class", "id"]
+        .into_iter()
+        .filter_map(|attr| el.attr(attr))
+        .map(|value| {
+            let value = value.to_ascii_lowercase();
+            if NEGATIVE_HINTS.iter().any(|hint| value.contains(hint)) {
+                -25.0
+            } else if POSITIVE_HINTS.iter().any(|hint| value.contains(hint)) {
+                25.0
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
+
+fn name(el: ElementRef<'_>) -> &str {
+    el.value().name()
+}
+
+fn parent_element(el: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    el.parent().and_then(ElementRef::wrap)
+}
+
+fn is_inside(el: ElementRef, ancestor: ElementRef) -> bool {
+    el.ancestors().any(|node| node.id() == ancestor.id())
+}
+
+/// Elements below `el`, in document order.
+fn elements(el: ElementRef<'_>) -> impl Iterator<Item = ElementRef<'_>> {
+    el.descendent_elements().skip(1)
+}
+
+fn has_block_child(el: ElementRef) -> bool {
+    el.child_elements()
+        .any(|child| BLOCK_ELEMENTS.contains(&name(child)))
+}
+
+/// Text with runs of whitespace collapsed.
+fn text(el: ElementRef) -> String {
+    let mut raw = String::new();
+    collect_text(*el, &mut raw);
+    raw.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn collect_text(node: NodeRef<Node>, out: &mut String) {
+    for child in node.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(_) => {
+                collect_text(child, out);
+                out.push(' ');
+            }
+            _ => {}
+        }
+    }
+}
+
+fn text_len(el: ElementRef) -> usize {
+    text(el).chars().count()
+}
+
+/// Share of the text under `el` that is link text.
+fn link_density(el: ElementRef) -> f64 {
+    let total = text_len(el);
+    if total == 0 {
+        return 0.0;
+    }
+    let links: usize = elements(el)
+        .filter(|child| name(*child) == "a")
+        .map(text_len)
+        .sum();
+    links as f64 / total as f64
+}
+
+/// Containers inside the article that look like clutter.
+fn is_clutter(node: NodeRef<Node>) -> bool {
+    ElementRef::wrap(node).is_some_and(|el| {
+        CLEANED_ELEMENTS.contains(&name(el)) && (class_weight(el) < 0.0 || link_density(el) > 0.5)
+    })
+}
+
+/// Writes `node` as HTML, keeping only `KEPT_ATTRIBUTES`.
+fn write_html(node: NodeRef<Node>, base: Option<&Url>, out: &mut String) {
+    let element = match node.value() {
+        Node::Text(text) => {
+            out.push_str(&html_escape::encode_text(&**text));
+            return;
+        }
+        Node::Element(element) => element,
+        _ => return,
+    };
+
+    out.push('<');
+    out.push_str(element.name());
+    for &kept in KEPT_ATTRIBUTES {
+        let mut value = element.attr(kept);
+        // Lazy-loaded images keep the real address in `data-src`
+        if kept == "src" && value.is_none_or(str::is_empty) {
+            value = element.attr("data-src");
+        }
+        let Some(value) = value else {
+            continue;
+        };
+        let value = match (kept, base) {
+            ("href" | "src", Some(base)) => base
+                .join(value)
+                .map(String::from)
+                .unwrap_or_else(|_| value.to_string()),
+            _ => value.to_string(),
+        };
+        out.push_str(&format!(
+            " {}=\"{}\"",
+            kept,
+            html_escape::encode_double_quoted_attribute(&value)
+        ));
+    }
+    out.push('>');
+    if VOID_ELEMENTS.contains(&element.name()) {
+        return;
+    }
+    write_children(node, base, out);
+    out.push_str(&format!("</{}>", element.name()));
+}
+
+/// Writes the children of `node`, leaving out clutter.
+fn write_children(node: NodeRef<Node>, base: Option<&Url>, out: &mut String) {
+    for child in node.children() {
+        if !is_clutter(child) {
+            write_html(child, base, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::ScraperConfig;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Post</title><style>p { color: red }</style></head>
+<body>
+  <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+  <div id="layout">
+    <div class="sidebar"><p>Popular posts, hand picked, every week, for you to read.</p></div>
+    <div class="story-body">
+      <h1>Why tokenizers matter</h1>
+      <p>Parsing HTML is messy, and real pages are worse: unclosed tags, stray
+      brackets, and scripts everywhere. A tolerant tokenizer copes with all of it.
+      <p>The second paragraph keeps going, with commas, clauses, and detail, so
+      it clearly reads like an article rather than navigation.
+      <img data-src="/img/diagram.png" alt="Diagram">
+      <ul class="share-links"><li><a href="https://social.example/share">Share</a></ul>
+      <p>See the <a href="notes.html">notes</a> for more background on the topic.</p>
+    </div>
+    <div class="comments"><p>First comment, which says nothing useful at all, really.</p></div>
+  </div>
+  <footer>Copyright</footer>
+</body></html>"#;
+
+    fn config_extract(html: &str) -> Option<String> {
+        let config = ScraperConfig::default();
+        extract(
+            html,
+            Some("https://example.com/blog/post"),
+            &config.content_selectors,
+            &config.remove_selectors,
+        )
+    }
+
+    #[test]
+    fn test_remove_selectors_take_any_css() {
+        let page = format!(
+            "<body><article><p>{}</p><p>{}</p><p class=\"note ad\">{}</p></article></body>",
+            "Kicker paragraph that the first-child selector should remove, with commas.",
+            "The body of the article is long enough, with commas, clauses, and detail, \
+             to pass the minimum length on its own.",
+            "Sponsored note, removed by an attribute selector with the ~= operator."
+        );
+        let remove = [
+            "article > p:first-child".to_string(),
+            "[class~=ad]".to_string(),
+        ];
+        let article = extract(&page, None, &[], &remove).unwrap();
+        assert!(article.contains("The body of the article"));
+        assert!(!article.contains("Kicker"));
+        assert!(!article.contains("Sponsored"));
+    }
+
+    #[test]
+    fn test_extract_scores_the_article_and_drops_clutter() {
+        let article = config_extract(PAGE).unwrap();
+        assert!(article.contains("Parsing HTML is messy"));
+        assert!(article.contains("The second paragraph keeps going"));
+        assert!(
+            article.contains(r#"<img src="https://example.com/img/diagram.png" alt="Diagram">"#)
+        );
+        assert!(article.contains(r#"<a href="https://example.com/blog/notes.html">notes</a>"#));
+        assert!(!article.contains("Popular posts"));
+        assert!(!article.contains("First comment"));
+        assert!(!article.contains("Share"));
+        assert!(!article.contains("Home"));
+        assert!(!article.contains("Copyright"));
+        assert!(!article.contains("class="));
+    }
+
+    #[test]
+    fn test_extract_uses_content_selector_match() {
+        let page = format!(
+            "<body><div class=\"teaser\"><p>{}</p><p>{}</p></div>\
+             <article><p>{}</p></article></body>",
+            "Teaser text, long enough to score, with commas, and more commas, here.",
+            "Another teaser paragraph, also long enough, to outscore the article.",
+            "The real article text lives inside the article element, which is \
+             long enough to pass the minimum length for a selector match."
+        );
+        let article = config_extract(&page).unwrap();
+        assert!(article.contains("The real article text"));
+        assert!(!article.contains("Teaser"));
+    }
+
+    #[test]
+    fn test_next_page_url_prefers_selector_then_rel_next() {
+        let page = "https://news.test/story?page=1#top";
+        let html = r#"<head><link rel="Next" href="?page=2"></head><body>
+            <a rel="next nofollow" href="/other">Other</a>
+            <div class="pager"><span>1</span><a href="/story?page=3">Continue reading</a></div>
+            <a class="self" href="?page=1#comments">Comments</a>
+            <a class="away" href="https://elsewhere.test/story?page=2">Mirror</a></body>"#;
+
+        assert_eq!(
+            next_page_url(html, page, &[]).as_deref(),
+            Some("https://news.test/story?page=2")
+        );
+        assert_eq!(
+            next_page_url(html, page, &[".missing".to_string(), ".pager".to_string()]).as_deref(),
+            Some("https://news.test/story?page=3")
+        );
+        assert_eq!(
+            next_page_url(
+                r#"<a class="self" href="?page=1#x">Again</a>"#,
+                page,
+                &[".self".to_string()]
+            ),
+            None
+        );
+        assert_eq!(next_page_url("<p>Only page</p>", page, &[]), None);
+    }
+
+    #[test]
+    fn test_next_page_url_ignores_next_posts_and_other_hosts() {
+        let page = "https://news.test/story";
+        // An `<a rel="next">` is usually the next post
+        assert_eq!(
+            next_page_url(r#"<a rel="next" href="/next-story">Next</a>"#, page, &[]),
+            None
+        );
+        assert_eq!(
+            next_page_url(
+                r#"<head><link rel="next" href="https://elsewhere.test/story?page=2"></head>"#,
+                page,
+                &[]
+            ),
+            None
+        );
+        assert_eq!(
+            next_page_url(
+                r#"<a class="away" href="https://elsewhere.test/story?page=2">Mirror</a>"#,
+                page,
+                &[".away".to_string()]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_rejects_pages_without_text() {
+        assert!(config_extract(
+            "<html><body><div id=\"app\"></div><script>render()</script></body></html>"
+        )
+        .is_none());
+    }
+}
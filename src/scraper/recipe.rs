@@ -0,0 +1,141 @@
+//! Per-site extraction recipes, from `[[scraper.recipes]]` in config.toml
+//! and from one TOML file per recipe in the `recipes` directory next to it.
+//! The first recipe whose `url` pattern matches a page decides how it is
+//! scraped; pages no recipe matches use the global `[scraper]` settings.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::config::ConfigError;
+use crate::scraper::readability::check_selector;
+
+/// How to scrape the pages of one site.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recipe {
+    pub name: String,
+    /// Case-insensitive regex searched for in the page URL
+    pub url: String,
+    /// Selector for the article, tried before `content_selectors`
+    pub content: Option<String>,
+    /// Selectors removed in addition to `remove_selectors`
+    pub remove: Vec<String>,
+    /// Selector for the link to the article's next page, tried before
+    /// `next_page_selectors`
+    pub next_page: Option<String>,
+    /// Chrome only: wait until this selector is on the page before extracting
+    pub wait_for: Option<String>,
+    /// Chrome only: elements clicked once the page loads, such as cookie or
+    /// consent buttons
+    pub click: Vec<String>,
+    /// `true` scrapes with Chrome and `false` with the built-in reader,
+    /// whatever `chrome_domains` and `chrome_feeds` say
+    pub javascript: Option<bool>,
+}
+
+/// Compiled recipes, in the order they are tried.
+#[derive(Debug, Clone, Default)]
+pub struct RecipeSet {
+    recipes: Vec<(Regex, Recipe)>,
+}
+
+impl RecipeSet {
+    /// Compiles the URL patterns and checks the `content`, `remove` and
+    /// `next_page` selectors, failing on the first invalid recipe. Unnamed
+    /// recipes are called `recipe N`.
+    pub fn compile(recipes: &[Recipe]) -> Result<Self, ConfigError> {
+        let recipes = recipes
+            .iter()
+            .enumerate()
+            .map(|(index, recipe)| {
+                let mut recipe = recipe.clone();
+                if recipe.name.trim().is_empty() {
+                    recipe.name = format!("recipe {}", index + 1);
+                }
+                let invalid = |message: String| ConfigError::InvalidRecipe {
+                    recipe: recipe.name.clone(),
+                    message,
+                };
+                if recipe.url.trim().is_empty() {
+                    return Err(invalid("needs a url pattern".to_string()));
+                }
+                let pattern = RegexBuilder::new(recipe.url.trim())
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| invalid(format!("bad url pattern: {}", e)))?;
+                for selector in recipe
+                    .content
+                    .iter()
+                    .chain(&recipe.remove)
+                    .chain(&recipe.next_page)
+                {
+                    check_selector(selector).map_err(&invalid)?;
+                }
+                Ok((pattern, recipe))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { recipes })
+    }
+
+    /// The recipe for `url`, if any matches.
+    pub fn find(&self, url: &str) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|(pattern, _)| pattern.is_match(url))
+            .map(|(_, recipe)| recipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(name: &str, url: &str) -> Recipe {
+        Recipe {
+            name: name.to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_first_matching_recipe_wins() {
+        let recipes = RecipeSet::compile(&[
+            recipe("news", r"^https://(www\.)?example\.com/news/"),
+            recipe("", r"example\.com"),
+        ])
+        .unwrap();
+
+        let find = |url: &str| recipes.find(url).map(|recipe| recipe.name.as_str());
+        assert_eq!(find("https://www.EXAMPLE.com/news/1"), Some("news"));
+        assert_eq!(find("https://example.com/blog/2"), Some("recipe 2"));
+        assert_eq!(find("https://other.test/"), None);
+    }
+
+    #[test]
+    fn test_invalid_recipes_are_rejected() {
+        let err = RecipeSet::compile(&[recipe("empty", " ")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid recipe \"empty\": needs a url pattern"
+        );
+        let err = RecipeSet::compile(&[recipe("", "(")]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid recipe \"recipe 1\": bad url pattern"));
+
+        let mut bad_selector = recipe("news", "example");
+        bad_selector.remove = vec![".ad".to_string(), "p:nonsense".to_string()];
+        let err = RecipeSet::compile(&[bad_selector]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid recipe \"news\": bad selector \"p:nonsense\""
+        );
+        let mut bad_selector = recipe("news", "example");
+        bad_selector.next_page = Some(".pager >".to_string());
+        let err = RecipeSet::compile(&[bad_selector]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid recipe \"news\": bad selector \".pager >\""));
+    }
+}
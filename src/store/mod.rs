@@ -1,8 +1,17 @@
+pub mod query;
 pub mod sqlite;
 
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
 use crate::app::Result;
-use crate::domain::{AuthProfile, Feed, FeedUpdate, Item, ItemState};
+use crate::domain::{
+    AuthProfile, Category, Enclosure, Feed, FeedHealth, FeedSchedule, FeedUpdate, FetchLogEntry,
+    Item, ItemRevision, ItemState,
+};
 
+pub use query::SearchQuery;
 pub use sqlite::SqliteStore;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,6 +24,31 @@ pub enum ItemListFilter {
     Archived,
 }
 
+impl ItemListFilter {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemListFilter::All => "all",
+            ItemListFilter::Unread => "unread",
+            ItemListFilter::Starred => "starred",
+            ItemListFilter::Queued => "queued",
+            ItemListFilter::Saved => "saved",
+            ItemListFilter::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "all" => Some(ItemListFilter::All),
+            "unread" => Some(ItemListFilter::Unread),
+            "starred" => Some(ItemListFilter::Starred),
+            "queued" => Some(ItemListFilter::Queued),
+            "saved" => Some(ItemListFilter::Saved),
+            "archived" => Some(ItemListFilter::Archived),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RefreshSource {
     Tui,
@@ -38,6 +72,8 @@ impl RefreshSource {
 pub struct AddItemsResult {
     pub count: usize,
     pub inserted_ids: Vec<String>,
+    /// Stored items whose feed entry changed; the old version is kept as a revision
+    pub updated_ids: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,12 +83,151 @@ pub struct FeedRefreshResult {
     pub inserted_item_ids: Vec<String>,
 }
 
+/// Items targeted by a bulk state change. Every field that is set must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemSelector {
+    pub item_id: Option<String>,
+    pub feed_id: Option<i64>,
+    /// Search query, as for `search_items`
+    pub search: Option<String>,
+    /// Published (or fetched, if undated) before this instant
+    pub older_than: Option<DateTime<Utc>>,
+}
+
+/// A bulk state change. `None` leaves that flag as it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemStateChange {
+    pub read: Option<bool>,
+    pub starred: Option<bool>,
+    pub queued: Option<bool>,
+    pub saved: Option<bool>,
+    pub archived: Option<bool>,
+    pub hidden: Option<bool>,
+}
+
+impl ItemStateChange {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `(flag column, timestamp column, value)` for each flag being changed.
+    pub(crate) fn columns(&self) -> Vec<(&'static str, &'static str, bool)> {
+        [
+            ("is_read", "read_at", self.read),
+            ("is_starred", "starred_at", self.starred),
+            ("is_queued", "queued_at", self.queued),
+            ("is_saved", "saved_at", self.saved),
+            ("is_archived", "archived_at", self.archived),
+            ("is_hidden", "hidden_at", self.hidden),
+        ]
+        .into_iter()
+        .filter_map(|(flag, at, value)| value.map(|v| (flag, at, v)))
+        .collect()
+    }
+}
+
+/// Age and count limits for one feed's items. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionLimits {
+    pub max_age_days: Option<u32>,
+    /// Newest items kept per feed, not counting exempt items
+    pub max_items: Option<usize>,
+}
+
+impl RetentionLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age_days.is_none() && self.max_items.is_none()
+    }
+}
+
+/// What `prune_items` removes. Starred, saved and queued items are always
+/// kept. Pruned items leave a tombstone so the next fetch doesn't re-add them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub default: RetentionLimits,
+    /// Per-feed overrides of `default`, keyed by feed ID
+    pub feeds: HashMap<i64, RetentionLimits>,
+    /// Completed refresh runs older than this are dropped; the latest run is always kept
+    pub refresh_run_days: Option<u32>,
+    /// Tombstones of pruned items older than this are dropped, letting the
+    /// items be fetched again if a feed still lists them
+    pub tombstone_days: Option<u32>,
+}
+
+impl RetentionPolicy {
+    pub fn limits_for(&self, feed_id: i64) -> RetentionLimits {
+        self.feeds.get(&feed_id).copied().unwrap_or(self.default)
+    }
+}
+
+/// What a prune removed, or would remove on a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// `(feed ID, items pruned)` for every feed that lost items
+    pub feed_items: Vec<(i64, usize)>,
+    pub refresh_runs: usize,
+    /// Expired tombstones
+    pub tombstones: usize,
+}
+
+impl PruneReport {
+    pub fn item_count(&self) -> usize {
+        self.feed_items.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.feed_items.is_empty() && self.refresh_runs == 0 && self.tombstones == 0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RecentItem {
     pub item: Item,
     pub feed_title: String,
     pub is_latest_refresh_item: bool,
     pub arrived_at: chrono::DateTime<chrono::Utc>,
+    /// Shared by copies of the same article in different feeds
+    pub group_id: String,
+    /// Titles of the other feeds that carried this article
+    pub also_in: Vec<String>,
+}
+
+/// A search result with an excerpt of where it matched.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub item: Item,
+    /// Text around the hits, which are wrapped in `query::HIT_START` and
+    /// `query::HIT_END`. `None` when the query has no free text.
+    pub snippet: Option<String>,
+}
+
+/// A search query saved under a name; shown as a smart feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub query: String,
+    pub filter: ItemListFilter,
+}
+
+impl SavedSearch {
+    /// Most items a smart feed lists; the best matches are kept.
+    pub const ITEM_LIMIT: usize = 1000;
+
+    /// The matching items, newest first like a feed.
+    pub fn items<S: Store + ?Sized>(&self, store: &S) -> Result<Vec<Item>> {
+        let mut items: Vec<Item> = store
+            .search_items(&self.query, self.filter, Self::ITEM_LIMIT)?
+            .into_iter()
+            .map(|hit| hit.item)
+            .collect();
+        items.sort_by(|a, b| {
+            b.published_at
+                .cmp(&a.published_at)
+                .then(b.fetched_at.cmp(&a.fetched_at))
+        });
+        Ok(items)
+    }
 }
 
 pub trait Store {
@@ -62,8 +237,32 @@ pub trait Store {
     fn get_feed_by_url(&self, url: &str) -> Result<Option<Feed>>;
     fn get_all_feeds(&self) -> Result<Vec<Feed>>;
     fn update_feed(&self, id: i64, update: &FeedUpdate) -> Result<()>;
+    /// Moves a feed to `url`, remembering the URL its items are keyed on.
+    /// Returns `false` without changing anything if another feed has `url`.
+    fn update_feed_url(&self, id: i64, url: &str) -> Result<bool>;
+    fn set_feed_active(&self, id: i64, is_active: bool) -> Result<()>;
     fn delete_feed(&self, id: i64) -> Result<()>;
 
+    // Refresh schedule operations
+    fn get_due_feeds(&self, now: DateTime<Utc>) -> Result<Vec<Feed>>;
+    fn get_feed_schedule(&self, feed_id: i64) -> Result<Option<FeedSchedule>>;
+    fn upsert_feed_schedule(&self, schedule: &FeedSchedule) -> Result<()>;
+    fn get_next_due_at(&self) -> Result<Option<DateTime<Utc>>>;
+
+    // Feed health operations
+    fn record_fetch_attempt(&self, entry: &FetchLogEntry) -> Result<()>;
+    fn get_fetch_log(&self, feed_id: i64, limit: usize) -> Result<Vec<FetchLogEntry>>;
+    fn get_feed_health(&self) -> Result<Vec<FeedHealth>>;
+    fn get_consecutive_failures(&self) -> Result<HashMap<i64, i64>>;
+
+    // Category operations
+    fn add_category(&self, category: &Category) -> Result<i64>;
+    fn get_all_categories(&self) -> Result<Vec<Category>>;
+    fn delete_category(&self, id: i64) -> Result<()>;
+    fn add_feed_to_category(&self, feed_id: i64, category_id: i64) -> Result<()>;
+    fn remove_feed_from_category(&self, feed_id: i64, category_id: i64) -> Result<()>;
+    fn get_feed_category_links(&self) -> Result<Vec<(i64, i64)>>;
+
     // Auth profile operations
     fn add_auth_profile(&self, profile: &AuthProfile) -> Result<i64>;
     fn get_auth_profile_by_name(&self, name: &str) -> Result<Option<AuthProfile>>;
@@ -74,6 +273,19 @@ pub trait Store {
     fn add_item(&self, item: &Item) -> Result<()>;
     fn add_items_with_report(&self, items: &[Item]) -> Result<AddItemsResult>;
     fn add_items(&self, items: &[Item]) -> Result<usize>;
+    fn get_enclosures(&self, item_id: &str) -> Result<Vec<Enclosure>>;
+    fn get_item_categories(&self, item_id: &str) -> Result<Vec<String>>;
+    /// Fills in the enclosures, categories and tags of every item with one
+    /// query per table rather than three per item.
+    fn load_item_media(&self, items: &mut [Item]) -> Result<()>;
+    /// Earlier versions of an item, oldest first.
+    fn get_item_revisions(&self, item_id: &str) -> Result<Vec<ItemRevision>>;
+    /// `get_item_revisions` for many items at once, keyed by item; items
+    /// without revisions are left out.
+    fn get_revisions_for_items(
+        &self,
+        item_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ItemRevision>>>;
     fn get_item(&self, id: &str) -> Result<Option<Item>>;
     fn get_items_by_feed(&self, feed_id: i64) -> Result<Vec<Item>>;
     fn get_all_items(&self) -> Result<Vec<Item>>;
@@ -85,7 +297,13 @@ pub trait Store {
         limit: usize,
         latest_run_id: Option<i64>,
     ) -> Result<Vec<RecentItem>>;
-    fn search_items(&self, query: &str, filter: ItemListFilter, limit: usize) -> Result<Vec<Item>>;
+    /// Items matching a query in the search language of `query`, best first.
+    fn search_items(
+        &self,
+        query: &str,
+        filter: ItemListFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>>;
     fn item_exists(&self, id: &str) -> Result<bool>;
     fn update_item_content(&self, id: &str, content: &str) -> Result<()>;
 
@@ -105,12 +323,66 @@ pub trait Store {
     ) -> Result<()>;
     fn get_latest_refresh_run_id(&self) -> Result<Option<i64>>;
 
+    // Retention operations
+    /// Deletes items and refresh runs outside `policy`, or only counts them
+    /// when `dry_run` is set.
+    fn prune_items(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<PruneReport>;
+    fn vacuum(&self) -> Result<()>;
+
     // State operations
     fn get_item_state(&self, item_id: &str) -> Result<Option<ItemState>>;
+    /// `get_item_state` for many items at once, keyed by item; items that
+    /// were never touched are left out.
+    fn get_item_states(&self, item_ids: &[String]) -> Result<HashMap<String, ItemState>>;
     fn set_read(&self, item_id: &str, is_read: bool) -> Result<()>;
     fn set_starred(&self, item_id: &str, is_starred: bool) -> Result<()>;
     fn set_queued(&self, item_id: &str, is_queued: bool) -> Result<()>;
     fn set_saved(&self, item_id: &str, is_saved: bool) -> Result<()>;
     fn set_archived(&self, item_id: &str, is_archived: bool) -> Result<()>;
+    /// Sets the read flag on many items; returns how many actually changed.
+    fn set_read_many(&self, item_ids: &[String], is_read: bool) -> Result<usize>;
+    /// Marks every unread item in the feeds that passes `filter`, and carries
+    /// `tag` if one is given, as read in one statement.
+    fn mark_feeds_read(
+        &self,
+        feed_ids: &[i64],
+        filter: ItemListFilter,
+        tag: Option<&str>,
+    ) -> Result<usize>;
+    /// How many items `mark_feeds_read` would mark.
+    fn count_unread_in_feeds(
+        &self,
+        feed_ids: &[i64],
+        filter: ItemListFilter,
+        tag: Option<&str>,
+    ) -> Result<usize>;
+    fn select_item_ids(&self, selector: &ItemSelector) -> Result<Vec<String>>;
+    /// Applies `change` to every item in one transaction; returns how many
+    /// items were updated.
+    fn set_item_states(&self, item_ids: &[String], change: &ItemStateChange) -> Result<usize>;
     fn get_unread_count(&self, feed_id: i64) -> Result<i64>;
+    fn get_unread_counts(&self) -> Result<HashMap<i64, i64>>;
+
+    // Tag operations
+    /// Adds `tag` to every item, creating the tag if needed; returns how many
+    /// items gained it.
+    fn tag_items(&self, item_ids: &[String], tag: &str) -> Result<usize>;
+    /// Removes `tag` from every item, dropping the tag once nothing carries
+    /// it; returns how many items lost it.
+    fn untag_items(&self, item_ids: &[String], tag: &str) -> Result<usize>;
+    fn get_item_tags(&self, item_id: &str) -> Result<Vec<String>>;
+    /// Every tag with the number of visible items carrying it.
+    fn get_tags(&self) -> Result<Vec<(String, usize)>>;
+    fn get_items_by_tag(&self, tag: &str, filter: ItemListFilter) -> Result<Vec<Item>>;
+
+    // Saved search operations
+    /// Saves `query` under `name`, replacing any search with that name.
+    /// Fails if the query isn't valid full-text syntax.
+    fn save_search(&self, name: &str, query: &str, filter: ItemListFilter) -> Result<i64>;
+    /// Returns `false` if no search has that name.
+    fn delete_saved_search(&self, name: &str) -> Result<bool>;
+    fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearch>>;
+    fn get_saved_searches(&self) -> Result<Vec<SavedSearch>>;
+    /// Unread items matching each saved search, keyed by search ID.
+    fn get_saved_search_unread_counts(&self) -> Result<HashMap<i64, i64>>;
 }
@@ -0,0 +1,487 @@
+//! The search query language shared by `rivulet search`, smart feeds, rules
+//! and the TUI: free text for the full-text index plus `feed:`, `author:`,
+//! `title:`, `tag:`, `before:`, `after:` and `is:` filters, `-` (or `NOT`)
+//! to exclude and `OR` between terms.
+//!
+//! User text never reaches FTS5 as syntax: every word is quoted, so inputs
+//! like `c++` or `foo-bar` search for what they say.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::app::{Result, RivuletError};
+use crate::daemon::DaemonConfig;
+use crate::html::strip_tags;
+use crate::store::ItemListFilter;
+
+/// Marks the start of a hit in a snippet.
+pub const HIT_START: char = '\u{2}';
+/// Marks the end of a hit in a snippet.
+pub const HIT_END: char = '\u{3}';
+
+/// Column a text term is restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Title,
+    Author,
+}
+
+impl TextField {
+    fn column(self) -> &'static str {
+        match self {
+            TextField::Title => "title",
+            TextField::Author => "author",
+        }
+    }
+}
+
+/// Item state tested by `is:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFlag {
+    Read,
+    Unread,
+    Starred,
+    Queued,
+    Saved,
+    Archived,
+}
+
+impl StateFlag {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "read" => Some(StateFlag::Read),
+            "unread" => Some(StateFlag::Unread),
+            "starred" | "star" => Some(StateFlag::Starred),
+            "queued" | "queue" => Some(StateFlag::Queued),
+            "saved" => Some(StateFlag::Saved),
+            "archived" => Some(StateFlag::Archived),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    /// Matched against the full-text index, or one column of it
+    Text {
+        field: Option<TextField>,
+        text: String,
+        prefix: bool,
+    },
+    /// Part of the feed title or URL
+    Feed(String),
+    Tag(String),
+    /// Published (or fetched, if undated) before this instant
+    Before(DateTime<Utc>),
+    /// Published (or fetched, if undated) at or after this instant
+    After(DateTime<Utc>),
+    Is(StateFlag),
+}
+
+impl Term {
+    /// The term as a quoted FTS5 expression; `None` for filters.
+    pub fn fts_expression(&self) -> Option<String> {
+        let Term::Text {
+            field,
+            text,
+            prefix,
+        } = self
+        else {
+            return None;
+        };
+        let mut expression = format!("\"{}\"", text.replace('"', "\"\""));
+        if *prefix {
+            expression.push('*');
+        }
+        if let Some(field) = field {
+            expression = format!("{} : {}", field.column(), expression);
+        }
+        Some(expression)
+    }
+}
+
+/// Terms joined by `OR`. A negated clause excludes what it matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    pub negated: bool,
+    pub terms: Vec<Term>,
+}
+
+impl Clause {
+    /// Positive and made only of text terms, so it can be ranked.
+    fn is_full_text(&self) -> bool {
+        !self.negated
+            && self
+                .terms
+                .iter()
+                .all(|term| matches!(term, Term::Text { .. }))
+    }
+}
+
+/// A parsed query: every clause must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub clauses: Vec<Clause>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_at(input, Utc::now())
+    }
+
+    /// Parses with relative dates (`after:7d`) counted back from `now`.
+    pub fn parse_at(input: &str, now: DateTime<Utc>) -> Result<Self> {
+        let mut clauses: Vec<Clause> = Vec::new();
+        let mut negate_next = false;
+        let mut or_next = false;
+
+        for token in tokens(input) {
+            match token.as_str() {
+                "OR" => {
+                    or_next = true;
+                    continue;
+                }
+                "AND" => continue,
+                "NOT" => {
+                    negate_next = true;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (negated, body) = match token.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, token.as_str()),
+            };
+            let negated = negated || std::mem::take(&mut negate_next);
+            let Some(term) = parse_term(body, now)? else {
+                continue;
+            };
+
+            match clauses.last_mut() {
+                Some(last) if std::mem::take(&mut or_next) && !negated && !last.negated => {
+                    last.terms.push(term)
+                }
+                _ => clauses.push(Clause {
+                    negated,
+                    terms: vec![term],
+                }),
+            }
+        }
+
+        Ok(Self { clauses })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// FTS5 expression for the clauses that rank results and give snippets:
+    /// the positive, text-only ones.
+    pub fn fts_expression(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .clauses
+            .iter()
+            .filter(|clause| clause.is_full_text())
+            .map(|clause| {
+                let alternatives: Vec<String> = clause
+                    .terms
+                    .iter()
+                    .filter_map(Term::fts_expression)
+                    .collect();
+                format!("({})", alternatives.join(" OR "))
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" AND "))
+    }
+
+    /// The clauses left out of `fts_expression`, which need SQL.
+    pub fn filter_clauses(&self) -> impl Iterator<Item = &Clause> {
+        self.clauses.iter().filter(|clause| !clause.is_full_text())
+    }
+
+    /// `is:archived` looks in the archive, which views otherwise leave out.
+    pub fn view_filter(&self, filter: ItemListFilter) -> ItemListFilter {
+        let wants_archived = self
+            .clauses
+            .iter()
+            .any(|clause| !clause.negated && clause.terms == [Term::Is(StateFlag::Archived)]);
+        if wants_archived && filter == ItemListFilter::All {
+            ItemListFilter::Archived
+        } else {
+            filter
+        }
+    }
+
+    /// Lowercase words the query searches for, for highlighting. Single
+    /// characters are left out as they would light up every word.
+    pub fn highlight_terms(&self) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        for clause in self.clauses.iter().filter(|clause| !clause.negated) {
+            for term in &clause.terms {
+                let Term::Text { text, .. } = term else {
+                    continue;
+                };
+                for word in text.split(|c: char| !c.is_alphanumeric()) {
+                    let word = word.to_lowercase();
+                    if word.chars().count() > 1 && !words.contains(&word) {
+                        words.push(word);
+                    }
+                }
+            }
+        }
+        words
+    }
+}
+
+/// Splits on whitespace outside double quotes. An unclosed quote runs to
+/// the end, so a phrase still being typed is searched as it stands.
+fn tokens(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in input.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+            current.push(c);
+        } else if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Strips quotes; a trailing `*` outside them asks for a prefix match.
+fn unquote(value: &str) -> (String, bool) {
+    let (value, prefix) = match value.strip_suffix('*') {
+        Some(rest) => (rest.trim_end_matches('*'), true),
+        None => (value, false),
+    };
+    let value = value
+        .strip_prefix('"')
+        .map_or(value, |inner| inner.strip_suffix('"').unwrap_or(inner));
+    (value.to_string(), prefix)
+}
+
+fn parse_term(token: &str, now: DateTime<Utc>) -> Result<Option<Term>> {
+    let field = token
+        .split_once(':')
+        .filter(|(key, _)| !key.starts_with('"'))
+        .map(|(key, value)| (key.to_ascii_lowercase(), value));
+
+    let Some((key, value)) = field.filter(|(key, _)| {
+        matches!(
+            key.as_str(),
+            "feed" | "author" | "title" | "tag" | "before" | "after" | "is"
+        )
+    }) else {
+        let (text, prefix) = unquote(token);
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some(Term::Text {
+            field: None,
+            text,
+            prefix,
+        }));
+    };
+
+    let (value, prefix) = unquote(value);
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err(invalid(format!("{}: needs a value", key)));
+    }
+
+    let term = match key.as_str() {
+        "feed" => Term::Feed(value),
+        "tag" => Term::Tag(value),
+        "title" | "author" => Term::Text {
+            field: Some(if key == "title" {
+                TextField::Title
+            } else {
+                TextField::Author
+            }),
+            text: value,
+            prefix,
+        },
+        "before" => Term::Before(parse_date(&value, now)?),
+        "after" => Term::After(parse_date(&value, now)?),
+        _ => Term::Is(StateFlag::parse(&value).ok_or_else(|| {
+            invalid(format!(
+                "unknown is:{}; use read, unread, starred, queued, saved or archived",
+                value
+            ))
+        })?),
+    };
+    Ok(Some(term))
+}
+
+/// A `YYYY-MM-DD` date (midnight UTC) or an age like `7d` or `12h`.
+fn parse_date(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    let secs = DaemonConfig::parse_interval(value).map_err(|_| {
+        invalid(format!(
+            "bad date {}; use YYYY-MM-DD or an age like 7d",
+            value
+        ))
+    })?;
+    Ok(now - chrono::Duration::seconds(secs as i64))
+}
+
+fn invalid(message: String) -> RivuletError {
+    RivuletError::InvalidQuery(message)
+}
+
+/// Tidies an FTS5 snippet taken from HTML: drops markup, including a tag
+/// cut off at either end, and collapses whitespace. Hit markers are kept.
+pub fn clean_snippet(raw: &str) -> String {
+    let raw = match (raw.find('>'), raw.find('<')) {
+        (Some(close), open) if open.is_none_or(|open| close < open) => &raw[close + 1..],
+        _ => raw,
+    };
+    strip_tags(raw)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a snippet into text runs, each flagged if it is a hit.
+pub fn snippet_parts(snippet: &str) -> Vec<(&str, bool)> {
+    let mut parts = Vec::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find(HIT_START) {
+        if start > 0 {
+            parts.push((&rest[..start], false));
+        }
+        rest = &rest[start + HIT_START.len_utf8()..];
+        let end = rest.find(HIT_END).unwrap_or(rest.len());
+        parts.push((&rest[..end], true));
+        rest = rest.get(end + HIT_END.len_utf8()..).unwrap_or("");
+    }
+    if !rest.is_empty() {
+        parts.push((rest, false));
+    }
+    parts
+}
+
+/// The snippet with each hit wrapped in `open` and `close`.
+pub fn render_snippet(snippet: &str, open: &str, close: &str) -> String {
+    snippet_parts(snippet)
+        .into_iter()
+        .map(|(text, hit)| {
+            if hit {
+                format!("{}{}{}", open, text, close)
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Term {
+        Term::Text {
+            field: None,
+            text: value.to_string(),
+            prefix: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_quotes_words_so_punctuation_is_not_syntax() {
+        let query = SearchQuery::parse("c++ foo-bar \"borrow checker\"").unwrap();
+        assert_eq!(
+            query.fts_expression().as_deref(),
+            Some("(\"c++\") AND (\"foo-bar\") AND (\"borrow checker\")")
+        );
+        assert_eq!(
+            query.highlight_terms(),
+            vec!["foo", "bar", "borrow", "checker"]
+        );
+    }
+
+    #[test]
+    fn test_parse_fields_operators_and_dates() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let query = SearchQuery::parse_at(
+            "rust OR go title:async* -feed:Spam NOT tag:old after:7d before:2024-06-01 is:unread",
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(query.clauses[0].terms, vec![text("rust"), text("go")]);
+        assert_eq!(
+            query.fts_expression().as_deref(),
+            Some("(\"rust\" OR \"go\") AND (title : \"async\"*)")
+        );
+        let filters: Vec<_> = query.filter_clauses().cloned().collect();
+        assert_eq!(
+            filters,
+            vec![
+                Clause {
+                    negated: true,
+                    terms: vec![Term::Feed("Spam".into())]
+                },
+                Clause {
+                    negated: true,
+                    terms: vec![Term::Tag("old".into())]
+                },
+                Clause {
+                    negated: false,
+                    terms: vec![Term::After(now - chrono::Duration::days(7))]
+                },
+                Clause {
+                    negated: false,
+                    terms: vec![Term::Before(
+                        "2024-06-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+                    )]
+                },
+                Clause {
+                    negated: false,
+                    terms: vec![Term::Is(StateFlag::Unread)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_filters() {
+        for input in ["feed:", "is:shiny", "before:last-tuesday"] {
+            assert!(matches!(
+                SearchQuery::parse(input),
+                Err(RivuletError::InvalidQuery(_))
+            ));
+        }
+        // Unknown keys, like a URL's scheme, are plain text
+        let query = SearchQuery::parse("https://example.com").unwrap();
+        assert_eq!(query.clauses[0].terms, vec![text("https://example.com")]);
+    }
+
+    #[test]
+    fn test_snippets_are_cleaned_and_split_into_hits() {
+        let raw = "ref=\"x\">Learn <b>\u{2}Rust\u{3}</b> &amp; \u{2}async\u{3} <a hr";
+        let snippet = clean_snippet(raw);
+        assert_eq!(snippet, "Learn \u{2}Rust\u{3} & \u{2}async\u{3}");
+        assert_eq!(
+            snippet_parts(&snippet),
+            vec![
+                ("Learn ", false),
+                ("Rust", true),
+                (" & ", false),
+                ("async", true)
+            ]
+        );
+        assert_eq!(render_snippet(&snippet, "[", "]"), "Learn [Rust] & [async]");
+    }
+}
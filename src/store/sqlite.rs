@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Mutex;
 
@@ -6,8 +7,49 @@ use rusqlite::{params, Connection, OptionalExtension};
 use rusqlite_migration::{Migrations, M};
 
 use crate::app::{Result, RivuletError};
-use crate::domain::{AuthProfile, Feed, FeedUpdate, Item, ItemState};
-use crate::store::{AddItemsResult, ItemListFilter, RecentItem, RefreshSource, Store};
+use crate::domain::{
+    AuthProfile, Category, Enclosure, Feed, FeedHealth, FeedSchedule, FeedUpdate, FetchLogEntry,
+    Item, ItemRevision, ItemState,
+};
+use crate::store::query::{self, SearchQuery, StateFlag, Term};
+use crate::store::{
+    AddItemsResult, ItemListFilter, ItemSelector, ItemStateChange, PruneReport, RecentItem,
+    RefreshSource, RetentionLimits, RetentionPolicy, SavedSearch, SearchHit, Store,
+};
+
+/// Item IDs bound per statement by `set_read_many`.
+const MARK_READ_CHUNK: usize = 500;
+
+/// Item IDs bound per statement when pruning.
+const PRUNE_CHUNK: usize = 500;
+
+/// Item IDs bound per statement when loading media, revisions or states for
+/// many items.
+const LOAD_CHUNK: usize = 500;
+
+/// Columns read by `row_to_item_state`, in order.
+const ITEM_STATE_COLUMNS: &str = "item_id, is_read, is_starred, is_queued, is_saved, is_archived,
+     read_at, starred_at, queued_at, saved_at, archived_at, is_hidden, hidden_at";
+
+/// Items loaded at a time when backfilling duplicate keys.
+const BACKFILL_PAGE: i64 = 500;
+
+/// Fetch log entries kept per feed; older attempts are trimmed on insert.
+const FETCH_LOG_KEEP: i64 = 50;
+
+/// Earlier versions kept per item; older revisions are trimmed on update.
+const REVISIONS_KEEP: i64 = 10;
+
+/// Tokens of context in a search result snippet.
+const SNIPPET_TOKENS: i64 = 16;
+
+/// Outcome of writing one fetched item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemWrite {
+    Inserted,
+    Updated,
+    Unchanged,
+}
 
 pub struct SqliteStore {
     conn: Mutex<Connection>,
@@ -39,6 +81,17 @@ impl SqliteStore {
             M::up(include_str!("../../migrations/003-search-index/up.sql")),
             M::up(include_str!("../../migrations/004-auth-profiles/up.sql")),
             M::up(include_str!("../../migrations/005-refresh-runs/up.sql")),
+            M::up(include_str!("../../migrations/006-categories/up.sql")),
+            M::up(include_str!("../../migrations/007-feed-schedules/up.sql")),
+            M::up(include_str!("../../migrations/008-feed-fetch-log/up.sql")),
+            M::up(include_str!("../../migrations/009-feed-redirects/up.sql")),
+            M::up(include_str!("../../migrations/010-enclosures/up.sql")),
+            M::up(include_str!("../../migrations/011-item-tombstones/up.sql")),
+            M::up(include_str!("../../migrations/012-duplicate-groups/up.sql")),
+            M::up(include_str!("../../migrations/013-item-revisions/up.sql")),
+            M::up(include_str!("../../migrations/014-hidden-items/up.sql")),
+            M::up(include_str!("../../migrations/015-tag-search/up.sql")),
+            M::up(include_str!("../../migrations/016-saved-searches/up.sql")),
         ]);
 
         let mut conn = self.conn.lock().map_err(|e| {
@@ -53,6 +106,7 @@ impl SqliteStore {
             .to_latest(&mut conn)
             .map_err(|_| RivuletError::Database(rusqlite::Error::InvalidQuery))?;
         Self::sweep_orphaned_refresh_runs_locked(&conn)?;
+        Self::backfill_duplicate_groups_locked(&mut conn)?;
 
         Ok(())
     }
@@ -81,17 +135,71 @@ impl SqliteStore {
                 .ok()
                 .and_then(|s| Self::parse_datetime(&s))
                 .unwrap_or_else(Utc::now),
+            updated_at: row
+                .get::<_, Option<String>>(9)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            enclosures: Vec::new(),
+            categories: Vec::new(),
+            tags: Vec::new(),
+        })
+    }
+
+    fn row_to_item_state(row: &rusqlite::Row<'_>) -> rusqlite::Result<ItemState> {
+        Ok(ItemState {
+            item_id: row.get(0)?,
+            is_read: row.get::<_, i32>(1)? != 0,
+            is_starred: row.get::<_, i32>(2)? != 0,
+            is_queued: row.get::<_, i32>(3)? != 0,
+            is_saved: row.get::<_, i32>(4)? != 0,
+            is_archived: row.get::<_, i32>(5)? != 0,
+            read_at: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            starred_at: row
+                .get::<_, Option<String>>(7)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            queued_at: row
+                .get::<_, Option<String>>(8)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            saved_at: row
+                .get::<_, Option<String>>(9)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            archived_at: row
+                .get::<_, Option<String>>(10)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            is_hidden: row.get::<_, i32>(11)? != 0,
+            hidden_at: row
+                .get::<_, Option<String>>(12)?
+                .and_then(|s| Self::parse_datetime(&s)),
+        })
+    }
+
+    fn row_to_revision(row: &rusqlite::Row<'_>) -> rusqlite::Result<ItemRevision> {
+        Ok(ItemRevision {
+            item_id: row.get(0)?,
+            title: row.get(1)?,
+            link: row.get(2)?,
+            content: row.get(3)?,
+            summary: row.get(4)?,
+            updated_at: row
+                .get::<_, Option<String>>(5)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            replaced_at: row
+                .get::<_, String>(6)
+                .ok()
+                .and_then(|s| Self::parse_datetime(&s))
+                .unwrap_or_else(Utc::now),
         })
     }
 
     fn row_to_recent_item(row: &rusqlite::Row<'_>) -> rusqlite::Result<RecentItem> {
         let item = Self::row_to_item(row)?;
         let feed_title = row
-            .get::<_, Option<String>>(9)?
+            .get::<_, Option<String>>(10)?
             .unwrap_or_else(|| "(Unknown feed)".to_string());
-        let is_latest_refresh_item = row.get::<_, i32>(10)? != 0;
+        let is_latest_refresh_item = row.get::<_, i32>(11)? != 0;
         let arrived_at = row
-            .get::<_, String>(11)
+            .get::<_, String>(12)
             .ok()
             .and_then(|s| Self::parse_datetime(&s))
             .unwrap_or(item.fetched_at);
@@ -101,9 +209,35 @@ impl SqliteStore {
             feed_title,
             is_latest_refresh_item,
             arrived_at,
+            group_id: row.get(13)?,
+            also_in: Vec::new(),
         })
     }
 
+    /// Keeps the first copy of each duplicate group and lists the feeds of
+    /// the others in its `also_in`.
+    fn collapse_duplicates(recent: Vec<RecentItem>) -> Vec<RecentItem> {
+        let mut collapsed: Vec<RecentItem> = Vec::with_capacity(recent.len());
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for entry in recent {
+            match positions.get(&entry.group_id) {
+                Some(&index) => {
+                    let first = &mut collapsed[index];
+                    if first.feed_title != entry.feed_title
+                        && !first.also_in.contains(&entry.feed_title)
+                    {
+                        first.also_in.push(entry.feed_title);
+                    }
+                }
+                None => {
+                    positions.insert(entry.group_id.clone(), collapsed.len());
+                    collapsed.push(entry);
+                }
+            }
+        }
+        collapsed
+    }
+
     fn sweep_orphaned_refresh_runs_locked(conn: &Connection) -> Result<()> {
         conn.execute(
             "UPDATE refresh_runs
@@ -116,6 +250,74 @@ impl SqliteStore {
         Ok(())
     }
 
+    /// Items in a feed that fall outside `limits`, oldest last. Starred,
+    /// saved and queued items are never returned and don't count toward
+    /// `max_items`.
+    fn prunable_item_ids_locked(
+        conn: &Connection,
+        feed_id: i64,
+        limits: RetentionLimits,
+    ) -> Result<Vec<String>> {
+        let max_age = limits.max_age_days.map(|days| format!("-{} days", days));
+        let max_items = limits.max_items.map(|n| n as i64);
+        let mut stmt = conn.prepare(
+            "SELECT id FROM (
+                 SELECT i.id,
+                        COALESCE(i.published_at, i.fetched_at) AS dated_at,
+                        ROW_NUMBER() OVER (
+                            ORDER BY julianday(COALESCE(i.published_at, i.fetched_at)) DESC,
+                                     julianday(i.fetched_at) DESC,
+                                     i.id
+                        ) AS position
+                 FROM items i
+                 LEFT JOIN item_state st ON st.item_id = i.id
+                 WHERE i.feed_id = ?1
+                   AND COALESCE(st.is_starred, 0) = 0
+                   AND COALESCE(st.is_saved, 0) = 0
+                   AND COALESCE(st.is_queued, 0) = 0
+             )
+             WHERE (?2 IS NOT NULL AND julianday(dated_at) < julianday('now', ?2))
+                OR (?3 IS NOT NULL AND position > ?3)
+             ORDER BY position",
+        )?;
+        let ids = stmt
+            .query_map(params![feed_id, max_age, max_items], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Deletes items and their search rows, leaving a tombstone for each.
+    /// State, enclosures, categories and refresh run entries go with them
+    /// through `ON DELETE CASCADE`.
+    fn delete_items_locked(conn: &Connection, item_ids: &[String]) -> Result<()> {
+        let deleted_at = Utc::now().to_rfc3339();
+        for chunk in item_ids.chunks(PRUNE_CHUNK) {
+            let placeholders: Vec<String> =
+                (0..chunk.len()).map(|i| format!("?{}", i + 1)).collect();
+            let placeholders = placeholders.join(", ");
+            let mut values: Vec<rusqlite::types::Value> =
+                chunk.iter().map(|id| id.clone().into()).collect();
+            values.push(deleted_at.clone().into());
+            conn.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO item_tombstones (item_id, feed_id, deleted_at)
+                     SELECT id, feed_id, ?{} FROM items WHERE id IN ({placeholders})",
+                    chunk.len() + 1
+                ),
+                rusqlite::params_from_iter(&values),
+            )?;
+            conn.execute(
+                &format!("DELETE FROM item_search WHERE item_id IN ({placeholders})"),
+                rusqlite::params_from_iter(chunk),
+            )?;
+            conn.execute(
+                &format!("DELETE FROM items WHERE id IN ({placeholders})"),
+                rusqlite::params_from_iter(chunk),
+            )?;
+        }
+        Ok(())
+    }
+
     fn get_items_where(&self, where_clause: Option<&str>) -> Result<Vec<Item>> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
@@ -126,7 +328,8 @@ impl SqliteStore {
 
         let sql = match where_clause {
             Some(where_clause) => format!(
-                "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author, i.published_at, i.fetched_at
+                "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author, i.published_at, i.fetched_at,
+                        i.updated_at
                  FROM items i
                  LEFT JOIN item_state s ON i.id = s.item_id
                  WHERE {}
@@ -134,7 +337,7 @@ impl SqliteStore {
                 where_clause
             ),
             None => {
-                "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at
+                "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at, updated_at
                  FROM items ORDER BY published_at DESC, fetched_at DESC"
                     .to_string()
             }
@@ -149,7 +352,8 @@ impl SqliteStore {
     }
 
     fn filter_clause(filter: ItemListFilter, state_alias: &str) -> String {
-        match filter {
+        let visible = format!("({state_alias}.is_hidden IS NULL OR {state_alias}.is_hidden = 0)");
+        let view = match filter {
             ItemListFilter::All => {
                 format!("({state_alias}.is_archived IS NULL OR {state_alias}.is_archived = 0)")
             }
@@ -166,6 +370,125 @@ impl SqliteStore {
                 "{state_alias}.is_saved = 1 AND ({state_alias}.is_archived IS NULL OR {state_alias}.is_archived = 0)"
             ),
             ItemListFilter::Archived => format!("{state_alias}.is_archived = 1"),
+        };
+        format!("{view} AND {visible}")
+    }
+
+    /// The unread items of `feed_ids` that pass `filter` (and carry `tag`, if
+    /// given), over `items i` left joined to `item_state st`. Placeholders
+    /// are numbered from `first`.
+    fn unread_in_feeds_clause(
+        feed_ids: &[i64],
+        filter: ItemListFilter,
+        tag: Option<&str>,
+        first: usize,
+    ) -> (String, Vec<rusqlite::types::Value>) {
+        let placeholders: Vec<String> = (0..feed_ids.len())
+            .map(|i| format!("?{}", i + first))
+            .collect();
+        let mut clause = format!(
+            "i.feed_id IN ({}) AND {} AND COALESCE(st.is_read, 0) = 0",
+            placeholders.join(", "),
+            Self::filter_clause(filter, "st")
+        );
+        let mut values: Vec<rusqlite::types::Value> =
+            feed_ids.iter().map(|id| (*id).into()).collect();
+        if let Some(tag) = tag {
+            clause.push_str(&format!(
+                " AND i.id IN (SELECT it.item_id FROM item_tags it
+                               JOIN tags t ON t.id = it.tag_id
+                               WHERE t.name = ?{})",
+                feed_ids.len() + first
+            ));
+            values.push(tag.to_string().into());
+        }
+        (clause, values)
+    }
+
+    /// SQL conditions on items `i` for a parsed query, binding into
+    /// `values`. With `fts_joined` the ranked part (`fts_expression`) is
+    /// left to the caller, which matches it against `item_search` itself.
+    fn search_conditions(
+        query: &SearchQuery,
+        fts_joined: bool,
+        values: &mut Vec<rusqlite::types::Value>,
+    ) -> Vec<String> {
+        let mut conditions = Vec::new();
+        if !fts_joined {
+            if let Some(expression) = query.fts_expression() {
+                values.push(expression.into());
+                conditions.push(format!(
+                    "i.id IN (SELECT item_id FROM item_search WHERE item_search MATCH ?{})",
+                    values.len()
+                ));
+            }
+        }
+        for clause in query.filter_clauses() {
+            let alternatives: Vec<String> = clause
+                .terms
+                .iter()
+                .map(|term| Self::search_term_condition(term, values))
+                .collect();
+            let condition = format!("({})", alternatives.join(" OR "));
+            conditions.push(if clause.negated {
+                format!("NOT {condition}")
+            } else {
+                condition
+            });
+        }
+        conditions
+    }
+
+    fn search_term_condition(term: &Term, values: &mut Vec<rusqlite::types::Value>) -> String {
+        let state = |column: &str| {
+            format!("EXISTS (SELECT 1 FROM item_state x WHERE x.item_id = i.id AND x.{column} = 1)")
+        };
+        match term {
+            Term::Text { .. } => {
+                values.push(term.fts_expression().unwrap_or_default().into());
+                format!(
+                    "i.id IN (SELECT item_id FROM item_search WHERE item_search MATCH ?{})",
+                    values.len()
+                )
+            }
+            Term::Feed(name) => {
+                let pattern = name
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                values.push(format!("%{pattern}%").into());
+                let n = values.len();
+                format!(
+                    "i.feed_id IN (SELECT id FROM feeds
+                                   WHERE title LIKE ?{n} ESCAPE '\\' OR url LIKE ?{n} ESCAPE '\\')"
+                )
+            }
+            Term::Tag(name) => {
+                values.push(name.clone().into());
+                format!(
+                    "EXISTS (SELECT 1 FROM item_tags it JOIN tags t ON t.id = it.tag_id
+                             WHERE it.item_id = i.id AND t.name = ?{})",
+                    values.len()
+                )
+            }
+            Term::Before(cutoff) | Term::After(cutoff) => {
+                values.push(cutoff.to_rfc3339().into());
+                let op = if matches!(term, Term::Before(_)) {
+                    "<"
+                } else {
+                    ">="
+                };
+                format!(
+                    "julianday(COALESCE(i.published_at, i.fetched_at)) {op} julianday(?{})",
+                    values.len()
+                )
+            }
+            Term::Is(StateFlag::Read) => state("is_read"),
+            Term::Is(StateFlag::Unread) => format!("NOT {}", state("is_read")),
+            Term::Is(StateFlag::Starred) => state("is_starred"),
+            Term::Is(StateFlag::Queued) => state("is_queued"),
+            Term::Is(StateFlag::Saved) => state("is_saved"),
+            Term::Is(StateFlag::Archived) => state("is_archived"),
         }
     }
 
@@ -175,14 +498,20 @@ impl SqliteStore {
             params![item_id],
         )?;
         conn.execute(
-            "INSERT INTO item_search (item_id, title, author, summary, content, feed_title, link)
+            "INSERT INTO item_search (item_id, title, author, summary, content, feed_title, link, tags)
              SELECT i.id,
                     COALESCE(i.title, ''),
                     COALESCE(i.author, ''),
                     COALESCE(i.summary, ''),
                     COALESCE(i.content, ''),
                     COALESCE(f.title, f.url, ''),
-                    COALESCE(i.link, '')
+                    COALESCE(i.link, ''),
+                    COALESCE((
+                        SELECT group_concat(t.name, ' ')
+                        FROM item_tags it
+                        JOIN tags t ON t.id = it.tag_id
+                        WHERE it.item_id = i.id
+                    ), '')
              FROM items i
              JOIN feeds f ON f.id = i.feed_id
              WHERE i.id = ?1",
@@ -191,6 +520,270 @@ impl SqliteStore {
         Ok(())
     }
 
+    /// Inserts a new item or applies the feed's edits to a stored one, merging
+    /// media into whichever was written. Pruned items are skipped. Copies of an article
+    /// already stored from another feed join that article's duplicate group
+    /// and inherit its read state.
+    fn write_item_locked(conn: &Connection, item: &Item) -> Result<ItemWrite> {
+        if Self::is_tombstoned_locked(conn, &item.id)? {
+            return Ok(ItemWrite::Unchanged);
+        }
+        let content_hash = item.content_hash();
+        let stored_hash: Option<Option<String>> = conn
+            .query_row(
+                "SELECT content_hash FROM items WHERE id = ?1",
+                params![item.id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(stored_hash) = stored_hash {
+            let write = Self::update_changed_item_locked(
+                conn,
+                item,
+                stored_hash.as_deref(),
+                &content_hash,
+            )?;
+            if write == ItemWrite::Updated {
+                Self::insert_item_media_locked(conn, item)?;
+            }
+            return Ok(write);
+        }
+
+        let canonical_url = item.canonical_url();
+        let fingerprint = item.fingerprint();
+        let group_id = Self::find_duplicate_group_locked(
+            conn,
+            item,
+            canonical_url.as_deref(),
+            fingerprint.as_deref(),
+        )?;
+
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO items (id, feed_id, title, link, content, summary, author, published_at, fetched_at,
+                                          updated_at, content_hash, canonical_url, fingerprint, group_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                item.id,
+                item.feed_id,
+                item.title,
+                item.link,
+                item.content,
+                item.summary,
+                item.author,
+                item.published_at.map(|dt| dt.to_rfc3339()),
+                item.fetched_at.to_rfc3339(),
+                item.updated_at.map(|dt| dt.to_rfc3339()),
+                content_hash,
+                canonical_url,
+                fingerprint,
+                group_id.as_deref().unwrap_or(&item.id)
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(ItemWrite::Unchanged);
+        }
+        Self::insert_item_media_locked(conn, item)?;
+        if group_id.is_some() {
+            conn.execute(
+                "INSERT OR IGNORE INTO item_state (item_id, is_read, read_at)
+                 SELECT ?1, 1, st.read_at
+                 FROM items m
+                 JOIN item_state st ON st.item_id = m.id
+                 WHERE m.group_id = ?2 AND m.id != ?1 AND st.is_read = 1
+                 LIMIT 1",
+                params![item.id, group_id],
+            )?;
+        }
+
+        Ok(ItemWrite::Inserted)
+    }
+
+    /// Replaces a stored item's feed-provided fields and duplicate keys when
+    /// its hash changed, keeping the old version as a revision. Items stored
+    /// before hashes were recorded just get one.
+    fn update_changed_item_locked(
+        conn: &Connection,
+        item: &Item,
+        stored_hash: Option<&str>,
+        content_hash: &str,
+    ) -> Result<ItemWrite> {
+        match stored_hash {
+            Some(stored) if stored == content_hash => return Ok(ItemWrite::Unchanged),
+            None => {
+                conn.execute(
+                    "UPDATE items SET content_hash = ?2, updated_at = COALESCE(?3, updated_at)
+                     WHERE id = ?1",
+                    params![
+                        item.id,
+                        content_hash,
+                        item.updated_at.map(|dt| dt.to_rfc3339())
+                    ],
+                )?;
+                return Ok(ItemWrite::Unchanged);
+            }
+            Some(_) => {}
+        }
+
+        conn.execute(
+            "INSERT INTO item_revisions (item_id, title, link, content, summary, updated_at, replaced_at)
+             SELECT id, title, link, content, summary, updated_at, ?2 FROM items WHERE id = ?1",
+            params![item.id, Utc::now().to_rfc3339()],
+        )?;
+        conn.execute(
+            "DELETE FROM item_revisions
+             WHERE item_id = ?1
+               AND id NOT IN (
+                   SELECT id FROM item_revisions WHERE item_id = ?1 ORDER BY id DESC LIMIT ?2
+               )",
+            params![item.id, REVISIONS_KEEP],
+        )?;
+        // An edited link or text can make the item a copy of a different
+        // article, or of none.
+        let canonical_url = item.canonical_url();
+        let fingerprint = item.fingerprint();
+        let group_id = Self::find_duplicate_group_locked(
+            conn,
+            item,
+            canonical_url.as_deref(),
+            fingerprint.as_deref(),
+        )?;
+        // Scraped article text outlives a feed that only carries summaries.
+        conn.execute(
+            "UPDATE items
+             SET title = ?2, link = ?3, content = COALESCE(?4, content), summary = ?5,
+                 updated_at = COALESCE(?6, ?8), content_hash = ?7,
+                 canonical_url = ?9, fingerprint = ?10, group_id = ?11
+             WHERE id = ?1",
+            params![
+                item.id,
+                item.title,
+                item.link,
+                item.content,
+                item.summary,
+                item.updated_at.map(|dt| dt.to_rfc3339()),
+                content_hash,
+                Utc::now().to_rfc3339(),
+                canonical_url,
+                fingerprint,
+                group_id.as_deref().unwrap_or(&item.id)
+            ],
+        )?;
+
+        Ok(ItemWrite::Updated)
+    }
+
+    /// The group of an item in another feed with the same canonical URL or
+    /// content fingerprint.
+    fn find_duplicate_group_locked(
+        conn: &Connection,
+        item: &Item,
+        canonical_url: Option<&str>,
+        fingerprint: Option<&str>,
+    ) -> Result<Option<String>> {
+        if canonical_url.is_none() && fingerprint.is_none() {
+            return Ok(None);
+        }
+        let group_id = conn
+            .query_row(
+                "SELECT group_id FROM items
+                 WHERE id != ?1 AND feed_id != ?2 AND group_id IS NOT NULL
+                   AND (canonical_url = ?3 OR fingerprint = ?4)
+                 ORDER BY julianday(fetched_at), id
+                 LIMIT 1",
+                params![item.id, item.feed_id, canonical_url, fingerprint],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(group_id)
+    }
+
+    /// Fills in duplicate keys for items stored before duplicate detection,
+    /// a page of items at a time so large databases aren't loaded whole.
+    fn backfill_duplicate_groups_locked(conn: &mut Connection) -> Result<()> {
+        let tx = conn.transaction()?;
+        let mut last_rowid = 0;
+        loop {
+            let page = {
+                let mut stmt = tx.prepare(
+                    "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at,
+                            updated_at, rowid
+                     FROM items WHERE group_id IS NULL AND rowid > ?1
+                     ORDER BY rowid
+                     LIMIT ?2",
+                )?;
+                let page = stmt
+                    .query_map(params![last_rowid, BACKFILL_PAGE], |row| {
+                        Ok((Self::row_to_item(row)?, row.get::<_, i64>(10)?))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                page
+            };
+            let Some((_, rowid)) = page.last() else {
+                break;
+            };
+            last_rowid = *rowid;
+
+            for (item, _) in page {
+                let canonical_url = item.canonical_url();
+                let fingerprint = item.fingerprint();
+                let group_id = Self::find_duplicate_group_locked(
+                    &tx,
+                    &item,
+                    canonical_url.as_deref(),
+                    fingerprint.as_deref(),
+                )?;
+                tx.execute(
+                    "UPDATE items SET canonical_url = ?2, fingerprint = ?3, group_id = ?4 WHERE id = ?1",
+                    params![
+                        item.id,
+                        canonical_url,
+                        fingerprint,
+                        group_id.as_deref().unwrap_or(&item.id)
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Whether the item was pruned and must not be inserted again.
+    fn is_tombstoned_locked(conn: &Connection, item_id: &str) -> Result<bool> {
+        let found = conn
+            .query_row(
+                "SELECT 1 FROM item_tombstones WHERE item_id = ?1",
+                params![item_id],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Merges enclosures and categories into the stored ones. Only inserted
+    /// and edited items get here, so an unchanged refresh writes nothing.
+    fn insert_item_media_locked(conn: &Connection, item: &Item) -> Result<()> {
+        for enclosure in &item.enclosures {
+            conn.execute(
+                "INSERT OR IGNORE INTO enclosures (item_id, url, mime_type, length, duration_secs)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    item.id,
+                    enclosure.url,
+                    enclosure.mime_type,
+                    enclosure.length,
+                    enclosure.duration_secs
+                ],
+            )?;
+        }
+        for name in &item.categories {
+            conn.execute(
+                "INSERT OR IGNORE INTO item_categories (item_id, name) VALUES (?1, ?2)",
+                params![item.id, name],
+            )?;
+        }
+        Ok(())
+    }
+
     fn refresh_search_index_for_feed_locked(conn: &Connection, feed_id: i64) -> Result<()> {
         let mut stmt = conn.prepare("SELECT id FROM items WHERE feed_id = ?1")?;
         let ids = stmt
@@ -221,6 +814,88 @@ impl SqliteStore {
             last_status: row.get(6)?,
         })
     }
+
+    fn row_to_feed(row: &rusqlite::Row<'_>) -> rusqlite::Result<Feed> {
+        Ok(Feed {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            description: row.get(3)?,
+            etag: row.get(4)?,
+            last_modified: row.get(5)?,
+            last_fetched_at: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| Self::parse_datetime(&s)),
+            created_at: row
+                .get::<_, String>(7)
+                .ok()
+                .and_then(|s| Self::parse_datetime(&s))
+                .unwrap_or_else(Utc::now),
+            original_url: row.get(8)?,
+            is_active: row.get::<_, i64>(9)? != 0,
+        })
+    }
+
+    fn row_to_schedule(row: &rusqlite::Row<'_>) -> rusqlite::Result<FeedSchedule> {
+        Ok(FeedSchedule {
+            feed_id: row.get(0)?,
+            interval_secs: row.get(1)?,
+            next_due_at: row
+                .get::<_, String>(2)
+                .ok()
+                .and_then(|s| Self::parse_datetime(&s))
+                .unwrap_or_else(Utc::now),
+            consecutive_failures: row.get(3)?,
+        })
+    }
+
+    fn row_to_fetch_log(row: &rusqlite::Row<'_>) -> rusqlite::Result<FetchLogEntry> {
+        Self::row_to_fetch_log_at(row, 0)
+    }
+
+    /// A fetch log entry whose columns start at `offset`.
+    fn row_to_fetch_log_at(
+        row: &rusqlite::Row<'_>,
+        offset: usize,
+    ) -> rusqlite::Result<FetchLogEntry> {
+        Ok(FetchLogEntry {
+            id: row.get(offset)?,
+            feed_id: row.get(offset + 1)?,
+            fetched_at: row
+                .get::<_, String>(offset + 2)
+                .ok()
+                .and_then(|s| Self::parse_datetime(&s))
+                .unwrap_or_else(Utc::now),
+            status_code: row.get(offset + 3)?,
+            error_kind: row.get(offset + 4)?,
+            error_message: row.get(offset + 5)?,
+            latency_ms: row.get(offset + 6)?,
+            bytes: row.get(offset + 7)?,
+            final_url: row.get(offset + 8)?,
+        })
+    }
+
+    fn row_to_category(row: &rusqlite::Row<'_>) -> rusqlite::Result<Category> {
+        Ok(Category {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            parent_id: row.get(2)?,
+            created_at: row
+                .get::<_, String>(3)
+                .ok()
+                .and_then(|s| Self::parse_datetime(&s))
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    fn row_to_saved_search(row: &rusqlite::Row<'_>) -> rusqlite::Result<SavedSearch> {
+        Ok(SavedSearch {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            query: row.get(2)?,
+            filter: ItemListFilter::parse(&row.get::<_, String>(3)?).unwrap_or(ItemListFilter::All),
+        })
+    }
 }
 
 impl Store for SqliteStore {
@@ -255,25 +930,11 @@ impl Store for SqliteStore {
 
         let result = conn
             .query_row(
-                "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at
+                "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at,
+                        original_url, is_active
                  FROM feeds WHERE id = ?1",
                 params![id],
-                |row| {
-                    Ok(Feed {
-                        id: row.get(0)?,
-                        url: row.get(1)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        etag: row.get(4)?,
-                        last_modified: row.get(5)?,
-                        last_fetched_at: row.get::<_, Option<String>>(6)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        created_at: row.get::<_, String>(7)
-                            .ok()
-                            .and_then(|s| Self::parse_datetime(&s))
-                            .unwrap_or_else(Utc::now),
-                    })
-                },
+                Self::row_to_feed,
             )
             .optional()?;
 
@@ -290,25 +951,12 @@ impl Store for SqliteStore {
 
         let result = conn
             .query_row(
-                "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at
-                 FROM feeds WHERE url = ?1",
+                "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at,
+                        original_url, is_active
+                 FROM feeds WHERE url = ?1 OR original_url = ?1
+                 ORDER BY url = ?1 DESC LIMIT 1",
                 params![url],
-                |row| {
-                    Ok(Feed {
-                        id: row.get(0)?,
-                        url: row.get(1)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        etag: row.get(4)?,
-                        last_modified: row.get(5)?,
-                        last_fetched_at: row.get::<_, Option<String>>(6)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        created_at: row.get::<_, String>(7)
-                            .ok()
-                            .and_then(|s| Self::parse_datetime(&s))
-                            .unwrap_or_else(Utc::now),
-                    })
-                },
+                Self::row_to_feed,
             )
             .optional()?;
 
@@ -324,29 +972,38 @@ impl Store for SqliteStore {
         })?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at
+            "SELECT id, url, title, description, etag, last_modified, last_fetched_at, created_at,
+                    original_url, is_active
              FROM feeds ORDER BY title, url",
         )?;
 
         let feeds = stmt
-            .query_map([], |row| {
-                Ok(Feed {
-                    id: row.get(0)?,
-                    url: row.get(1)?,
-                    title: row.get(2)?,
-                    description: row.get(3)?,
-                    etag: row.get(4)?,
-                    last_modified: row.get(5)?,
-                    last_fetched_at: row
-                        .get::<_, Option<String>>(6)?
-                        .and_then(|s| Self::parse_datetime(&s)),
-                    created_at: row
-                        .get::<_, String>(7)
-                        .ok()
-                        .and_then(|s| Self::parse_datetime(&s))
-                        .unwrap_or_else(Utc::now),
-                })
-            })?
+            .query_map([], Self::row_to_feed)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(feeds)
+    }
+
+    fn get_due_feeds(&self, now: DateTime<Utc>) -> Result<Vec<Feed>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.url, f.title, f.description, f.etag, f.last_modified,
+                    f.last_fetched_at, f.created_at, f.original_url, f.is_active
+             FROM feeds f
+             LEFT JOIN feed_schedules s ON s.feed_id = f.id
+             WHERE f.is_active = 1
+               AND (s.feed_id IS NULL OR julianday(s.next_due_at) <= julianday(?1))
+             ORDER BY f.title, f.url",
+        )?;
+
+        let feeds = stmt
+            .query_map(params![now.to_rfc3339()], Self::row_to_feed)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(feeds)
@@ -395,7 +1052,7 @@ impl Store for SqliteStore {
         Ok(())
     }
 
-    fn delete_feed(&self, id: i64) -> Result<()> {
+    fn update_feed_url(&self, id: i64, url: &str) -> Result<bool> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
@@ -403,16 +1060,24 @@ impl Store for SqliteStore {
             ))
         })?;
 
+        let taken: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM feeds WHERE url = ?1 AND id != ?2)",
+            params![url, id],
+            |row| row.get(0),
+        )?;
+        if taken {
+            return Ok(false);
+        }
+
         conn.execute(
-            "DELETE FROM item_search
-             WHERE item_id IN (SELECT id FROM items WHERE feed_id = ?1)",
-            params![id],
+            "UPDATE feeds SET original_url = COALESCE(original_url, url), url = ?1 WHERE id = ?2",
+            params![url, id],
         )?;
-        conn.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
-        Ok(())
+
+        Ok(true)
     }
 
-    fn add_auth_profile(&self, profile: &AuthProfile) -> Result<i64> {
+    fn set_feed_active(&self, id: i64, is_active: bool) -> Result<()> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
@@ -421,31 +1086,14 @@ impl Store for SqliteStore {
         })?;
 
         conn.execute(
-            "INSERT INTO auth_profiles (name, site_url, profile_dir, created_at, last_checked_at, last_status)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-             ON CONFLICT(name) DO UPDATE SET
-                site_url = excluded.site_url,
-                profile_dir = excluded.profile_dir",
-            params![
-                profile.name,
-                profile.site_url,
-                profile.profile_dir,
-                profile.created_at.to_rfc3339(),
-                profile.last_checked_at.map(|dt| dt.to_rfc3339()),
-                profile.last_status
-            ],
-        )?;
-
-        let id = conn.query_row(
-            "SELECT id FROM auth_profiles WHERE name = ?1",
-            params![profile.name],
-            |row| row.get(0),
+            "UPDATE feeds SET is_active = ?1 WHERE id = ?2",
+            params![is_active as i32, id],
         )?;
 
-        Ok(id)
+        Ok(())
     }
 
-    fn get_auth_profile_by_name(&self, name: &str) -> Result<Option<AuthProfile>> {
+    fn delete_feed(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
@@ -453,13 +1101,167 @@ impl Store for SqliteStore {
             ))
         })?;
 
-        let profile = conn
-            .query_row(
-                "SELECT id, name, site_url, profile_dir, created_at, last_checked_at, last_status
-                 FROM auth_profiles WHERE name = ?1",
-                params![name],
-                Self::row_to_auth_profile,
-            )
+        conn.execute(
+            "DELETE FROM item_search
+             WHERE item_id IN (SELECT id FROM items WHERE feed_id = ?1)",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn add_category(&self, category: &Category) -> Result<i64> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO categories (name, parent_id, created_at) VALUES (?1, ?2, ?3)",
+            params![
+                category.name,
+                category.parent_id,
+                category.created_at.to_rfc3339()
+            ],
+        )?;
+
+        let id = conn.query_row(
+            "SELECT id FROM categories WHERE name = ?1 AND parent_id IS ?2",
+            params![category.name, category.parent_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(id)
+    }
+
+    fn get_all_categories(&self) -> Result<Vec<Category>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, parent_id, created_at FROM categories ORDER BY name COLLATE NOCASE, id",
+        )?;
+
+        let categories = stmt
+            .query_map([], Self::row_to_category)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(categories)
+    }
+
+    fn delete_category(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute("DELETE FROM categories WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn add_feed_to_category(&self, feed_id: i64, category_id: i64) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO feed_categories (feed_id, category_id) VALUES (?1, ?2)",
+            params![feed_id, category_id],
+        )?;
+        Ok(())
+    }
+
+    fn remove_feed_from_category(&self, feed_id: i64, category_id: i64) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "DELETE FROM feed_categories WHERE feed_id = ?1 AND category_id = ?2",
+            params![feed_id, category_id],
+        )?;
+        Ok(())
+    }
+
+    fn get_feed_category_links(&self) -> Result<Vec<(i64, i64)>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt =
+            conn.prepare("SELECT feed_id, category_id FROM feed_categories ORDER BY feed_id")?;
+        let links = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(links)
+    }
+
+    fn add_auth_profile(&self, profile: &AuthProfile) -> Result<i64> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "INSERT INTO auth_profiles (name, site_url, profile_dir, created_at, last_checked_at, last_status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+                site_url = excluded.site_url,
+                profile_dir = excluded.profile_dir",
+            params![
+                profile.name,
+                profile.site_url,
+                profile.profile_dir,
+                profile.created_at.to_rfc3339(),
+                profile.last_checked_at.map(|dt| dt.to_rfc3339()),
+                profile.last_status
+            ],
+        )?;
+
+        let id = conn.query_row(
+            "SELECT id FROM auth_profiles WHERE name = ?1",
+            params![profile.name],
+            |row| row.get(0),
+        )?;
+
+        Ok(id)
+    }
+
+    fn get_auth_profile_by_name(&self, name: &str) -> Result<Option<AuthProfile>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let profile = conn
+            .query_row(
+                "SELECT id, name, site_url, profile_dir, created_at, last_checked_at, last_status
+                 FROM auth_profiles WHERE name = ?1",
+                params![name],
+                Self::row_to_auth_profile,
+            )
             .optional()?;
 
         Ok(profile)
@@ -509,22 +1311,7 @@ impl Store for SqliteStore {
             ))
         })?;
 
-        let inserted = conn.execute(
-            "INSERT OR IGNORE INTO items (id, feed_id, title, link, content, summary, author, published_at, fetched_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
-                item.id,
-                item.feed_id,
-                item.title,
-                item.link,
-                item.content,
-                item.summary,
-                item.author,
-                item.published_at.map(|dt| dt.to_rfc3339()),
-                item.fetched_at.to_rfc3339()
-            ],
-        )?;
-        if inserted > 0 {
+        if Self::write_item_locked(&conn, item)? != ItemWrite::Unchanged {
             Self::refresh_search_index_for_item_locked(&conn, &item.id)?;
         }
 
@@ -542,37 +1329,28 @@ impl Store for SqliteStore {
         let tx = conn.transaction()?;
         let mut count = 0;
         let mut inserted_ids = Vec::new();
+        let mut updated_ids = Vec::new();
 
         for item in items {
-            let inserted = tx.execute(
-                "INSERT OR IGNORE INTO items (id, feed_id, title, link, content, summary, author, published_at, fetched_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-                params![
-                    item.id,
-                    item.feed_id,
-                    item.title,
-                    item.link,
-                    item.content,
-                    item.summary,
-                    item.author,
-                    item.published_at.map(|dt| dt.to_rfc3339()),
-                    item.fetched_at.to_rfc3339()
-                ],
-            )?;
-            count += inserted;
-            if inserted > 0 {
-                inserted_ids.push(item.id.clone());
+            match Self::write_item_locked(&tx, item)? {
+                ItemWrite::Inserted => {
+                    count += 1;
+                    inserted_ids.push(item.id.clone());
+                }
+                ItemWrite::Updated => updated_ids.push(item.id.clone()),
+                ItemWrite::Unchanged => {}
             }
         }
 
         tx.commit()?;
-        for item_id in &inserted_ids {
+        for item_id in inserted_ids.iter().chain(&updated_ids) {
             Self::refresh_search_index_for_item_locked(&conn, item_id)?;
         }
 
         Ok(AddItemsResult {
             count,
             inserted_ids,
+            updated_ids,
         })
     }
 
@@ -580,6 +1358,176 @@ impl Store for SqliteStore {
         Ok(self.add_items_with_report(items)?.count)
     }
 
+    fn get_enclosures(&self, item_id: &str) -> Result<Vec<Enclosure>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT url, mime_type, length, duration_secs
+             FROM enclosures WHERE item_id = ?1 ORDER BY id",
+        )?;
+        let enclosures = stmt
+            .query_map(params![item_id], |row| {
+                Ok(Enclosure {
+                    url: row.get(0)?,
+                    mime_type: row.get(1)?,
+                    length: row.get(2)?,
+                    duration_secs: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(enclosures)
+    }
+
+    fn get_item_categories(&self, item_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT name FROM item_categories WHERE item_id = ?1 ORDER BY name COLLATE NOCASE",
+        )?;
+        let names = stmt
+            .query_map(params![item_id], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(names)
+    }
+
+    fn load_item_media(&self, items: &mut [Item]) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        let mut enclosures: HashMap<String, Vec<Enclosure>> = HashMap::new();
+        let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for chunk in ids.chunks(LOAD_CHUNK) {
+            let placeholders: Vec<String> =
+                (0..chunk.len()).map(|i| format!("?{}", i + 1)).collect();
+            let placeholders = placeholders.join(", ");
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT item_id, url, mime_type, length, duration_secs
+                 FROM enclosures WHERE item_id IN ({placeholders}) ORDER BY id"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    Enclosure {
+                        url: row.get(1)?,
+                        mime_type: row.get(2)?,
+                        length: row.get(3)?,
+                        duration_secs: row.get(4)?,
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (item_id, enclosure) = row?;
+                enclosures.entry(item_id).or_default().push(enclosure);
+            }
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT item_id, name FROM item_categories
+                 WHERE item_id IN ({placeholders})
+                 ORDER BY name COLLATE NOCASE"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (item_id, name) = row?;
+                categories.entry(item_id).or_default().push(name);
+            }
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT it.item_id, t.name FROM item_tags it
+                 JOIN tags t ON t.id = it.tag_id
+                 WHERE it.item_id IN ({placeholders})
+                 ORDER BY t.name COLLATE NOCASE"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (item_id, name) = row?;
+                tags.entry(item_id).or_default().push(name);
+            }
+        }
+
+        for item in items {
+            item.enclosures = enclosures.remove(&item.id).unwrap_or_default();
+            item.categories = categories.remove(&item.id).unwrap_or_default();
+            item.tags = tags.remove(&item.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    fn get_item_revisions(&self, item_id: &str) -> Result<Vec<ItemRevision>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT item_id, title, link, content, summary, updated_at, replaced_at
+             FROM item_revisions WHERE item_id = ?1 ORDER BY id",
+        )?;
+        let revisions = stmt
+            .query_map(params![item_id], Self::row_to_revision)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(revisions)
+    }
+
+    fn get_revisions_for_items(
+        &self,
+        item_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ItemRevision>>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut revisions: HashMap<String, Vec<ItemRevision>> = HashMap::new();
+        for chunk in item_ids.chunks(LOAD_CHUNK) {
+            let placeholders: Vec<String> =
+                (0..chunk.len()).map(|i| format!("?{}", i + 1)).collect();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT item_id, title, link, content, summary, updated_at, replaced_at
+                 FROM item_revisions WHERE item_id IN ({}) ORDER BY id",
+                placeholders.join(", ")
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), Self::row_to_revision)?;
+            for row in rows {
+                let revision = row?;
+                revisions
+                    .entry(revision.item_id.clone())
+                    .or_default()
+                    .push(revision);
+            }
+        }
+
+        Ok(revisions)
+    }
+
     fn get_item(&self, id: &str) -> Result<Option<Item>> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
@@ -590,7 +1538,7 @@ impl Store for SqliteStore {
 
         let result = conn
             .query_row(
-                "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at
+                "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at, updated_at
                  FROM items WHERE id = ?1",
                 params![id],
                 Self::row_to_item,
@@ -609,7 +1557,7 @@ impl Store for SqliteStore {
         })?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at
+            "SELECT id, feed_id, title, link, content, summary, author, published_at, fetched_at, updated_at
              FROM items WHERE feed_id = ?1 ORDER BY published_at DESC, fetched_at DESC",
         )?;
 
@@ -650,8 +1598,8 @@ impl Store for SqliteStore {
 
         let batch_sql = format!(
             "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author,
-                    i.published_at, i.fetched_at, COALESCE(f.title, f.url), 1,
-                    COALESCE(ri.inserted_at, i.fetched_at)
+                    i.published_at, i.fetched_at, i.updated_at, COALESCE(f.title, f.url), 1,
+                    COALESCE(ri.inserted_at, i.fetched_at), COALESCE(i.group_id, i.id)
              FROM refresh_run_items ri
              JOIN items i ON i.id = ri.item_id
              JOIN feeds f ON f.id = i.feed_id
@@ -667,8 +1615,8 @@ impl Store for SqliteStore {
 
         let tail_sql = format!(
             "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author,
-                    i.published_at, i.fetched_at, COALESCE(f.title, f.url), 0,
-                    i.fetched_at
+                    i.published_at, i.fetched_at, i.updated_at, COALESCE(f.title, f.url), 0,
+                    i.fetched_at, COALESCE(i.group_id, i.id)
              FROM items i
              JOIN feeds f ON f.id = i.feed_id
              LEFT JOIN item_state s ON i.id = s.item_id
@@ -691,11 +1639,17 @@ impl Store for SqliteStore {
             .collect::<std::result::Result<Vec<_>, _>>()?,
         );
 
-        Ok(recent)
+        Ok(Self::collapse_duplicates(recent))
     }
 
-    fn search_items(&self, query: &str, filter: ItemListFilter, limit: usize) -> Result<Vec<Item>> {
-        if query.trim().is_empty() || limit == 0 {
+    fn search_items(
+        &self,
+        query: &str,
+        filter: ItemListFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let query = SearchQuery::parse(query)?;
+        if query.is_empty() || limit == 0 {
             return Ok(Vec::new());
         }
 
@@ -706,23 +1660,51 @@ impl Store for SqliteStore {
             ))
         })?;
 
-        let state_clause = Self::filter_clause(filter, "st");
+        // Free text ranks results and gives snippets; filters alone list
+        // the newest matches
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        let mut conditions = Vec::new();
+        let (source, snippet, rank) = match query.fts_expression() {
+            Some(expression) => {
+                values.push(expression.into());
+                conditions.push("item_search MATCH ?1".to_string());
+                (
+                    "item_search s JOIN items i ON i.id = s.item_id",
+                    format!("snippet(item_search, -1, char(2), char(3), '…', {SNIPPET_TOKENS})"),
+                    "bm25(item_search), ",
+                )
+            }
+            None => ("items i", "NULL".to_string(), ""),
+        };
+        conditions.extend(Self::search_conditions(&query, true, &mut values));
+        conditions.push(Self::filter_clause(query.view_filter(filter), "st"));
+        values.push((limit as i64).into());
+
         let sql = format!(
-            "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author, i.published_at, i.fetched_at
-             FROM item_search s
-             JOIN items i ON i.id = s.item_id
+            "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author, i.published_at, i.fetched_at,
+                        i.updated_at, {snippet}
+             FROM {source}
              LEFT JOIN item_state st ON i.id = st.item_id
-             WHERE item_search MATCH ?1 AND {state_clause}
-             ORDER BY bm25(item_search), i.published_at DESC, i.fetched_at DESC
-             LIMIT ?2"
+             WHERE {}
+             ORDER BY {rank}i.published_at DESC, i.fetched_at DESC
+             LIMIT ?{}",
+            conditions.join(" AND "),
+            values.len()
         );
 
         let mut stmt = conn.prepare(&sql)?;
-        let items = stmt
-            .query_map(params![query.trim(), limit as i64], Self::row_to_item)?
+        let hits = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok(SearchHit {
+                    item: Self::row_to_item(row)?,
+                    snippet: row
+                        .get::<_, Option<String>>(10)?
+                        .map(|raw| query::clean_snippet(&raw)),
+                })
+            })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(items)
+        Ok(hits)
     }
 
     fn item_exists(&self, id: &str) -> Result<bool> {
@@ -839,53 +1821,145 @@ impl Store for SqliteStore {
         Ok(run_id)
     }
 
-    fn get_item_state(&self, item_id: &str) -> Result<Option<ItemState>> {
-        let conn = self.conn.lock().map_err(|e| {
+    fn prune_items(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<PruneReport> {
+        let mut conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
                 Some(e.to_string()),
             ))
         })?;
 
-        let result = conn
-            .query_row(
-                "SELECT item_id, is_read, is_starred, is_queued, is_saved, is_archived,
-                        read_at, starred_at, queued_at, saved_at, archived_at
-                 FROM item_state WHERE item_id = ?1",
-                params![item_id],
-                |row| {
-                    Ok(ItemState {
-                        item_id: row.get(0)?,
-                        is_read: row.get::<_, i32>(1)? != 0,
-                        is_starred: row.get::<_, i32>(2)? != 0,
-                        is_queued: row.get::<_, i32>(3)? != 0,
-                        is_saved: row.get::<_, i32>(4)? != 0,
-                        is_archived: row.get::<_, i32>(5)? != 0,
-                        read_at: row
-                            .get::<_, Option<String>>(6)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        starred_at: row
-                            .get::<_, Option<String>>(7)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        queued_at: row
-                            .get::<_, Option<String>>(8)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        saved_at: row
-                            .get::<_, Option<String>>(9)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                        archived_at: row
-                            .get::<_, Option<String>>(10)?
-                            .and_then(|s| Self::parse_datetime(&s)),
-                    })
-                },
-            )
-            .optional()?;
+        let feed_ids = {
+            let mut stmt = conn.prepare("SELECT id FROM feeds ORDER BY id")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            ids
+        };
 
-        Ok(result)
-    }
+        let tx = conn.transaction()?;
+        let mut report = PruneReport::default();
+        for feed_id in feed_ids {
+            let limits = policy.limits_for(feed_id);
+            if limits.is_unlimited() {
+                continue;
+            }
+            let ids = Self::prunable_item_ids_locked(&tx, feed_id, limits)?;
+            if ids.is_empty() {
+                continue;
+            }
+            if !dry_run {
+                Self::delete_items_locked(&tx, &ids)?;
+            }
+            report.feed_items.push((feed_id, ids.len()));
+        }
 
-    fn set_read(&self, item_id: &str, is_read: bool) -> Result<()> {
-        let conn = self.conn.lock().map_err(|e| {
+        if let Some(days) = policy.refresh_run_days {
+            // Only the latest completed run feeds the Latest view.
+            let where_clause = "completed_at IS NOT NULL
+                 AND julianday(started_at) < julianday('now', ?1)
+                 AND id != COALESCE((
+                     SELECT id FROM refresh_runs
+                     WHERE completed_at IS NOT NULL
+                     ORDER BY completed_at DESC, id DESC
+                     LIMIT 1
+                 ), -1)";
+            let age = format!("-{} days", days);
+            report.refresh_runs = if dry_run {
+                tx.query_row(
+                    &format!("SELECT COUNT(*) FROM refresh_runs WHERE {where_clause}"),
+                    params![age],
+                    |row| row.get::<_, i64>(0),
+                )? as usize
+            } else {
+                tx.execute(
+                    &format!("DELETE FROM refresh_runs WHERE {where_clause}"),
+                    params![age],
+                )?
+            };
+        }
+
+        if let Some(days) = policy.tombstone_days {
+            let where_clause = "julianday(deleted_at) < julianday('now', ?1)";
+            let age = format!("-{} days", days);
+            report.tombstones = if dry_run {
+                tx.query_row(
+                    &format!("SELECT COUNT(*) FROM item_tombstones WHERE {where_clause}"),
+                    params![age],
+                    |row| row.get::<_, i64>(0),
+                )? as usize
+            } else {
+                tx.execute(
+                    &format!("DELETE FROM item_tombstones WHERE {where_clause}"),
+                    params![age],
+                )?
+            };
+        }
+        tx.commit()?;
+
+        Ok(report)
+    }
+
+    fn vacuum(&self) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    fn get_item_state(&self, item_id: &str) -> Result<Option<ItemState>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let result = conn
+            .query_row(
+                &format!("SELECT {ITEM_STATE_COLUMNS} FROM item_state WHERE item_id = ?1"),
+                params![item_id],
+                Self::row_to_item_state,
+            )
+            .optional()?;
+
+        Ok(result)
+    }
+
+    fn get_item_states(&self, item_ids: &[String]) -> Result<HashMap<String, ItemState>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut states = HashMap::new();
+        for chunk in item_ids.chunks(LOAD_CHUNK) {
+            let placeholders: Vec<String> =
+                (0..chunk.len()).map(|i| format!("?{}", i + 1)).collect();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ITEM_STATE_COLUMNS} FROM item_state WHERE item_id IN ({})",
+                placeholders.join(", ")
+            ))?;
+            let rows =
+                stmt.query_map(rusqlite::params_from_iter(chunk), Self::row_to_item_state)?;
+            for row in rows {
+                let state = row?;
+                states.insert(state.item_id.clone(), state);
+            }
+        }
+
+        Ok(states)
+    }
+
+    fn set_read(&self, item_id: &str, is_read: bool) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
                 Some(e.to_string()),
@@ -999,6 +2073,203 @@ impl Store for SqliteStore {
         Ok(())
     }
 
+    fn set_read_many(&self, item_ids: &[String], is_read: bool) -> Result<usize> {
+        if item_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let read_at = is_read.then(|| Utc::now().to_rfc3339());
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        // One statement per chunk keeps us under SQLite's bound-parameter limit.
+        for chunk in item_ids.chunks(MARK_READ_CHUNK) {
+            let placeholders: Vec<String> =
+                (0..chunk.len()).map(|i| format!("?{}", i + 3)).collect();
+            let sql = format!(
+                "INSERT INTO item_state (item_id, is_read, read_at)
+                 SELECT i.id, ?1, ?2 FROM items i
+                 LEFT JOIN item_state st ON st.item_id = i.id
+                 WHERE i.id IN ({}) AND COALESCE(st.is_read, 0) != ?1
+                 ON CONFLICT(item_id) DO UPDATE SET is_read = excluded.is_read, read_at = excluded.read_at",
+                placeholders.join(", ")
+            );
+            let mut values: Vec<rusqlite::types::Value> =
+                vec![i64::from(is_read).into(), read_at.clone().into()];
+            values.extend(chunk.iter().map(|id| id.clone().into()));
+            changed += tx.execute(&sql, rusqlite::params_from_iter(values))?;
+        }
+        tx.commit()?;
+
+        Ok(changed)
+    }
+
+    fn mark_feeds_read(
+        &self,
+        feed_ids: &[i64],
+        filter: ItemListFilter,
+        tag: Option<&str>,
+    ) -> Result<usize> {
+        if feed_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let (unread_clause, mut values) = Self::unread_in_feeds_clause(feed_ids, filter, tag, 2);
+        let sql = format!(
+            "INSERT INTO item_state (item_id, is_read, read_at)
+             SELECT i.id, 1, ?1 FROM items i
+             LEFT JOIN item_state st ON st.item_id = i.id
+             WHERE {unread_clause}
+             ON CONFLICT(item_id) DO UPDATE SET is_read = 1, read_at = excluded.read_at"
+        );
+        values.insert(0, Utc::now().to_rfc3339().into());
+        let changed = conn.execute(&sql, rusqlite::params_from_iter(values))?;
+
+        Ok(changed)
+    }
+
+    fn count_unread_in_feeds(
+        &self,
+        feed_ids: &[i64],
+        filter: ItemListFilter,
+        tag: Option<&str>,
+    ) -> Result<usize> {
+        if feed_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let (unread_clause, values) = Self::unread_in_feeds_clause(feed_ids, filter, tag, 1);
+        let count: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM items i
+                 LEFT JOIN item_state st ON st.item_id = i.id
+                 WHERE {unread_clause}"
+            ),
+            rusqlite::params_from_iter(values),
+            |row| row.get(0),
+        )?;
+
+        Ok(count as usize)
+    }
+
+    fn select_item_ids(&self, selector: &ItemSelector) -> Result<Vec<String>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut clauses = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(item_id) = &selector.item_id {
+            values.push(item_id.clone().into());
+            clauses.push(format!("i.id = ?{}", values.len()));
+        }
+        if let Some(feed_id) = selector.feed_id {
+            values.push(feed_id.into());
+            clauses.push(format!("i.feed_id = ?{}", values.len()));
+        }
+        if let Some(query) = selector.search.as_deref() {
+            let query = SearchQuery::parse(query)?;
+            if query.is_empty() {
+                return Ok(Vec::new());
+            }
+            clauses.extend(Self::search_conditions(&query, false, &mut values));
+        }
+        if let Some(cutoff) = selector.older_than {
+            values.push(cutoff.to_rfc3339().into());
+            clauses.push(format!(
+                "julianday(COALESCE(i.published_at, i.fetched_at)) < julianday(?{})",
+                values.len()
+            ));
+        }
+        if clauses.is_empty() {
+            clauses.push("1 = 1".to_string());
+        }
+
+        let sql = format!(
+            "SELECT i.id FROM items i WHERE {} ORDER BY i.published_at DESC, i.fetched_at DESC",
+            clauses.join(" AND ")
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(ids)
+    }
+
+    fn set_item_states(&self, item_ids: &[String], change: &ItemStateChange) -> Result<usize> {
+        let columns = change.columns();
+        if item_ids.is_empty() || columns.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        // Timestamps record when a flag was turned on and are cleared with it.
+        let now = Utc::now().to_rfc3339();
+        let assignments: Vec<String> = columns
+            .iter()
+            .map(|(flag, at, value)| {
+                if *value {
+                    format!("{flag} = 1, {at} = ?2")
+                } else {
+                    format!("{flag} = 0, {at} = NULL")
+                }
+            })
+            .collect();
+        let update = format!(
+            "UPDATE item_state SET {} WHERE item_id = ?1",
+            assignments.join(", ")
+        );
+
+        let tx = conn.transaction()?;
+        let mut updated = 0;
+        {
+            let mut insert =
+                tx.prepare("INSERT OR IGNORE INTO item_state (item_id) VALUES (?1)")?;
+            let mut update = tx.prepare(&update)?;
+            for item_id in item_ids {
+                insert.execute(params![item_id])?;
+                updated += if columns.iter().any(|(_, _, value)| *value) {
+                    update.execute(params![item_id, now])?
+                } else {
+                    update.execute(params![item_id])?
+                };
+            }
+        }
+        tx.commit()?;
+
+        Ok(updated)
+    }
+
     fn get_unread_count(&self, feed_id: i64) -> Result<i64> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
@@ -1012,6 +2283,7 @@ impl Store for SqliteStore {
              LEFT JOIN item_state s ON i.id = s.item_id
              WHERE i.feed_id = ?1
                AND (s.is_archived IS NULL OR s.is_archived = 0)
+               AND (s.is_hidden IS NULL OR s.is_hidden = 0)
                AND (s.is_read IS NULL OR s.is_read = 0)",
             params![feed_id],
             |row| row.get(0),
@@ -1020,7 +2292,7 @@ impl Store for SqliteStore {
         Ok(count)
     }
 
-    fn update_item_content(&self, id: &str, content: &str) -> Result<()> {
+    fn get_unread_counts(&self) -> Result<HashMap<i64, i64>> {
         let conn = self.conn.lock().map_err(|e| {
             RivuletError::Database(rusqlite::Error::SqliteFailure(
                 rusqlite::ffi::Error::new(1),
@@ -1028,87 +2300,1001 @@ impl Store for SqliteStore {
             ))
         })?;
 
-        conn.execute(
-            "UPDATE items SET content = ?1 WHERE id = ?2",
-            params![content, id],
+        let mut stmt = conn.prepare(
+            "SELECT i.feed_id, COUNT(*) FROM items i
+             LEFT JOIN item_state s ON i.id = s.item_id
+             WHERE (s.is_archived IS NULL OR s.is_archived = 0)
+               AND (s.is_hidden IS NULL OR s.is_hidden = 0)
+               AND (s.is_read IS NULL OR s.is_read = 0)
+             GROUP BY i.feed_id",
         )?;
-        Self::refresh_search_index_for_item_locked(&conn, id)?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
 
-        Ok(())
+        Ok(counts)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn tag_items(&self, item_ids: &[String], tag: &str) -> Result<usize> {
+        let tag = tag.trim();
+        if item_ids.is_empty() || tag.is_empty() {
+            return Ok(0);
+        }
 
-    #[test]
-    fn test_add_and_get_feed() {
-        let store = SqliteStore::in_memory().unwrap();
-        let feed = Feed::new("https://example.com/feed.xml".into());
-        let id = store.add_feed(&feed).unwrap();
+        let mut conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
 
-        let retrieved = store.get_feed(id).unwrap().unwrap();
-        assert_eq!(retrieved.url, "https://example.com/feed.xml");
+        let now = Utc::now().to_rfc3339();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)",
+            params![tag, now],
+        )?;
+        let tag_id: i64 =
+            tx.query_row("SELECT id FROM tags WHERE name = ?1", params![tag], |row| {
+                row.get(0)
+            })?;
+        let mut tagged = 0;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO item_tags (item_id, tag_id, tagged_at) VALUES (?1, ?2, ?3)",
+            )?;
+            for item_id in item_ids {
+                let added = insert.execute(params![item_id, tag_id, now])?;
+                if added > 0 {
+                    Self::refresh_search_index_for_item_locked(&tx, item_id)?;
+                }
+                tagged += added;
+            }
+        }
+        tx.commit()?;
+
+        Ok(tagged)
     }
 
-    #[test]
-    fn test_add_and_get_item() {
-        let store = SqliteStore::in_memory().unwrap();
-        let feed = Feed::new("https://example.com/feed.xml".into());
-        let feed_id = store.add_feed(&feed).unwrap();
+    fn untag_items(&self, item_ids: &[String], tag: &str) -> Result<usize> {
+        let tag = tag.trim();
+        if item_ids.is_empty() || tag.is_empty() {
+            return Ok(0);
+        }
 
-        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
-        item.title = Some("Test Item".into());
-        store.add_item(&item).unwrap();
+        let mut conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
 
-        let retrieved = store.get_item(&item.id).unwrap().unwrap();
-        assert_eq!(retrieved.title, Some("Test Item".into()));
-    }
+        let tx = conn.transaction()?;
+        let Some(tag_id) = tx
+            .query_row("SELECT id FROM tags WHERE name = ?1", params![tag], |row| {
+                row.get::<_, i64>(0)
+            })
+            .optional()?
+        else {
+            return Ok(0);
+        };
+        let mut untagged = 0;
+        {
+            let mut delete =
+                tx.prepare("DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2")?;
+            for item_id in item_ids {
+                let removed = delete.execute(params![item_id, tag_id])?;
+                if removed > 0 {
+                    Self::refresh_search_index_for_item_locked(&tx, item_id)?;
+                }
+                untagged += removed;
+            }
+        }
+        // A tag lives only as long as something carries it.
+        tx.execute(
+            "DELETE FROM tags
+             WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM item_tags WHERE tag_id = ?1)",
+            params![tag_id],
+        )?;
+        tx.commit()?;
 
-    #[test]
-    fn test_set_read_state() {
-        let store = SqliteStore::in_memory().unwrap();
-        let feed = Feed::new("https://example.com/feed.xml".into());
-        let feed_id = store.add_feed(&feed).unwrap();
+        Ok(untagged)
+    }
 
-        let item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
-        store.add_item(&item).unwrap();
+    fn get_item_tags(&self, item_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
 
-        store.set_read(&item.id, true).unwrap();
-        let state = store.get_item_state(&item.id).unwrap().unwrap();
-        assert!(state.is_read);
+        let mut stmt = conn.prepare(
+            "SELECT t.name FROM item_tags it
+             JOIN tags t ON t.id = it.tag_id
+             WHERE it.item_id = ?1
+             ORDER BY t.name COLLATE NOCASE",
+        )?;
+        let tags = stmt
+            .query_map(params![item_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
 
-        store.set_read(&item.id, false).unwrap();
-        let state = store.get_item_state(&item.id).unwrap().unwrap();
-        assert!(!state.is_read);
+        Ok(tags)
     }
 
-    #[test]
-    fn test_unread_count() {
-        let store = SqliteStore::in_memory().unwrap();
-        let feed = Feed::new("https://example.com/feed.xml".into());
-        let feed_id = store.add_feed(&feed).unwrap();
+    fn get_tags(&self) -> Result<Vec<(String, usize)>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
 
-        for i in 0..5 {
-            let item = Item::new(
-                feed_id,
-                "https://example.com/feed.xml",
-                &format!("entry-{}", i),
-            );
-            store.add_item(&item).unwrap();
-        }
+        let mut stmt = conn.prepare(
+            "SELECT t.name, COUNT(*) FROM tags t
+             JOIN item_tags it ON it.tag_id = t.id
+             LEFT JOIN item_state s ON s.item_id = it.item_id
+             WHERE s.is_hidden IS NULL OR s.is_hidden = 0
+             GROUP BY t.id
+             ORDER BY t.name COLLATE NOCASE",
+        )?;
+        let tags = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        assert_eq!(store.get_unread_count(feed_id).unwrap(), 5);
+        Ok(tags)
+    }
 
-        let items = store.get_items_by_feed(feed_id).unwrap();
-        store.set_read(&items[0].id, true).unwrap();
-        store.set_read(&items[1].id, true).unwrap();
+    fn get_items_by_tag(&self, tag: &str, filter: ItemListFilter) -> Result<Vec<Item>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let state_clause = Self::filter_clause(filter, "s");
+        let sql = format!(
+            "SELECT i.id, i.feed_id, i.title, i.link, i.content, i.summary, i.author, i.published_at, i.fetched_at,
+                    i.updated_at
+             FROM items i
+             JOIN item_tags it ON it.item_id = i.id
+             JOIN tags t ON t.id = it.tag_id
+             LEFT JOIN item_state s ON i.id = s.item_id
+             WHERE t.name = ?1 AND {state_clause}
+             ORDER BY i.published_at DESC, i.fetched_at DESC"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let items = stmt
+            .query_map(params![tag.trim()], Self::row_to_item)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    fn save_search(&self, name: &str, query: &str, filter: ItemListFilter) -> Result<i64> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        // Surface query errors now rather than on every count
+        let parsed = SearchQuery::parse(query)?;
+        if parsed.is_empty() {
+            return Err(RivuletError::InvalidQuery(
+                "nothing to search for".to_string(),
+            ));
+        }
+        let mut values = Vec::new();
+        let conditions = Self::search_conditions(&parsed, false, &mut values);
+        conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM items i WHERE {}",
+                conditions.join(" AND ")
+            ),
+            rusqlite::params_from_iter(values),
+            |row| row.get::<_, i64>(0),
+        )?;
+
+        conn.execute(
+            "INSERT INTO saved_searches (name, query, filter, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET query = excluded.query, filter = excluded.filter",
+            params![
+                name.trim(),
+                query.trim(),
+                filter.as_str(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        let id = conn.query_row(
+            "SELECT id FROM saved_searches WHERE name = ?1",
+            params![name.trim()],
+            |row| row.get(0),
+        )?;
+
+        Ok(id)
+    }
+
+    fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let deleted = conn.execute(
+            "DELETE FROM saved_searches WHERE name = ?1",
+            params![name.trim()],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearch>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let search = conn
+            .query_row(
+                "SELECT id, name, query, filter FROM saved_searches WHERE name = ?1",
+                params![name.trim()],
+                Self::row_to_saved_search,
+            )
+            .optional()?;
+
+        Ok(search)
+    }
+
+    fn get_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, query, filter FROM saved_searches ORDER BY name COLLATE NOCASE",
+        )?;
+        let searches = stmt
+            .query_map([], Self::row_to_saved_search)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(searches)
+    }
+
+    fn get_saved_search_unread_counts(&self) -> Result<HashMap<i64, i64>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, query, filter FROM saved_searches ORDER BY name COLLATE NOCASE",
+        )?;
+        let searches = stmt
+            .query_map([], Self::row_to_saved_search)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut counts = HashMap::new();
+        for search in searches {
+            // A query that no longer parses counts as empty
+            let Ok(query) = SearchQuery::parse(&search.query) else {
+                counts.insert(search.id, 0);
+                continue;
+            };
+            let mut values = Vec::new();
+            let mut conditions = Self::search_conditions(&query, false, &mut values);
+            conditions.push(Self::filter_clause(query.view_filter(search.filter), "st"));
+            let count: i64 = conn.query_row(
+                &format!(
+                    "SELECT COUNT(*)
+                     FROM items i
+                     LEFT JOIN item_state st ON st.item_id = i.id
+                     WHERE {} AND (st.is_read IS NULL OR st.is_read = 0)",
+                    conditions.join(" AND ")
+                ),
+                rusqlite::params_from_iter(values),
+                |row| row.get(0),
+            )?;
+            counts.insert(search.id, count);
+        }
+
+        Ok(counts)
+    }
+
+    fn get_feed_schedule(&self, feed_id: i64) -> Result<Option<FeedSchedule>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let schedule = conn
+            .query_row(
+                "SELECT feed_id, interval_secs, next_due_at, consecutive_failures
+                 FROM feed_schedules WHERE feed_id = ?1",
+                params![feed_id],
+                Self::row_to_schedule,
+            )
+            .optional()?;
+
+        Ok(schedule)
+    }
+
+    fn upsert_feed_schedule(&self, schedule: &FeedSchedule) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "INSERT INTO feed_schedules (feed_id, interval_secs, next_due_at, consecutive_failures)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(feed_id) DO UPDATE SET
+                interval_secs = excluded.interval_secs,
+                next_due_at = excluded.next_due_at,
+                consecutive_failures = excluded.consecutive_failures",
+            params![
+                schedule.feed_id,
+                schedule.interval_secs,
+                schedule.next_due_at.to_rfc3339(),
+                schedule.consecutive_failures
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_next_due_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        // A feed without a schedule has never been fetched and is due now.
+        let unscheduled: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM feeds f
+             LEFT JOIN feed_schedules s ON s.feed_id = f.id
+             WHERE s.feed_id IS NULL AND f.is_active = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        if unscheduled > 0 {
+            return Ok(Some(Utc::now()));
+        }
+
+        let next: Option<String> = conn
+            .query_row(
+                "SELECT s.next_due_at FROM feed_schedules s
+             JOIN feeds f ON f.id = s.feed_id
+             WHERE f.is_active = 1
+             ORDER BY julianday(s.next_due_at) LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(next.and_then(|s| Self::parse_datetime(&s)))
+    }
+
+    fn record_fetch_attempt(&self, entry: &FetchLogEntry) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "INSERT INTO feed_fetch_log
+                (feed_id, fetched_at, status_code, error_kind, error_message, latency_ms, bytes, final_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.feed_id,
+                entry.fetched_at.to_rfc3339(),
+                entry.status_code,
+                entry.error_kind,
+                entry.error_message,
+                entry.latency_ms,
+                entry.bytes,
+                entry.final_url
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM feed_fetch_log
+             WHERE feed_id = ?1
+               AND id NOT IN (
+                   SELECT id FROM feed_fetch_log WHERE feed_id = ?1 ORDER BY id DESC LIMIT ?2
+               )",
+            params![entry.feed_id, FETCH_LOG_KEEP],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_fetch_log(&self, feed_id: i64, limit: usize) -> Result<Vec<FetchLogEntry>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, feed_id, fetched_at, status_code, error_kind, error_message,
+                    latency_ms, bytes, final_url
+             FROM feed_fetch_log WHERE feed_id = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let entries = stmt
+            .query_map(params![feed_id, limit as i64], Self::row_to_fetch_log)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    fn get_feed_health(&self) -> Result<Vec<FeedHealth>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "WITH last_attempt AS (
+                 SELECT feed_id, MAX(id) AS id FROM feed_fetch_log GROUP BY feed_id
+             ),
+             last_success AS (
+                 SELECT feed_id, MAX(id) AS id FROM feed_fetch_log
+                 WHERE error_kind IS NULL GROUP BY feed_id
+             ),
+             newest_item AS (
+                 -- SQLite takes the bare column from the row holding the MAX
+                 SELECT feed_id, COALESCE(published_at, fetched_at) AS at,
+                        MAX(julianday(COALESCE(published_at, fetched_at)))
+                 FROM items GROUP BY feed_id
+             )
+             SELECT f.id, f.url, f.title, f.description, f.etag, f.last_modified,
+                    f.last_fetched_at, f.created_at, f.original_url, f.is_active,
+                    COALESCE(s.consecutive_failures, 0), ok.fetched_at, n.at,
+                    l.id, l.feed_id, l.fetched_at, l.status_code, l.error_kind,
+                    l.error_message, l.latency_ms, l.bytes, l.final_url
+             FROM feeds f
+             LEFT JOIN feed_schedules s ON s.feed_id = f.id
+             LEFT JOIN last_attempt la ON la.feed_id = f.id
+             LEFT JOIN feed_fetch_log l ON l.id = la.id
+             LEFT JOIN last_success ls ON ls.feed_id = f.id
+             LEFT JOIN feed_fetch_log ok ON ok.id = ls.id
+             LEFT JOIN newest_item n ON n.feed_id = f.id
+             ORDER BY f.title, f.url",
+        )?;
+        let report = stmt
+            .query_map([], |row| {
+                let feed = Self::row_to_feed(row)?;
+                let last_attempt = match row.get::<_, Option<i64>>(13)? {
+                    Some(_) => Some(Self::row_to_fetch_log_at(row, 13)?),
+                    None => None,
+                };
+                Ok(FeedHealth {
+                    consecutive_failures: row.get(10)?,
+                    last_attempt,
+                    last_success_at: row
+                        .get::<_, Option<String>>(11)?
+                        .and_then(|s| Self::parse_datetime(&s))
+                        .or(feed.last_fetched_at),
+                    newest_item_at: row
+                        .get::<_, Option<String>>(12)?
+                        .and_then(|s| Self::parse_datetime(&s)),
+                    feed,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(report)
+    }
+
+    fn get_consecutive_failures(&self) -> Result<HashMap<i64, i64>> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT feed_id, consecutive_failures FROM feed_schedules
+             WHERE consecutive_failures > 0",
+        )?;
+        let failures = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+        Ok(failures)
+    }
+
+    fn update_item_content(&self, id: &str, content: &str) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| {
+            RivuletError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(e.to_string()),
+            ))
+        })?;
+
+        conn.execute(
+            "UPDATE items SET content = ?1 WHERE id = ?2",
+            params![content, id],
+        )?;
+        Self::refresh_search_index_for_item_locked(&conn, id)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_get_feed() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let id = store.add_feed(&feed).unwrap();
+
+        let retrieved = store.get_feed(id).unwrap().unwrap();
+        assert_eq!(retrieved.url, "https://example.com/feed.xml");
+    }
+
+    #[test]
+    fn test_add_and_get_item() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        item.title = Some("Test Item".into());
+        store.add_item(&item).unwrap();
+
+        let retrieved = store.get_item(&item.id).unwrap().unwrap();
+        assert_eq!(retrieved.title, Some("Test Item".into()));
+    }
+
+    #[test]
+    fn test_enclosures_and_categories_round_trip() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+
+        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "ep-1");
+        let mut enclosure = Enclosure::new("https://cdn.example.com/ep1.mp3".into());
+        enclosure.mime_type = Some("audio/mpeg".into());
+        enclosure.duration_secs = Some(1800);
+        item.enclosures = vec![enclosure.clone()];
+        item.categories = vec!["rust".into(), "Audio".into()];
+        store.add_items(std::slice::from_ref(&item)).unwrap();
+
+        // An edit that adds a second file doesn't duplicate the first.
+        item.title = Some("Episode 1 (extended)".into());
+        item.enclosures
+            .push(Enclosure::new("https://cdn.example.com/ep1.ogg".into()));
+        let report = store.add_items_with_report(&[item.clone()]).unwrap();
+        assert_eq!(report.updated_ids, vec![item.id.clone()]);
+
+        let enclosures = store.get_enclosures(&item.id).unwrap();
+        assert_eq!(enclosures.len(), 2);
+        assert_eq!(enclosures[0], enclosure);
+        assert_eq!(
+            store.get_item_categories(&item.id).unwrap(),
+            vec!["Audio", "rust"]
+        );
+
+        let plain = Item::new(feed_id, "https://example.com/feed.xml", "ep-2");
+        store.add_items(std::slice::from_ref(&plain)).unwrap();
+        store
+            .tag_items(std::slice::from_ref(&item.id), "listen")
+            .unwrap();
+        let mut loaded = vec![store.get_item(&item.id).unwrap().unwrap(), plain];
+        store.load_item_media(&mut loaded).unwrap();
+        assert_eq!(loaded[0].enclosures, enclosures);
+        assert_eq!(loaded[0].categories, vec!["Audio", "rust"]);
+        assert_eq!(loaded[0].tags, vec!["listen"]);
+        assert!(loaded[1].enclosures.is_empty() && loaded[1].tags.is_empty());
+
+        store.delete_feed(feed_id).unwrap();
+        assert!(store.get_enclosures(&item.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bulk_state_change_by_selector() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_a = store
+            .add_feed(&Feed::new("https://a.example.com/feed.xml".into()))
+            .unwrap();
+        let feed_b = store
+            .add_feed(&Feed::new("https://b.example.com/feed.xml".into()))
+            .unwrap();
+        let now = Utc::now();
+        let mut items = Vec::new();
+        for (feed_id, key, title, days_old) in [
+            (feed_a, "a1", "Rust release", 40),
+            (feed_a, "a2", "Gardening", 1),
+            (feed_b, "b1", "Rust async", 2),
+        ] {
+            let mut item = Item::new(feed_id, "https://example.com/feed.xml", key);
+            item.title = Some(title.into());
+            item.published_at = Some(now - chrono::Duration::days(days_old));
+            items.push(item);
+        }
+        store.add_items(&items).unwrap();
+
+        let old = store
+            .select_item_ids(&ItemSelector {
+                feed_id: Some(feed_a),
+                older_than: Some(now - chrono::Duration::days(30)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(old, vec![items[0].id.clone()]);
+
+        let rust = store
+            .select_item_ids(&ItemSelector {
+                search: Some("rust".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(rust.len(), 2);
+
+        let change = ItemStateChange {
+            read: Some(true),
+            starred: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(store.set_item_states(&rust, &change).unwrap(), 2);
+        let state = store.get_item_state(&items[2].id).unwrap().unwrap();
+        assert!(state.is_read && state.is_starred);
+        assert!(state.read_at.is_some());
+        assert!(store.get_item_state(&items[1].id).unwrap().is_none());
+
+        let unstar = ItemStateChange {
+            starred: Some(false),
+            ..Default::default()
+        };
+        store.set_item_states(&rust, &unstar).unwrap();
+        let state = store.get_item_state(&items[2].id).unwrap().unwrap();
+        assert!(state.is_read && !state.is_starred);
+        assert!(state.starred_at.is_none());
+    }
+
+    #[test]
+    fn test_set_read_many_and_mark_feeds_read_count_changes() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let items: Vec<Item> = (0..4)
+            .map(|i| Item::new(feed_id, "https://example.com/feed.xml", &format!("e{i}")))
+            .collect();
+        store.add_items(&items).unwrap();
+        let ids: Vec<String> = items.iter().map(|i| i.id.clone()).collect();
+
+        store.set_read(&ids[0], true).unwrap();
+        assert_eq!(store.set_read_many(&ids[..2], true).unwrap(), 1);
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 2);
+
+        store.set_starred(&ids[2], true).unwrap();
+        let other_id = store
+            .add_feed(&Feed::new("https://other.example.com/feed.xml".into()))
+            .unwrap();
+        let other = Item::new(other_id, "https://other.example.com/feed.xml", "o1");
+        store.add_items(std::slice::from_ref(&other)).unwrap();
+        store.set_starred(&other.id, true).unwrap();
+        let both = [feed_id, other_id];
+        assert_eq!(
+            store
+                .count_unread_in_feeds(&both, ItemListFilter::Starred, None)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            store
+                .mark_feeds_read(&both, ItemListFilter::Starred, None)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            store
+                .count_unread_in_feeds(&[feed_id], ItemListFilter::All, None)
+                .unwrap(),
+            1
+        );
+        store
+            .tag_items(std::slice::from_ref(&ids[3]), "Later")
+            .unwrap();
+        assert_eq!(
+            store
+                .count_unread_in_feeds(&both, ItemListFilter::All, Some("later"))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .mark_feeds_read(&[feed_id], ItemListFilter::All, Some("other"))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            store
+                .mark_feeds_read(&[feed_id], ItemListFilter::All, None)
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .mark_feeds_read(&[], ItemListFilter::All, None)
+                .unwrap(),
+            0
+        );
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 0);
+        assert!(store.get_item_state(&ids[2]).unwrap().unwrap().is_starred);
+
+        assert_eq!(store.set_read_many(&ids, false).unwrap(), 4);
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_set_read_state() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        store.add_item(&item).unwrap();
+
+        store.set_read(&item.id, true).unwrap();
+        let state = store.get_item_state(&item.id).unwrap().unwrap();
+        assert!(state.is_read);
+
+        store.set_read(&item.id, false).unwrap();
+        let state = store.get_item_state(&item.id).unwrap().unwrap();
+        assert!(!state.is_read);
+    }
+
+    #[test]
+    fn test_get_item_states_batches_and_skips_untouched() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let items: Vec<Item> = (0..3)
+            .map(|i| {
+                Item::new(
+                    feed_id,
+                    "https://example.com/feed.xml",
+                    &format!("entry-{i}"),
+                )
+            })
+            .collect();
+        for item in &items {
+            store.add_item(item).unwrap();
+        }
+        store.set_read(&items[0].id, true).unwrap();
+        store.set_starred(&items[2].id, true).unwrap();
+
+        let ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        let states = store.get_item_states(&ids).unwrap();
+        assert_eq!(states.len(), 2);
+        assert!(states[&items[0].id].is_read);
+        assert!(!states.contains_key(&items[1].id));
+        assert!(states[&items[2].id].is_starred);
+        assert!(store.get_item_states(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unread_count() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        for i in 0..5 {
+            let item = Item::new(
+                feed_id,
+                "https://example.com/feed.xml",
+                &format!("entry-{}", i),
+            );
+            store.add_item(&item).unwrap();
+        }
+
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 5);
+
+        let items = store.get_items_by_feed(feed_id).unwrap();
+        store.set_read(&items[0].id, true).unwrap();
+        store.set_read(&items[1].id, true).unwrap();
+
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 3);
+
+        store.set_archived(&items[2].id, true).unwrap();
+        assert_eq!(store.get_unread_count(feed_id).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_due_feeds_follow_schedules() {
+        let store = SqliteStore::in_memory().unwrap();
+        let fresh = store
+            .add_feed(&Feed::new("https://example.com/fresh.xml".into()))
+            .unwrap();
+        let due = store
+            .add_feed(&Feed::new("https://example.com/due.xml".into()))
+            .unwrap();
+        let never = store
+            .add_feed(&Feed::new("https://example.com/never.xml".into()))
+            .unwrap();
+        let now = Utc::now();
+
+        let mut schedule = FeedSchedule::new(fresh, 3600);
+        schedule.next_due_at = now + chrono::Duration::hours(1);
+        store.upsert_feed_schedule(&schedule).unwrap();
+        let mut schedule = FeedSchedule::new(due, 3600);
+        schedule.next_due_at = now - chrono::Duration::minutes(1);
+        store.upsert_feed_schedule(&schedule).unwrap();
+
+        let mut ids: Vec<i64> = store
+            .get_due_feeds(now)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![due, never]);
+        assert!(store.get_next_due_at().unwrap().unwrap() <= Utc::now());
+
+        store.delete_feed(never).unwrap();
+        assert_eq!(
+            store.get_next_due_at().unwrap(),
+            Some(now - chrono::Duration::minutes(1))
+        );
+        store.delete_feed(due).unwrap();
+        assert!(store.get_feed_schedule(due).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fetch_log_feeds_health_report() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+
+        let mut ok = FetchLogEntry::new(feed_id);
+        ok.status_code = Some(200);
+        ok.bytes = Some(1024);
+        store.record_fetch_attempt(&ok).unwrap();
+        for _ in 0..FETCH_LOG_KEEP {
+            let mut failed = FetchLogEntry::new(feed_id);
+            failed.status_code = Some(500);
+            failed.error_kind = Some("http".into());
+            store.record_fetch_attempt(&failed).unwrap();
+        }
+        let mut schedule = FeedSchedule::new(feed_id, 3600);
+        schedule.consecutive_failures = 4;
+        store.upsert_feed_schedule(&schedule).unwrap();
+
+        let log = store.get_fetch_log(feed_id, 100).unwrap();
+        assert_eq!(log.len(), FETCH_LOG_KEEP as usize);
+        assert!(log.iter().all(|e| e.status_code == Some(500)));
+
+        let quiet = store
+            .add_feed(&Feed::new("https://example.com/quiet.xml".into()))
+            .unwrap();
+        let mut older = Item::new(feed_id, "https://example.com/feed.xml", "older");
+        older.published_at = Some(Utc::now() - chrono::Duration::days(2));
+        let mut newer = Item::new(feed_id, "https://example.com/feed.xml", "newer");
+        newer.published_at = Some(Utc::now() - chrono::Duration::days(1));
+        store.add_items(&[newer.clone(), older]).unwrap();
+
+        let report = store.get_feed_health().unwrap();
+        assert_eq!(report.len(), 2);
+        let quiet_health = report.iter().find(|h| h.feed.id == quiet).unwrap();
+        assert!(quiet_health.last_attempt.is_none());
+        assert!(quiet_health.last_success_at.is_none());
+        assert!(quiet_health.newest_item_at.is_none());
+
+        let report: Vec<_> = report
+            .into_iter()
+            .filter(|h| h.feed.id == feed_id)
+            .collect();
+        assert_eq!(report[0].consecutive_failures, 4);
+        assert_eq!(
+            report[0].newest_item_at.map(|at| at.timestamp()),
+            newer.published_at.map(|at| at.timestamp())
+        );
+
+        assert_eq!(
+            report[0]
+                .last_attempt
+                .as_ref()
+                .unwrap()
+                .error_kind
+                .as_deref(),
+            Some("http")
+        );
+        assert!(report[0].is_broken());
+        assert_eq!(
+            store.get_consecutive_failures().unwrap().get(&feed_id),
+            Some(&4)
+        );
+    }
+
+    #[test]
+    fn test_update_feed_url_keeps_original_and_avoids_collisions() {
+        let store = SqliteStore::in_memory().unwrap();
+        let moved = store
+            .add_feed(&Feed::new("http://old.example.com/feed.xml".into()))
+            .unwrap();
+        let existing = store
+            .add_feed(&Feed::new("https://taken.example.com/feed.xml".into()))
+            .unwrap();
+
+        assert!(store
+            .update_feed_url(moved, "https://new.example.com/feed.xml")
+            .unwrap());
+        assert!(store
+            .update_feed_url(moved, "https://newer.example.com/feed.xml")
+            .unwrap());
+        let feed = store.get_feed(moved).unwrap().unwrap();
+        assert_eq!(feed.url, "https://newer.example.com/feed.xml");
+        assert_eq!(
+            feed.original_url.as_deref(),
+            Some("http://old.example.com/feed.xml")
+        );
+        assert_eq!(feed.item_key_url(), "http://old.example.com/feed.xml");
+        assert_eq!(
+            store
+                .get_feed_by_url("http://old.example.com/feed.xml")
+                .unwrap()
+                .map(|f| f.id),
+            Some(moved)
+        );
 
-        assert_eq!(store.get_unread_count(feed_id).unwrap(), 3);
+        assert!(!store
+            .update_feed_url(moved, "https://taken.example.com/feed.xml")
+            .unwrap());
+        assert_eq!(
+            store.get_feed(moved).unwrap().unwrap().url,
+            "https://newer.example.com/feed.xml"
+        );
+        assert_eq!(
+            store.get_feed(existing).unwrap().unwrap().url,
+            "https://taken.example.com/feed.xml"
+        );
+    }
 
-        store.set_archived(&items[2].id, true).unwrap();
-        assert_eq!(store.get_unread_count(feed_id).unwrap(), 2);
+    #[test]
+    fn test_inactive_feeds_are_never_due() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        assert!(store.get_feed(feed_id).unwrap().unwrap().is_active);
+
+        store.set_feed_active(feed_id, false).unwrap();
+        assert!(!store.get_feed(feed_id).unwrap().unwrap().is_active);
+        assert!(store.get_due_feeds(Utc::now()).unwrap().is_empty());
+        assert_eq!(store.get_next_due_at().unwrap(), None);
     }
 
     #[test]
@@ -1360,6 +3546,313 @@ mod tests {
         assert_eq!(error_count, 3);
     }
 
+    #[test]
+    fn test_prune_items_applies_limits_and_exempts_kept_items() {
+        let store = SqliteStore::in_memory().unwrap();
+        let url = "https://example.com/feed.xml";
+        let feed_id = store.add_feed(&Feed::new(url.into())).unwrap();
+        let other_id = store
+            .add_feed(&Feed::new("https://example.com/other.xml".into()))
+            .unwrap();
+
+        let items: Vec<Item> = (0..5)
+            .map(|i| {
+                let mut item = Item::new(feed_id, url, &format!("e{i}"));
+                item.title = Some(format!("Entry {i}"));
+                item.published_at = Some(Utc::now() - chrono::Duration::days(i * 10));
+                item
+            })
+            .collect();
+        let mut other = Item::new(other_id, "https://example.com/other.xml", "old");
+        other.published_at = Some(Utc::now() - chrono::Duration::days(365));
+        store.add_items(&items).unwrap();
+        store.add_item(&other).unwrap();
+        store.set_starred(&items[4].id, true).unwrap();
+        store.set_queued(&items[3].id, true).unwrap();
+
+        let mut policy = RetentionPolicy {
+            default: RetentionLimits {
+                max_age_days: Some(15),
+                max_items: None,
+            },
+            ..Default::default()
+        };
+        policy.feeds.insert(
+            other_id,
+            RetentionLimits {
+                max_age_days: None,
+                max_items: Some(1),
+            },
+        );
+
+        let dry = store.prune_items(&policy, true).unwrap();
+        assert_eq!(dry.feed_items, vec![(feed_id, 1)]);
+        assert_eq!(store.get_all_items().unwrap().len(), 6);
+
+        let report = store.prune_items(&policy, false).unwrap();
+        assert_eq!(report, dry);
+        assert!(store.get_item(&items[2].id).unwrap().is_none());
+        assert!(store.get_item(&items[3].id).unwrap().is_some());
+        assert!(store.get_item(&items[4].id).unwrap().is_some());
+        assert!(store.get_item(&other.id).unwrap().is_some());
+        assert!(store
+            .search_items("\"Entry 2\"", ItemListFilter::All, 10)
+            .unwrap()
+            .is_empty());
+
+        policy.default = RetentionLimits {
+            max_age_days: None,
+            max_items: Some(1),
+        };
+        let report = store.prune_items(&policy, false).unwrap();
+        assert_eq!(report.item_count(), 1);
+        let remaining: Vec<String> = store
+            .get_items_by_feed(feed_id)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.contains(&items[0].id));
+    }
+
+    #[test]
+    fn test_prune_items_drops_old_refresh_runs_but_keeps_latest() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+        let item = Item::new(feed_id, "https://example.com/feed.xml", "e1");
+        store.add_item(&item).unwrap();
+
+        let old_run = store.begin_refresh_run(RefreshSource::Cli, 1).unwrap();
+        store
+            .record_refresh_run_items(old_run, feed_id, std::slice::from_ref(&item.id))
+            .unwrap();
+        store.complete_refresh_run(old_run, 1, 0).unwrap();
+        let latest_run = store.begin_refresh_run(RefreshSource::Cli, 1).unwrap();
+        store.complete_refresh_run(latest_run, 0, 0).unwrap();
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE refresh_runs SET started_at = ?1",
+                params![(Utc::now() - chrono::Duration::days(60)).to_rfc3339()],
+            )
+            .unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            refresh_run_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(store.prune_items(&policy, true).unwrap().refresh_runs, 1);
+        let report = store.prune_items(&policy, false).unwrap();
+        assert_eq!(report.refresh_runs, 1);
+        assert_eq!(report.item_count(), 0);
+        assert_eq!(store.get_latest_refresh_run_id().unwrap(), Some(latest_run));
+
+        let conn = store.conn.lock().unwrap();
+        let run_items: i64 = conn
+            .query_row("SELECT COUNT(*) FROM refresh_run_items", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(run_items, 0);
+        drop(conn);
+        store.vacuum().unwrap();
+    }
+
+    #[test]
+    fn test_pruned_items_are_tombstoned_until_expiry() {
+        let store = SqliteStore::in_memory().unwrap();
+        let url = "https://example.com/feed.xml";
+        let feed_id = store.add_feed(&Feed::new(url.into())).unwrap();
+        let mut old = Item::new(feed_id, url, "old");
+        old.published_at = Some(Utc::now() - chrono::Duration::days(90));
+        old.enclosures = vec![Enclosure::new("https://cdn.example.com/old.mp3".into())];
+        let fresh = Item::new(feed_id, url, "fresh");
+        store.add_items(&[old.clone(), fresh.clone()]).unwrap();
+
+        let policy = RetentionPolicy {
+            default: RetentionLimits {
+                max_age_days: Some(30),
+                max_items: None,
+            },
+            tombstone_days: Some(365),
+            ..Default::default()
+        };
+        assert_eq!(store.prune_items(&policy, false).unwrap().item_count(), 1);
+
+        let report = store
+            .add_items_with_report(&[old.clone(), fresh.clone()])
+            .unwrap();
+        assert_eq!(report.count, 0);
+        store.add_item(&old).unwrap();
+        assert!(!store.item_exists(&old.id).unwrap());
+
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE item_tombstones SET deleted_at = ?1",
+                params![(Utc::now() - chrono::Duration::days(400)).to_rfc3339()],
+            )
+            .unwrap();
+        }
+        let report = store.prune_items(&policy, true).unwrap();
+        assert_eq!(report.tombstones, 1);
+        assert_eq!(report.item_count(), 0);
+        assert_eq!(store.prune_items(&policy, false).unwrap().tombstones, 1);
+        assert_eq!(store.add_items(std::slice::from_ref(&old)).unwrap(), 1);
+
+        store.prune_items(&policy, false).unwrap();
+        store.delete_feed(feed_id).unwrap();
+        let conn = store.conn.lock().unwrap();
+        let tombstones: i64 = conn
+            .query_row("SELECT COUNT(*) FROM item_tombstones", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tombstones, 0);
+    }
+
+    #[test]
+    fn test_cross_posts_share_read_state_and_collapse_in_latest() {
+        let store = SqliteStore::in_memory().unwrap();
+        let mut blog = Feed::new("https://blog.example.com/feed.xml".into());
+        blog.title = Some("Blog".into());
+        let blog_id = store.add_feed(&blog).unwrap();
+        let mut aggregator = Feed::new("https://news.example.org/rss".into());
+        aggregator.title = Some("Aggregator".into());
+        let aggregator_id = store.add_feed(&aggregator).unwrap();
+        let mut mirror = Feed::new("https://mirror.example.net/atom".into());
+        mirror.title = Some("Mirror".into());
+        let mirror_id = store.add_feed(&mirror).unwrap();
+
+        let body = "<p>This week we shipped incremental parsing, a faster indexer and a long list of fixes.</p>";
+        let mut original = Item::new(blog_id, &blog.url, "post-1");
+        original.title = Some("Release notes".into());
+        original.link = Some("https://blog.example.com/posts/release/".into());
+        original.content = Some(body.into());
+        let mut linked = Item::new(aggregator_id, &aggregator.url, "agg-99");
+        linked.title = Some("Release notes (blog.example.com)".into());
+        linked.link =
+            Some("http://www.blog.example.com/posts/release?utm_source=aggregator".into());
+        let mut copied = Item::new(mirror_id, &mirror.url, "mirror-7");
+        copied.title = Some("Release Notes".into());
+        copied.link = Some("https://mirror.example.net/2024/release-notes".into());
+        copied.content = Some(body.into());
+        let unrelated = Item::new(mirror_id, &mirror.url, "mirror-8");
+
+        let report = store
+            .add_items_with_report(&[
+                original.clone(),
+                linked.clone(),
+                copied.clone(),
+                unrelated.clone(),
+            ])
+            .unwrap();
+        assert_eq!(report.count, 4);
+
+        let recent = store
+            .get_recent_items(ItemListFilter::All, 7, 10, None)
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+        let article = recent
+            .iter()
+            .find(|r| r.group_id == original.id)
+            .expect("group keyed by the first copy");
+        let mut also_in = article.also_in.clone();
+        also_in.sort();
+        assert_eq!(
+            also_in,
+            vec!["Aggregator", "Blog", "Mirror"]
+                .into_iter()
+                .filter(|title| *title != article.feed_title)
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+
+        store.set_read(&linked.id, true).unwrap();
+        for id in [&original.id, &linked.id, &copied.id] {
+            assert!(store.get_item_state(id).unwrap().unwrap().is_read);
+        }
+        assert!(store.get_item_state(&unrelated.id).unwrap().is_none());
+
+        store
+            .set_read_many(std::slice::from_ref(&copied.id), false)
+            .unwrap();
+        for id in [&original.id, &linked.id, &copied.id] {
+            assert!(!store.get_item_state(id).unwrap().unwrap().is_read);
+        }
+
+        store
+            .mark_feeds_read(&[blog_id], ItemListFilter::All, None)
+            .unwrap();
+        assert_eq!(store.get_unread_count(aggregator_id).unwrap(), 0);
+        assert_eq!(store.get_unread_count(mirror_id).unwrap(), 1);
+
+        let other = store
+            .add_feed(&Feed::new("https://planet.example.com/rss".into()))
+            .unwrap();
+        let mut late = Item::new(other, "https://planet.example.com/rss", "p-1");
+        late.link = Some("https://blog.example.com/posts/release#comments".into());
+        store.add_item(&late).unwrap();
+        assert!(store.get_item_state(&late.id).unwrap().unwrap().is_read);
+    }
+
+    #[test]
+    fn test_same_feed_items_sharing_a_link_are_not_grouped() {
+        let store = SqliteStore::in_memory().unwrap();
+        let url = "https://example.com/changelog.xml";
+        let feed_id = store.add_feed(&Feed::new(url.into())).unwrap();
+        let items: Vec<Item> = (0..2)
+            .map(|i| {
+                let mut item = Item::new(feed_id, url, &format!("v{i}"));
+                item.link = Some("https://example.com/changelog".into());
+                item
+            })
+            .collect();
+        store.add_items(&items).unwrap();
+
+        store.set_read(&items[0].id, true).unwrap();
+        assert!(store.get_item_state(&items[1].id).unwrap().is_none());
+        let recent = store
+            .get_recent_items(ItemListFilter::All, 7, 10, None)
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn test_backfill_groups_existing_items() {
+        let store = SqliteStore::in_memory().unwrap();
+        let a = store
+            .add_feed(&Feed::new("https://a.example/feed".into()))
+            .unwrap();
+        let b = store
+            .add_feed(&Feed::new("https://b.example/feed".into()))
+            .unwrap();
+        let mut first = Item::new(a, "https://a.example/feed", "1");
+        first.link = Some("https://example.com/story".into());
+        let mut second = Item::new(b, "https://b.example/feed", "1");
+        second.link = Some("https://example.com/story?utm_campaign=x".into());
+        store.add_items(&[first.clone(), second.clone()]).unwrap();
+
+        let mut conn = store.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE items SET canonical_url = NULL, fingerprint = NULL, group_id = NULL",
+            [],
+        )
+        .unwrap();
+        SqliteStore::backfill_duplicate_groups_locked(&mut conn).unwrap();
+        let groups: Vec<String> = conn
+            .prepare("SELECT group_id FROM items ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], groups[1]);
+    }
+
     #[test]
     fn test_add_duplicate_item_ignored() {
         let store = SqliteStore::in_memory().unwrap();
@@ -1370,15 +3863,142 @@ mod tests {
         item.title = Some("Original Title".into());
         store.add_item(&item).unwrap();
 
-        // Same ID with different title — should be ignored
-        let mut dup = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
-        dup.title = Some("Different Title".into());
-        store.add_item(&dup).unwrap();
+        // Same ID and same entry — nothing to update
+        let report = store.add_items_with_report(&[item.clone()]).unwrap();
+        assert_eq!(report.count, 0);
+        assert!(report.updated_ids.is_empty());
+        assert!(store.get_item_revisions(&item.id).unwrap().is_empty());
 
         let retrieved = store.get_item(&item.id).unwrap().unwrap();
         assert_eq!(retrieved.title, Some("Original Title".into()));
     }
 
+    #[test]
+    fn test_changed_item_is_updated_with_revision() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        item.title = Some("Original Title".into());
+        item.summary = Some("teh summary".into());
+        store.add_item(&item).unwrap();
+        store
+            .update_item_content(&item.id, "<p>Scraped article</p>")
+            .unwrap();
+
+        let mut changed = item.clone();
+        changed.title = Some("Fixed Title".into());
+        changed.summary = Some("the summary".into());
+        let report = store.add_items_with_report(&[changed]).unwrap();
+        assert_eq!(report.count, 0);
+        assert_eq!(report.updated_ids, vec![item.id.clone()]);
+
+        let retrieved = store.get_item(&item.id).unwrap().unwrap();
+        assert_eq!(retrieved.title.as_deref(), Some("Fixed Title"));
+        assert_eq!(retrieved.summary.as_deref(), Some("the summary"));
+        assert_eq!(retrieved.content.as_deref(), Some("<p>Scraped article</p>"));
+        assert!(retrieved.updated_at.is_some());
+
+        let revisions = store.get_item_revisions(&item.id).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].title.as_deref(), Some("Original Title"));
+        assert_eq!(revisions[0].summary.as_deref(), Some("teh summary"));
+
+        let other = Item::new(feed_id, "https://example.com/feed.xml", "entry-2");
+        store.add_item(&other).unwrap();
+        let by_item = store
+            .get_revisions_for_items(&[item.id.clone(), other.id.clone()])
+            .unwrap();
+        assert_eq!(by_item.len(), 1);
+        assert_eq!(by_item[&item.id], revisions);
+    }
+
+    #[test]
+    fn test_edited_item_joins_the_group_of_its_new_link() {
+        let store = SqliteStore::in_memory().unwrap();
+        let a = store
+            .add_feed(&Feed::new("https://a.example/feed".into()))
+            .unwrap();
+        let b = store
+            .add_feed(&Feed::new("https://b.example/feed".into()))
+            .unwrap();
+        let mut original = Item::new(a, "https://a.example/feed", "1");
+        original.link = Some("https://example.com/story".into());
+        let mut copy = Item::new(b, "https://b.example/feed", "1");
+        copy.link = Some("https://b.example/draft".into());
+        store.add_items(&[original.clone(), copy.clone()]).unwrap();
+
+        let group_of = |id: &str| -> String {
+            store
+                .conn
+                .lock()
+                .unwrap()
+                .query_row(
+                    "SELECT group_id FROM items WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        assert_ne!(group_of(&original.id), group_of(&copy.id));
+
+        copy.link = Some("https://example.com/story?utm_source=rss".into());
+        store.add_items(std::slice::from_ref(&copy)).unwrap();
+        assert_eq!(group_of(&copy.id), group_of(&original.id));
+
+        copy.link = Some("https://b.example/elsewhere".into());
+        store.add_items(std::slice::from_ref(&copy)).unwrap();
+        assert_eq!(group_of(&copy.id), copy.id);
+    }
+
+    #[test]
+    fn test_item_without_hash_is_backfilled_without_revision() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        item.title = Some("Title".into());
+        store.add_item(&item).unwrap();
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute("UPDATE items SET content_hash = NULL", [])
+                .unwrap();
+        }
+
+        let mut changed = item.clone();
+        changed.title = Some("New Title".into());
+        let report = store.add_items_with_report(&[changed.clone()]).unwrap();
+        assert!(report.updated_ids.is_empty());
+        assert!(store.get_item_revisions(&item.id).unwrap().is_empty());
+
+        // The backfilled hash is the new entry's, so repeating it is a no-op
+        let report = store.add_items_with_report(&[changed]).unwrap();
+        assert!(report.updated_ids.is_empty());
+    }
+
+    #[test]
+    fn test_item_revisions_are_capped() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let mut item = Item::new(feed_id, "https://example.com/feed.xml", "entry-1");
+        for version in 0..(REVISIONS_KEEP + 3) {
+            item.title = Some(format!("Version {}", version));
+            store.add_items_with_report(&[item.clone()]).unwrap();
+        }
+
+        let revisions = store.get_item_revisions(&item.id).unwrap();
+        assert_eq!(revisions.len(), REVISIONS_KEEP as usize);
+        assert_eq!(revisions[0].title.as_deref(), Some("Version 2"));
+        assert_eq!(
+            revisions.last().unwrap().title.as_deref(),
+            Some(format!("Version {}", REVISIONS_KEEP + 1).as_str())
+        );
+    }
+
     #[test]
     fn test_item_exists() {
         let store = SqliteStore::in_memory().unwrap();
@@ -1544,7 +4164,7 @@ mod tests {
             .search_items("release", ItemListFilter::All, 10)
             .unwrap();
         assert_eq!(title_results.len(), 1);
-        assert_eq!(title_results[0].id, item.id);
+        assert_eq!(title_results[0].item.id, item.id);
 
         store
             .update_item_content(&item.id, "Ownership and borrow checker deep dive")
@@ -1554,7 +4174,115 @@ mod tests {
             .search_items("ownership", ItemListFilter::All, 10)
             .unwrap();
         assert_eq!(content_results.len(), 1);
-        assert_eq!(content_results[0].id, item.id);
+        assert_eq!(content_results[0].item.id, item.id);
+    }
+
+    #[test]
+    fn test_tags_are_listed_removed_and_searchable() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let mut first = Item::new(feed_id, "https://example.com/feed.xml", "first");
+        first.title = Some("Quarterly report".into());
+        let mut second = Item::new(feed_id, "https://example.com/feed.xml", "second");
+        second.title = Some("Release notes".into());
+        store.add_items(&[first.clone(), second.clone()]).unwrap();
+        let both = [first.id.clone(), second.id.clone()];
+
+        assert_eq!(store.tag_items(&both, "Finance").unwrap(), 2);
+        assert_eq!(
+            store.tag_items(&[first.id.clone()], "archive-me").unwrap(),
+            1
+        );
+        assert_eq!(
+            store.get_item_tags(&first.id).unwrap(),
+            vec!["archive-me", "Finance"]
+        );
+        assert_eq!(
+            store.get_tags().unwrap(),
+            vec![("archive-me".to_string(), 1), ("Finance".to_string(), 2)]
+        );
+        let tagged = store
+            .get_items_by_tag("finance", ItemListFilter::All)
+            .unwrap();
+        assert_eq!(tagged.len(), 2);
+
+        let found = store
+            .search_items("finance", ItemListFilter::All, 10)
+            .unwrap();
+        assert_eq!(found.len(), 2);
+
+        assert_eq!(
+            store.untag_items(&[second.id.clone()], "FINANCE").unwrap(),
+            1
+        );
+        assert!(store.get_item_tags(&second.id).unwrap().is_empty());
+        let found = store
+            .search_items("finance", ItemListFilter::All, 10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].item.id, first.id);
+
+        // Removing the last use of a tag drops the tag itself
+        store
+            .untag_items(&[first.id.clone()], "archive-me")
+            .unwrap();
+        assert_eq!(store.get_tags().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_saved_searches_round_trip_and_count_unread() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed = Feed::new("https://example.com/feed.xml".into());
+        let feed_id = store.add_feed(&feed).unwrap();
+
+        let items: Vec<Item> = ["Rust 1.80", "Rust in production", "Go generics"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                let mut item = Item::new(feed_id, &feed.url, &format!("entry-{i}"));
+                item.title = Some(title.to_string());
+                item
+            })
+            .collect();
+        store.add_items(&items).unwrap();
+        store.set_read(&items[0].id, true).unwrap();
+
+        let id = store
+            .save_search("Rust", "rust", ItemListFilter::All)
+            .unwrap();
+        let starred = store
+            .save_search("starred go", "go", ItemListFilter::Starred)
+            .unwrap();
+        assert!(store
+            .save_search("broken", "is:shiny", ItemListFilter::All)
+            .is_err());
+
+        let counts = store.get_saved_search_unread_counts().unwrap();
+        assert_eq!(counts[&id], 1);
+        assert_eq!(counts[&starred], 0);
+        store.set_starred(&items[2].id, true).unwrap();
+        assert_eq!(store.get_saved_search_unread_counts().unwrap()[&starred], 1);
+
+        let search = store.get_saved_search("RUST").unwrap().unwrap();
+        assert_eq!(search.query, "rust");
+        assert_eq!(search.items(&store).unwrap().len(), 2);
+
+        // Saving under an existing name replaces the query, keeping the ID
+        assert_eq!(
+            store
+                .save_search("rust", "production", ItemListFilter::Unread)
+                .unwrap(),
+            id
+        );
+        let search = store.get_saved_search("rust").unwrap().unwrap();
+        assert_eq!(search.filter, ItemListFilter::Unread);
+        assert_eq!(search.items(&store).unwrap().len(), 1);
+
+        assert!(store.delete_saved_search("Rust").unwrap());
+        assert!(!store.delete_saved_search("Rust").unwrap());
+        assert_eq!(store.get_saved_searches().unwrap().len(), 1);
     }
 
     #[test]
@@ -1576,13 +4304,84 @@ mod tests {
             .search_items("searchable", ItemListFilter::All, 10)
             .unwrap();
         assert_eq!(active_results.len(), 1);
-        assert_eq!(active_results[0].id, active.id);
+        assert_eq!(active_results[0].item.id, active.id);
 
         let archived_results = store
             .search_items("searchable", ItemListFilter::Archived, 10)
             .unwrap();
         assert_eq!(archived_results.len(), 1);
-        assert_eq!(archived_results[0].id, archived.id);
+        assert_eq!(archived_results[0].item.id, archived.id);
+    }
+
+    #[test]
+    fn test_search_query_language_filters_and_snippets() {
+        let store = SqliteStore::in_memory().unwrap();
+        let mut rust_feed = Feed::new("https://rust.example/feed.xml".into());
+        rust_feed.title = Some("Rust Blog".into());
+        let rust_feed_id = store.add_feed(&rust_feed).unwrap();
+        let news_feed_id = store
+            .add_feed(&Feed::new("https://news.example/feed.xml".into()))
+            .unwrap();
+
+        let mut cpp = Item::new(rust_feed_id, &rust_feed.url, "cpp");
+        cpp.title = Some("Porting c++ code".into());
+        cpp.author = Some("Ferris".into());
+        cpp.content =
+            Some("<p>Moving a <b>c++</b> codebase to Rust, one module at a time.</p>".into());
+        cpp.published_at = Some("2024-03-10T00:00:00Z".parse().unwrap());
+        let mut news = Item::new(news_feed_id, "https://news.example/feed.xml", "news");
+        news.title = Some("Rust in the news".into());
+        news.author = Some("Reporter".into());
+        news.published_at = Some("2024-05-01T00:00:00Z".parse().unwrap());
+        store.add_items(&[cpp.clone(), news.clone()]).unwrap();
+        store.tag_items(&[news.id.clone()], "press").unwrap();
+        store.set_starred(&news.id, true).unwrap();
+        store.set_read(&news.id, true).unwrap();
+
+        let ids = |query: &str| -> Vec<String> {
+            store
+                .search_items(query, ItemListFilter::All, 10)
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.item.id)
+                .collect()
+        };
+        assert_eq!(ids("c++"), vec![cpp.id.clone()]);
+        assert_eq!(ids("foo-bar OR codebase"), vec![cpp.id.clone()]);
+        assert_eq!(ids("rust feed:\"rust blog\""), vec![cpp.id.clone()]);
+        assert_eq!(ids("rust -feed:rust.example"), vec![news.id.clone()]);
+        assert_eq!(ids("author:ferr*"), vec![cpp.id.clone()]);
+        assert_eq!(ids("title:rust"), vec![news.id.clone()]);
+        assert_eq!(ids("tag:PRESS"), vec![news.id.clone()]);
+        assert_eq!(ids("is:starred"), vec![news.id.clone()]);
+        assert_eq!(ids("rust is:unread"), vec![cpp.id.clone()]);
+        assert_eq!(ids("after:2024-04-01"), vec![news.id.clone()]);
+        assert_eq!(ids("rust before:2024-04-01"), vec![cpp.id.clone()]);
+        assert!(matches!(
+            store.search_items("is:", ItemListFilter::All, 10),
+            Err(RivuletError::InvalidQuery(_))
+        ));
+
+        let hits = store
+            .search_items("codebase", ItemListFilter::All, 10)
+            .unwrap();
+        let snippet = hits[0].snippet.as_deref().unwrap();
+        assert!(snippet.contains("\u{2}codebase\u{3}"));
+        assert!(!snippet.contains('<'));
+        let hits = store
+            .search_items("is:starred", ItemListFilter::All, 10)
+            .unwrap();
+        assert_eq!(hits[0].snippet, None);
+
+        assert_eq!(
+            store
+                .select_item_ids(&ItemSelector {
+                    search: Some("-tag:press".into()),
+                    ..ItemSelector::default()
+                })
+                .unwrap(),
+            vec![cpp.id.clone()]
+        );
     }
 
     #[test]
@@ -1675,4 +4474,74 @@ mod tests {
         let store = SqliteStore::in_memory().unwrap();
         assert!(store.get_item_state("nonexistent-id").unwrap().is_none());
     }
+
+    #[test]
+    fn test_categories_nest_and_link_feeds() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed_id = store
+            .add_feed(&Feed::new("https://example.com/feed.xml".into()))
+            .unwrap();
+
+        let tech = store
+            .add_category(&Category::new("Tech".into(), None))
+            .unwrap();
+        let rust = store
+            .add_category(&Category::new("Rust".into(), Some(tech)))
+            .unwrap();
+        // Re-adding an existing folder returns the same id instead of failing.
+        assert_eq!(
+            store
+                .add_category(&Category::new("Tech".into(), None))
+                .unwrap(),
+            tech
+        );
+        // The same name under a different parent is a separate folder.
+        let nested_tech = store
+            .add_category(&Category::new("Tech".into(), Some(rust)))
+            .unwrap();
+        assert_ne!(nested_tech, tech);
+
+        store.add_feed_to_category(feed_id, rust).unwrap();
+        store.add_feed_to_category(feed_id, rust).unwrap();
+        assert_eq!(
+            store.get_feed_category_links().unwrap(),
+            vec![(feed_id, rust)]
+        );
+
+        let categories = store.get_all_categories().unwrap();
+        assert_eq!(categories.len(), 3);
+        let stored_rust = categories.iter().find(|c| c.id == rust).unwrap();
+        assert_eq!(stored_rust.parent_id, Some(tech));
+
+        store.remove_feed_from_category(feed_id, rust).unwrap();
+        assert!(store.get_feed_category_links().unwrap().is_empty());
+
+        store.add_feed_to_category(feed_id, rust).unwrap();
+        store.delete_category(tech).unwrap();
+        assert!(store.get_all_categories().unwrap().is_empty());
+        assert!(store.get_feed_category_links().unwrap().is_empty());
+        assert!(store.get_feed(feed_id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_get_unread_counts_groups_by_feed() {
+        let store = SqliteStore::in_memory().unwrap();
+        let feed1_id = store
+            .add_feed(&Feed::new("https://example.com/feed1.xml".into()))
+            .unwrap();
+        let feed2_id = store
+            .add_feed(&Feed::new("https://example.com/feed2.xml".into()))
+            .unwrap();
+
+        let read = Item::new(feed1_id, "https://example.com/feed1.xml", "e1");
+        store.add_item(&read).unwrap();
+        store
+            .add_item(&Item::new(feed1_id, "https://example.com/feed1.xml", "e2"))
+            .unwrap();
+        store.set_read(&read.id, true).unwrap();
+
+        let counts = store.get_unread_counts().unwrap();
+        assert_eq!(counts.get(&feed1_id), Some(&1));
+        assert_eq!(counts.get(&feed2_id), None);
+    }
 }
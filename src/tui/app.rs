@@ -1,7 +1,15 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
 use ratatui::widgets::ListState;
 
-use crate::domain::{Feed, Item, ItemState};
-use crate::store::{ItemListFilter, RecentItem};
+use crate::app::Result;
+use crate::domain::health::BROKEN_AFTER_FAILURES;
+use crate::domain::{Category, Feed, Item, ItemRevision, ItemState};
+use crate::html::html_to_text;
+use crate::store::{ItemListFilter, RecentItem, SavedSearch, SearchQuery};
+use crate::tui::diff::{text_diff, DiffLine};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActivePane {
@@ -81,16 +89,239 @@ impl ItemView {
 
 pub const PAGE_SIZE: usize = 10;
 
+/// What a confirmed bulk mark-read applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkReadTarget {
+    /// Exactly these items (the visible list, or part of it)
+    Items(Vec<String>),
+    /// Every item in these feeds that passes the current `ItemView`
+    Feeds(Vec<i64>),
+}
+
+/// A bulk mark-read waiting for y/n, like `pending_delete`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMarkRead {
+    pub target: MarkReadTarget,
+    /// Shown in the prompt, e.g. `12 items in Latest`
+    pub description: String,
+}
+
+/// What a status-bar text prompt is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Comma-separated tags that replace the item's current ones
+    Tags { item_id: String },
+    /// Tag the item lists are narrowed to; empty clears the filter
+    TagFilter,
+    /// Full-text query, searched as it is typed
+    Search,
+}
+
+/// A line of input typed into the status bar. While one is open every key
+/// goes to it; Enter submits and Esc cancels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextPrompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+impl TextPrompt {
+    pub fn new(kind: PromptKind, input: String) -> Self {
+        Self { kind, input }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.kind {
+            PromptKind::Tags { .. } => "Tags",
+            PromptKind::TagFilter => "Filter by tag",
+            PromptKind::Search => "Search",
+        }
+    }
+}
+
+/// Splits comma-separated prompt input into tags, dropping blanks and
+/// case-insensitive repeats.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Link-hint mode over the selected item's links. While it is open every
+/// key goes to it; a number or j/k picks a link and o/y/a act on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkHints {
+    /// Hint `n` is `links[n - 1]`, the same numbers as the preview footnotes
+    pub links: Vec<String>,
+    pub selected: usize,
+    /// Digits typed so far
+    pub typed: String,
+}
+
+impl LinkHints {
+    /// `None` when there are no links to pick from.
+    pub fn new(links: Vec<String>) -> Option<Self> {
+        (!links.is_empty()).then(|| Self {
+            links,
+            selected: 0,
+            typed: String::new(),
+        })
+    }
+
+    pub fn selected_link(&self) -> &str {
+        &self.links[self.selected]
+    }
+
+    /// Selects the link numbered by the digits typed so far. A digit that
+    /// would run past the last link starts a new number instead.
+    pub fn push_digit(&mut self, digit: char) {
+        let number = |typed: &str| {
+            typed
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=self.links.len()).contains(n))
+        };
+        self.typed.push(digit);
+        if number(&self.typed).is_none() {
+            self.typed = digit.to_string();
+        }
+        match number(&self.typed) {
+            Some(n) => self.selected = n - 1,
+            None => self.typed.clear(),
+        }
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        self.typed.clear();
+        self.selected = if down {
+            (self.selected + 1).min(self.links.len() - 1)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+}
+
+/// The diff view's lines for one item, kept between frames: the diff is
+/// too slow to redo on every redraw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDiff {
+    pub item_id: String,
+    /// `replaced_at` of the revision the item is diffed against
+    pub replaced_at: DateTime<Utc>,
+    pub lines: Vec<DiffLine>,
+}
+
+/// How long the `/` prompt waits after the last keystroke before searching.
+pub const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// A search started with `/`. Its results fill the Reader Items pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchState {
+    pub query: String,
+    /// What the Reader had loaded, and the active tab, before the search;
+    /// restored when the search is cancelled
+    pub previous: Option<ItemSource>,
+    pub previous_tab: AppTab,
+    /// Set by each keystroke; the search runs once this is `SEARCH_DEBOUNCE` old
+    pub pending_since: Option<Instant>,
+    /// Excerpts around the hits, by item ID, for the preview
+    pub snippets: HashMap<String, String>,
+}
+
+impl SearchState {
+    /// Lowercased words of the query to highlight, leaving out filters and
+    /// excluded terms.
+    pub fn terms(&self) -> Vec<String> {
+        SearchQuery::parse(&self.query)
+            .map(|query| query.highlight_terms())
+            .unwrap_or_default()
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.pending_since
+            .is_some_and(|since| now.duration_since(since) >= SEARCH_DEBOUNCE)
+    }
+}
+
+/// The query as typed, with a trailing `*` on the last word so results
+/// appear while it is still being typed. Quoted phrases and filters such as
+/// `feed:` are left alone.
+pub fn incremental_query(input: &str) -> String {
+    let query = input.trim();
+    let last_word = query.rsplit(char::is_whitespace).next().unwrap_or("");
+    let open_quote = query.matches('"').count() % 2 == 1;
+    let is_operator = matches!(last_word, "AND" | "OR" | "NOT");
+    let is_filter = last_word
+        .trim_start_matches('-')
+        .split_once(':')
+        .is_some_and(|(key, _)| {
+            matches!(
+                key.to_ascii_lowercase().as_str(),
+                "feed" | "tag" | "before" | "after" | "is"
+            )
+        });
+    if !open_quote
+        && !is_operator
+        && !is_filter
+        && query.ends_with(|c: char| c.is_alphanumeric())
+        && !query.ends_with('"')
+    {
+        format!("{}*", query)
+    } else {
+        query.to_string()
+    }
+}
+
+/// One visible row in the Reader feed rail. Smart feeds, folders and feeds
+/// share a single cursor (`TuiApp::feed_index`), so the rail is flattened into
+/// rows whenever feeds, folders, or collapse state change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedRow {
+    /// Index into `TuiApp::saved_searches`
+    SavedSearch {
+        search_index: usize,
+    },
+    Category {
+        category_id: i64,
+        name: String,
+        depth: usize,
+        unread: i64,
+        collapsed: bool,
+    },
+    Feed {
+        feed_index: usize,
+        depth: usize,
+    },
+}
+
+/// Where the Reader Items pane's items came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemSource {
+    Feed(i64),
+    /// A saved search (smart feed), by ID
+    SavedSearch(i64),
+    /// Results of the `/` search in `TuiApp::search`
+    Search,
+}
+
 /// All four pieces of state that describe "items for the loaded Reader feed are
 /// rendered." Bundling them makes the invariant structural: you cannot have an
-/// `items` Vec without a `feed_id`, and you cannot have an `item_index` /
+/// `items` Vec without a `source`, and you cannot have an `item_index` /
 /// `item_list_state` referring to a different list than the one that's loaded.
 ///
 /// Construct via [`LoadedFeed::new`]; mutate the cursor only through the
 /// methods on this type so `item_index` and `item_list_state` cannot drift.
 #[derive(Debug)]
 pub struct LoadedFeed {
-    pub feed_id: i64,
+    pub source: ItemSource,
     pub items: Vec<Item>,
     pub item_index: usize,
     pub item_list_state: ListState,
@@ -98,12 +329,24 @@ pub struct LoadedFeed {
 
 impl LoadedFeed {
     pub fn new(feed_id: i64, items: Vec<Item>) -> Self {
+        Self::from_source(ItemSource::Feed(feed_id), items)
+    }
+
+    pub fn saved_search(search_id: i64, items: Vec<Item>) -> Self {
+        Self::from_source(ItemSource::SavedSearch(search_id), items)
+    }
+
+    pub fn search_results(items: Vec<Item>) -> Self {
+        Self::from_source(ItemSource::Search, items)
+    }
+
+    fn from_source(source: ItemSource, items: Vec<Item>) -> Self {
         let mut state = ListState::default();
         if !items.is_empty() {
             state.select(Some(0));
         }
         Self {
-            feed_id,
+            source,
             items,
             item_index: 0,
             item_list_state: state,
@@ -181,18 +424,47 @@ impl LoadedFeed {
     }
 }
 
+fn plural_items(count: usize) -> &'static str {
+    if count == 1 {
+        "item"
+    } else {
+        "items"
+    }
+}
+
 pub struct TuiApp {
     pub active_tab: AppTab,
     pub active_pane: ActivePane,
     pub feed_panel: FeedPanelState,
     pub feeds: Vec<Feed>,
+    pub categories: Vec<Category>,
+    // (feed_id, category_id) pairs
+    pub feed_category_links: Vec<(i64, i64)>,
+    pub collapsed_categories: HashSet<i64>,
+    pub unread_counts: HashMap<i64, i64>,
+    /// Smart feeds, listed at the top of the rail
+    pub saved_searches: Vec<SavedSearch>,
+    /// Unread matches per saved search ID
+    pub saved_search_unread: HashMap<i64, i64>,
+    /// Consecutive fetch failures per feed; only failing feeds are present.
+    pub feed_failures: HashMap<i64, i64>,
+    /// Flattened feed rail; `feed_index` indexes into this, not `feeds`.
+    pub feed_rows: Vec<FeedRow>,
     pub latest_items: Vec<RecentItem>,
-    /// `Some` iff a feed has been loaded into the Reader Items pane. The four
-    /// pieces of correlated state (feed_id / items / item_index /
+    /// `Some` iff a feed or smart feed has been loaded into the Reader Items
+    /// pane. The four pieces of correlated state (source / items / item_index /
     /// item_list_state) live together inside `LoadedFeed` so they cannot
     /// drift.
     pub loaded_feed: Option<LoadedFeed>,
-    pub item_states: std::collections::HashMap<String, ItemState>,
+    pub item_states: HashMap<String, ItemState>,
+    /// Earlier versions of loaded items that have any, oldest first
+    pub item_revisions: HashMap<String, Vec<ItemRevision>>,
+    /// Flag updated items and allow the diff view (`[ui] show_updates`)
+    pub show_updates: bool,
+    /// Preview shows changes since the previous revision instead of the item
+    pub show_diff: bool,
+    /// Diff of the selected item while `show_diff` is on
+    pub content_diff: Option<ContentDiff>,
     pub feed_index: usize,
     pub latest_index: usize,
     pub item_view: ItemView,
@@ -211,6 +483,15 @@ pub struct TuiApp {
     pub recent_limit: usize,
     // Pending delete confirmation (feed_id, feed_title)
     pub pending_delete: Option<(i64, String)>,
+    pub pending_mark_read: Option<PendingMarkRead>,
+    pub prompt: Option<TextPrompt>,
+    /// Item lists only show items carrying this tag
+    pub tag_filter: Option<String>,
+    pub search: Option<SearchState>,
+    /// Preview rows holding a search match, as last rendered; `n` / `N`
+    /// scroll between them
+    pub preview_match_rows: Vec<u16>,
+    pub link_hints: Option<LinkHints>,
     // Pending multi-key chord (e.g. Ctrl+W awaiting a direction)
     pub pending_chord: Option<PendingChord>,
 }
@@ -227,9 +508,21 @@ impl TuiApp {
             active_pane: ActivePane::Items,
             feed_panel: FeedPanelState::Collapsed,
             feeds: Vec::new(),
+            categories: Vec::new(),
+            feed_category_links: Vec::new(),
+            collapsed_categories: HashSet::new(),
+            unread_counts: HashMap::new(),
+            saved_searches: Vec::new(),
+            saved_search_unread: HashMap::new(),
+            feed_failures: HashMap::new(),
+            feed_rows: Vec::new(),
             latest_items: Vec::new(),
             loaded_feed: None,
-            item_states: std::collections::HashMap::new(),
+            item_states: HashMap::new(),
+            item_revisions: HashMap::new(),
+            show_updates: true,
+            show_diff: false,
+            content_diff: None,
             feed_index: 0,
             latest_index: 0,
             item_view: ItemView::All,
@@ -245,16 +538,299 @@ impl TuiApp {
             recent_days: 7,
             recent_limit: 200,
             pending_delete: None,
+            pending_mark_read: None,
+            prompt: None,
+            tag_filter: None,
+            search: None,
+            preview_match_rows: Vec::new(),
+            link_hints: None,
             pending_chord: None,
         }
     }
 
+    pub fn is_feed_broken(&self, feed: &Feed) -> bool {
+        !feed.is_active
+            || self.feed_failures.get(&feed.id).copied().unwrap_or(0) >= BROKEN_AFTER_FAILURES
+    }
+
     pub fn selected_feed(&self) -> Option<&Feed> {
-        self.feeds.get(self.feed_index)
+        match self.feed_rows.get(self.feed_index)? {
+            FeedRow::Feed { feed_index, .. } => self.feeds.get(*feed_index),
+            FeedRow::Category { .. } | FeedRow::SavedSearch { .. } => None,
+        }
+    }
+
+    pub fn selected_category_id(&self) -> Option<i64> {
+        match self.feed_rows.get(self.feed_index)? {
+            FeedRow::Category { category_id, .. } => Some(*category_id),
+            FeedRow::Feed { .. } | FeedRow::SavedSearch { .. } => None,
+        }
+    }
+
+    pub fn selected_saved_search(&self) -> Option<&SavedSearch> {
+        match self.feed_rows.get(self.feed_index)? {
+            FeedRow::SavedSearch { search_index } => self.saved_searches.get(*search_index),
+            FeedRow::Feed { .. } | FeedRow::Category { .. } => None,
+        }
+    }
+
+    /// Row position of the first visible occurrence of `feed_id` in the rail.
+    pub fn feed_row_position(&self, feed_id: i64) -> Option<usize> {
+        self.feed_rows.iter().position(|row| match row {
+            FeedRow::Feed { feed_index, .. } => {
+                self.feeds.get(*feed_index).map(|feed| feed.id) == Some(feed_id)
+            }
+            FeedRow::Category { .. } | FeedRow::SavedSearch { .. } => false,
+        })
+    }
+
+    pub fn saved_search_row_position(&self, search_id: i64) -> Option<usize> {
+        self.feed_rows.iter().position(|row| match row {
+            FeedRow::SavedSearch { search_index } => {
+                self.saved_searches
+                    .get(*search_index)
+                    .map(|search| search.id)
+                    == Some(search_id)
+            }
+            FeedRow::Feed { .. } | FeedRow::Category { .. } => false,
+        })
+    }
+
+    pub fn toggle_category(&mut self, category_id: i64) {
+        if !self.collapsed_categories.remove(&category_id) {
+            self.collapsed_categories.insert(category_id);
+        }
+        self.rebuild_feed_rows();
+    }
+
+    /// Rebuild `feed_rows` from feeds, folders, links, and collapse state.
+    /// Smart feeds come first, then folders (nested, sorted by name) followed
+    /// by feeds that are not filed anywhere. Without any smart feeds or
+    /// folders the rows mirror `feeds` 1:1.
+    pub fn rebuild_feed_rows(&mut self) {
+        let mut rows: Vec<FeedRow> = (0..self.saved_searches.len())
+            .map(|search_index| FeedRow::SavedSearch { search_index })
+            .collect();
+        let mut children: HashMap<Option<i64>, Vec<&Category>> = HashMap::new();
+        for category in &self.categories {
+            children
+                .entry(category.parent_id)
+                .or_default()
+                .push(category);
+        }
+
+        let mut categorized = HashSet::new();
+        for (feed_id, _) in &self.feed_category_links {
+            categorized.insert(*feed_id);
+        }
+        let folder_feeds = self.feeds_by_category();
+
+        if let Some(roots) = children.get(&None) {
+            for category in roots {
+                self.push_category_rows(category, 0, &children, &folder_feeds, &mut rows);
+            }
+        }
+
+        for (index, feed) in self.feeds.iter().enumerate() {
+            if !categorized.contains(&feed.id) {
+                rows.push(FeedRow::Feed {
+                    feed_index: index,
+                    depth: 0,
+                });
+            }
+        }
+
+        self.feed_rows = rows;
+        if self.feed_rows.is_empty() {
+            self.feed_index = 0;
+            self.feed_list_state.select(None);
+        } else {
+            self.feed_index = self.feed_index.min(self.feed_rows.len() - 1);
+            self.feed_list_state.select(Some(self.feed_index));
+        }
+    }
+
+    /// Feeds in a folder and all of its subfolders.
+    pub fn category_feed_ids(&self, category_id: i64) -> Vec<i64> {
+        let mut children: HashMap<Option<i64>, Vec<&Category>> = HashMap::new();
+        for category in &self.categories {
+            children
+                .entry(category.parent_id)
+                .or_default()
+                .push(category);
+        }
+        let mut feed_ids = HashSet::new();
+        self.collect_subtree_feeds(
+            category_id,
+            &children,
+            &self.feeds_by_category(),
+            &mut feed_ids,
+        );
+        let mut feed_ids: Vec<i64> = feed_ids.into_iter().collect();
+        feed_ids.sort_unstable();
+        feed_ids
+    }
+
+    /// Indices into `feeds` of the feeds filed directly in each folder, in
+    /// feed order.
+    fn feeds_by_category(&self) -> HashMap<i64, Vec<usize>> {
+        let feed_index: HashMap<i64, usize> = self
+            .feeds
+            .iter()
+            .enumerate()
+            .map(|(index, feed)| (feed.id, index))
+            .collect();
+        let mut by_category: HashMap<i64, Vec<usize>> = HashMap::new();
+        for (feed_id, category_id) in &self.feed_category_links {
+            if let Some(&index) = feed_index.get(feed_id) {
+                by_category.entry(*category_id).or_default().push(index);
+            }
+        }
+        for indices in by_category.values_mut() {
+            indices.sort_unstable();
+            indices.dedup();
+        }
+        by_category
+    }
+
+    fn push_category_rows(
+        &self,
+        category: &Category,
+        depth: usize,
+        children: &HashMap<Option<i64>, Vec<&Category>>,
+        folder_feeds: &HashMap<i64, Vec<usize>>,
+        rows: &mut Vec<FeedRow>,
+    ) {
+        let collapsed = self.collapsed_categories.contains(&category.id);
+        let mut subtree_feeds = HashSet::new();
+        self.collect_subtree_feeds(category.id, children, folder_feeds, &mut subtree_feeds);
+        let unread = subtree_feeds
+            .iter()
+            .map(|feed_id| self.unread_counts.get(feed_id).copied().unwrap_or(0))
+            .sum();
+
+        rows.push(FeedRow::Category {
+            category_id: category.id,
+            name: category.name.clone(),
+            depth,
+            unread,
+            collapsed,
+        });
+        if collapsed {
+            return;
+        }
+
+        if let Some(subcategories) = children.get(&Some(category.id)) {
+            for child in subcategories {
+                self.push_category_rows(child, depth + 1, children, folder_feeds, rows);
+            }
+        }
+        for &index in folder_feeds.get(&category.id).into_iter().flatten() {
+            rows.push(FeedRow::Feed {
+                feed_index: index,
+                depth: depth + 1,
+            });
+        }
+    }
+
+    fn collect_subtree_feeds(
+        &self,
+        category_id: i64,
+        children: &HashMap<Option<i64>, Vec<&Category>>,
+        folder_feeds: &HashMap<i64, Vec<usize>>,
+        feed_ids: &mut HashSet<i64>,
+    ) {
+        for &index in folder_feeds.get(&category_id).into_iter().flatten() {
+            feed_ids.insert(self.feeds[index].id);
+        }
+        if let Some(subcategories) = children.get(&Some(category_id)) {
+            for child in subcategories {
+                self.collect_subtree_feeds(child.id, children, folder_feeds, feed_ids);
+            }
+        }
     }
 
     pub fn loaded_feed_id(&self) -> Option<i64> {
-        self.loaded_feed.as_ref().map(|loaded| loaded.feed_id)
+        match self.loaded_feed.as_ref()?.source {
+            ItemSource::Feed(feed_id) => Some(feed_id),
+            ItemSource::SavedSearch(_) | ItemSource::Search => None,
+        }
+    }
+
+    pub fn loaded_search_id(&self) -> Option<i64> {
+        match self.loaded_feed.as_ref()?.source {
+            ItemSource::SavedSearch(search_id) => Some(search_id),
+            ItemSource::Feed(_) | ItemSource::Search => None,
+        }
+    }
+
+    pub fn showing_search_results(&self) -> bool {
+        self.loaded_feed
+            .as_ref()
+            .is_some_and(|loaded| loaded.source == ItemSource::Search)
+    }
+
+    /// Words to highlight in the preview while a search is open.
+    pub fn search_terms(&self) -> Vec<String> {
+        self.search
+            .as_ref()
+            .map(SearchState::terms)
+            .unwrap_or_default()
+    }
+
+    /// The excerpt around an item's hits, while search results are shown.
+    pub fn search_snippet(&self, item_id: &str) -> Option<&str> {
+        if !self.showing_search_results() {
+            return None;
+        }
+        self.search
+            .as_ref()
+            .and_then(|search| search.snippets.get(item_id))
+            .map(String::as_str)
+    }
+
+    /// Scrolls the preview to the next (or previous) match, wrapping around.
+    /// Returns the 1-based match number, or `None` without matches.
+    pub fn jump_to_match(&mut self, forward: bool) -> Option<usize> {
+        let rows = &self.preview_match_rows;
+        let index = if forward {
+            rows.iter()
+                .position(|row| *row > self.preview_scroll)
+                .unwrap_or(0)
+        } else {
+            rows.iter()
+                .rposition(|row| *row < self.preview_scroll)
+                .unwrap_or(rows.len().checked_sub(1)?)
+        };
+        self.preview_scroll = *rows.get(index)?;
+        Some(index + 1)
+    }
+
+    /// The item view label, plus the tag filter if one is set.
+    pub fn view_label(&self) -> String {
+        match &self.tag_filter {
+            Some(tag) => format!("{} #{}", self.item_view.label(), tag),
+            None => self.item_view.label().to_string(),
+        }
+    }
+
+    pub fn matches_tag_filter(&self, item: &Item) -> bool {
+        self.tag_filter
+            .as_deref()
+            .is_none_or(|wanted| item.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted)))
+    }
+
+    /// Updates an item's tags wherever it is loaded, without reloading the
+    /// lists (which would move the cursor).
+    pub fn set_item_tags(&mut self, item_id: &str, tags: &[String]) {
+        let loaded = self
+            .loaded_feed
+            .iter_mut()
+            .flat_map(|loaded| loaded.items.iter_mut());
+        let latest = self.latest_items.iter_mut().map(|recent| &mut recent.item);
+        for item in loaded.chain(latest).filter(|item| item.id == item_id) {
+            item.tags = tags.to_vec();
+        }
     }
 
     pub fn loaded_items(&self) -> &[Item] {
@@ -264,6 +840,125 @@ impl TuiApp {
             .unwrap_or(&[])
     }
 
+    /// Items in the list the user is looking at, with the cursor position.
+    fn visible_items(&self) -> (Vec<&Item>, usize) {
+        match self.active_tab {
+            AppTab::Latest => (
+                self.latest_items
+                    .iter()
+                    .map(|recent| &recent.item)
+                    .collect(),
+                self.latest_index,
+            ),
+            AppTab::Reader => (
+                self.loaded_items().iter().collect(),
+                self.loaded_item_index(),
+            ),
+        }
+    }
+
+    fn unread_ids<'a>(&self, items: impl IntoIterator<Item = &'a Item>) -> Vec<String> {
+        items
+            .into_iter()
+            .filter(|item| !self.is_item_read(&item.id))
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    /// What "mark all read" would touch: the highlighted feed or folder in
+    /// the rail, otherwise the visible item list (which is also what a
+    /// highlighted smart feed covers). `None` if nothing is unread. Rail
+    /// targets are counted by `count_unread` with the current view's filter
+    /// and tag filter, so the prompt only appears when the mark would change
+    /// something.
+    pub fn plan_mark_all_read(
+        &self,
+        count_unread: impl FnOnce(&[i64], ItemListFilter, Option<&str>) -> Result<usize>,
+    ) -> Result<Option<PendingMarkRead>> {
+        let mut view_suffix = match self.item_view {
+            ItemView::All => String::new(),
+            view => format!(" shown in {}", view.label()),
+        };
+        if let Some(tag) = &self.tag_filter {
+            view_suffix.push_str(&format!(" tagged \"{}\"", tag));
+        }
+
+        if self.active_tab == AppTab::Reader && self.active_pane == ActivePane::Feeds {
+            let rail_target = if let Some(category_id) = self.selected_category_id() {
+                let name = self
+                    .categories
+                    .iter()
+                    .find(|c| c.id == category_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_default();
+                Some((self.category_feed_ids(category_id), name))
+            } else if self.selected_saved_search().is_none() {
+                let Some(feed) = self.selected_feed() else {
+                    return Ok(None);
+                };
+                Some((vec![feed.id], feed.display_title().to_string()))
+            } else {
+                None
+            };
+            if let Some((feed_ids, name)) = rail_target {
+                let tag = self.tag_filter.as_deref();
+                if count_unread(&feed_ids, self.item_view.filter(), tag)? == 0 {
+                    return Ok(None);
+                }
+                return Ok(Some(PendingMarkRead {
+                    target: MarkReadTarget::Feeds(feed_ids),
+                    description: format!("everything in \"{}\"{}", name, view_suffix),
+                }));
+            }
+        }
+
+        let (items, _) = self.visible_items();
+        let ids = self.unread_ids(items);
+        if ids.is_empty() {
+            return Ok(None);
+        }
+        let place = match self.active_tab {
+            AppTab::Latest => "Latest".to_string(),
+            AppTab::Reader => match self.loaded_search_id() {
+                Some(search_id) => self
+                    .saved_searches
+                    .iter()
+                    .find(|search| search.id == search_id)
+                    .map(|search| format!("\"{}\"", search.name))
+                    .unwrap_or_else(|| "this search".to_string()),
+                None => self
+                    .loaded_feed_id()
+                    .and_then(|id| self.feeds.iter().find(|f| f.id == id))
+                    .map(|f| format!("\"{}\"", f.display_title()))
+                    .unwrap_or_else(|| "this feed".to_string()),
+            },
+        };
+        Ok(Some(PendingMarkRead {
+            description: format!("{} {} in {}", ids.len(), plural_items(ids.len()), place),
+            target: MarkReadTarget::Items(ids),
+        }))
+    }
+
+    /// What "mark older read" would touch: unread items below the cursor.
+    pub fn plan_mark_older_read(&self) -> Option<PendingMarkRead> {
+        if self.active_tab == AppTab::Reader && self.active_pane == ActivePane::Feeds {
+            return None;
+        }
+        let (items, index) = self.visible_items();
+        let ids = self.unread_ids(items.into_iter().skip(index + 1));
+        if ids.is_empty() {
+            return None;
+        }
+        Some(PendingMarkRead {
+            description: format!(
+                "{} {} older than the selected one",
+                ids.len(),
+                plural_items(ids.len())
+            ),
+            target: MarkReadTarget::Items(ids),
+        })
+    }
+
     pub fn loaded_item_index(&self) -> usize {
         self.loaded_feed
             .as_ref()
@@ -283,6 +978,18 @@ impl TuiApp {
             .map(|recent| &recent.item)
     }
 
+    /// Other feeds that carried a Latest item, when it is shown on that tab.
+    pub fn latest_also_in(&self, item_id: &str) -> &[String] {
+        if self.active_tab != AppTab::Latest {
+            return &[];
+        }
+        self.latest_items
+            .iter()
+            .find(|recent| recent.item.id == item_id)
+            .map(|recent| recent.also_in.as_slice())
+            .unwrap_or(&[])
+    }
+
     pub fn selected_item_for_active_tab(&self) -> Option<&Item> {
         match self.active_tab {
             AppTab::Latest => self.selected_latest_item(),
@@ -297,6 +1004,59 @@ impl TuiApp {
             .unwrap_or(false)
     }
 
+    /// The feed changed the item since it was last read (or it is unread and
+    /// has changed at all).
+    pub fn is_item_updated(&self, item_id: &str) -> bool {
+        if !self.show_updates {
+            return false;
+        }
+        let Some(last) = self.item_revisions.get(item_id).and_then(|r| r.last()) else {
+            return false;
+        };
+        match self.item_states.get(item_id) {
+            Some(state) if state.is_read => state
+                .read_at
+                .is_none_or(|read_at| read_at < last.replaced_at),
+            _ => true,
+        }
+    }
+
+    /// The version an updated item replaced, for the diff view.
+    pub fn previous_revision(&self, item_id: &str) -> Option<&ItemRevision> {
+        if !self.show_updates {
+            return None;
+        }
+        self.item_revisions.get(item_id).and_then(|r| r.last())
+    }
+
+    /// Brings `content_diff` up to date with the selected item, diffing only
+    /// when the item, its previous revision or `show_diff` changed.
+    pub fn update_content_diff(&mut self) {
+        let current = self.selected_item_for_active_tab().and_then(|item| {
+            let previous = self.previous_revision(&item.id)?;
+            Some((item, previous))
+        });
+        let Some((item, previous)) = current.filter(|_| self.show_diff) else {
+            self.content_diff = None;
+            return;
+        };
+        let up_to_date = self.content_diff.as_ref().is_some_and(|diff| {
+            diff.item_id == item.id && diff.replaced_at == previous.replaced_at
+        });
+        if up_to_date {
+            return;
+        }
+        let lines = text_diff(
+            &html_to_text(previous.display_content()),
+            &html_to_text(item.display_content()),
+        );
+        self.content_diff = Some(ContentDiff {
+            item_id: item.id.clone(),
+            replaced_at: previous.replaced_at,
+            lines,
+        });
+    }
+
     pub fn is_item_starred(&self, item_id: &str) -> bool {
         self.item_states
             .get(item_id)
@@ -363,7 +1123,7 @@ impl TuiApp {
     pub fn move_down(&mut self) {
         match self.active_pane {
             ActivePane::Feeds => {
-                if !self.feeds.is_empty() && self.feed_index < self.feeds.len() - 1 {
+                if !self.feed_rows.is_empty() && self.feed_index < self.feed_rows.len() - 1 {
                     self.feed_index += 1;
                     self.feed_list_state.select(Some(self.feed_index));
                 }
@@ -427,7 +1187,7 @@ impl TuiApp {
     pub fn move_bottom(&mut self) {
         match self.active_pane {
             ActivePane::Feeds => {
-                self.feed_index = self.feeds.len().saturating_sub(1);
+                self.feed_index = self.feed_rows.len().saturating_sub(1);
                 self.feed_list_state.select(Some(self.feed_index));
             }
             ActivePane::Items => match self.active_tab {
@@ -456,7 +1216,7 @@ impl TuiApp {
     pub fn next_page(&mut self) {
         match self.active_pane {
             ActivePane::Feeds => {
-                let max_index = self.feeds.len().saturating_sub(1);
+                let max_index = self.feed_rows.len().saturating_sub(1);
                 let new_index = (self.feed_index + PAGE_SIZE).min(max_index);
                 if new_index != self.feed_index {
                     self.feed_index = new_index;
@@ -562,7 +1322,7 @@ mod loaded_feed_tests {
     #[test]
     fn new_with_items_selects_first_row() {
         let loaded = LoadedFeed::new(7, vec![make_item(7, 0), make_item(7, 1)]);
-        assert_eq!(loaded.feed_id, 7);
+        assert_eq!(loaded.source, ItemSource::Feed(7));
         assert_eq!(loaded.item_index, 0);
         assert_eq!(loaded.item_list_state.selected(), Some(0));
     }
@@ -611,3 +1371,93 @@ mod loaded_feed_tests {
         assert_eq!(loaded.item_list_state.selected(), Some(1));
     }
 }
+
+#[cfg(test)]
+mod link_hints_tests {
+    use super::*;
+
+    #[test]
+    fn link_hints_select_by_number_and_movement() {
+        assert!(LinkHints::new(Vec::new()).is_none());
+        let links = (1..=12).map(|n| format!("https://example.com/{n}"));
+        let mut hints = LinkHints::new(links.collect()).unwrap();
+
+        hints.push_digit('1');
+        assert_eq!(hints.selected_link(), "https://example.com/1");
+        hints.push_digit('2');
+        assert_eq!(hints.selected_link(), "https://example.com/12");
+        // 123 is past the end, so 3 starts over
+        hints.push_digit('3');
+        assert_eq!(hints.selected_link(), "https://example.com/3");
+        hints.push_digit('0');
+        assert_eq!(hints.selected, 2);
+        assert!(hints.typed.is_empty());
+
+        hints.move_selection(false);
+        hints.move_selection(false);
+        hints.move_selection(false);
+        assert_eq!(hints.selected, 0);
+        hints.push_digit('9');
+        hints.move_selection(true);
+        assert_eq!(hints.selected, 9);
+        assert!(hints.typed.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod content_diff_tests {
+    use super::*;
+
+    fn revision(item_id: &str, content: &str) -> ItemRevision {
+        ItemRevision {
+            item_id: item_id.to_string(),
+            title: None,
+            link: None,
+            content: Some(content.to_string()),
+            summary: None,
+            updated_at: None,
+            replaced_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn content_diff_is_computed_once_per_item_and_revision() {
+        let mut item = Item::new(7, "https://example.com/7.xml", "entry-0");
+        item.content = Some("<p>Intro. New ending.</p>".into());
+        let mut app = TuiApp::new();
+        app.active_tab = AppTab::Reader;
+        app.item_revisions
+            .insert(item.id.clone(), vec![revision(&item.id, "<p>Intro.</p>")]);
+        app.loaded_feed = Some(LoadedFeed::new(7, vec![item.clone()]));
+
+        app.update_content_diff();
+        assert!(app.content_diff.is_none());
+
+        app.show_diff = true;
+        app.update_content_diff();
+        let diff = app.content_diff.clone().unwrap();
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Same("Intro.".into()),
+                DiffLine::Added("New ending.".into()),
+            ]
+        );
+
+        // Redraws reuse the cached lines rather than diffing again
+        app.content_diff.as_mut().unwrap().lines.clear();
+        app.update_content_diff();
+        assert!(app.content_diff.as_ref().unwrap().lines.is_empty());
+
+        // A newer revision is diffed afresh
+        let mut newer = revision(&item.id, "<p>Intro. Old ending.</p>");
+        newer.replaced_at = diff.replaced_at + chrono::Duration::seconds(1);
+        app.item_revisions.get_mut(&item.id).unwrap().push(newer);
+        app.update_content_diff();
+        assert_eq!(app.content_diff.as_ref().unwrap().lines.len(), 3);
+
+        app.show_diff = false;
+        app.update_content_diff();
+        assert!(app.content_diff.is_none());
+    }
+}
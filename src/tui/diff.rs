@@ -0,0 +1,120 @@
+//! Sentence diff between an item's previous and current text.
+
+/// One sentence (or line) of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Above this many sentences per side the LCS table gets too large; the
+/// diff degrades to "everything removed, everything added".
+const MAX_DIFF_UNITS: usize = 2000;
+
+/// Diff of `old` against `new` by sentence, based on the longest common
+/// subsequence. Plain text from HTML is one long line, so sentences are the
+/// smallest unit that still reads well. Blank lines are ignored.
+pub fn text_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = sentences(old);
+    let new: Vec<&str> = sentences(new);
+
+    if old.len() > MAX_DIFF_UNITS || new.len() > MAX_DIFF_UNITS {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain(new.iter().map(|line| DiffLine::Added(line.to_string())))
+            .collect();
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    diff
+}
+
+/// Splits on newlines and after `.`, `!` or `?` followed by a space.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut units = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let at_boundary = matches!(c, '.' | '!' | '?')
+                && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+            if at_boundary {
+                units.push(&line[start..=i]);
+                start = i + 1;
+            }
+        }
+        units.push(&line[start..]);
+    }
+    units
+        .into_iter()
+        .map(str::trim)
+        .filter(|unit| !unit.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_diff_marks_changed_sentences() {
+        let diff = text_diff(
+            "intro. teh typo! outro\n",
+            "intro.\n\nthe typo! outro\nnew ending",
+        );
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("intro.".into()),
+                DiffLine::Removed("teh typo!".into()),
+                DiffLine::Added("the typo!".into()),
+                DiffLine::Same("outro".into()),
+                DiffLine::Added("new ending".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_diff_identical_text_has_no_changes() {
+        let diff = text_diff("a. b", "a.\nb");
+        assert!(diff.iter().all(|line| matches!(line, DiffLine::Same(_))));
+    }
+}
@@ -2,6 +2,7 @@ use crossterm::event::{self, Event, KeyEvent};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+use crate::app::subscribe::Subscription;
 use crate::app::Result;
 
 #[derive(Debug)]
@@ -10,6 +11,7 @@ pub enum AppEvent {
     Tick,
     RefreshProgress(usize, usize),
     RefreshComplete(i64, Vec<(i64, Result<crate::store::FeedRefreshResult>)>),
+    Subscribed(Result<Subscription>),
 }
 
 pub struct EventHandler {
@@ -82,10 +84,19 @@ pub enum Action {
     ViewLatest,
     ViewReader,
     OpenInBrowser,
+    FollowLink,
     Refresh,
     ToggleMaximize,
     ToggleFeedPanel,
     DeleteFeed,
+    MarkAllRead,
+    MarkOlderRead,
+    ToggleDiff,
+    EditTags,
+    FilterTag,
+    Search,
+    NextMatch,
+    PrevMatch,
     WindowChord,
     None,
 }
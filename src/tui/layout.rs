@@ -2,13 +2,16 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
 use crate::config::ColorConfig;
 use crate::domain::Item;
-use crate::tui::app::{ActivePane, AppTab, FeedPanelState, TuiApp};
+use crate::html::render_html;
+use crate::store::query::snippet_parts;
+use crate::tui::app::{ActivePane, AppTab, FeedPanelState, FeedRow, LinkHints, TuiApp};
+use crate::tui::diff::DiffLine;
 
 pub fn render(frame: &mut Frame, app: &mut TuiApp, colors: &ColorConfig) {
     let chunks = Layout::default()
@@ -22,9 +25,53 @@ pub fn render(frame: &mut Frame, app: &mut TuiApp, colors: &ColorConfig) {
 
     render_tab_strip(frame, app, chunks[0], colors);
     render_body(frame, app, chunks[1], colors);
+    if let Some(hints) = &app.link_hints {
+        render_link_hints(frame, hints, chunks[1], colors);
+    }
     render_status_bar(frame, app, chunks[2], colors);
 }
 
+/// The link picker, drawn over the middle of the body.
+fn render_link_hints(frame: &mut Frame, hints: &LinkHints, area: Rect, colors: &ColorConfig) {
+    let digits = hints.links.len().to_string().len();
+    let items: Vec<ListItem> = hints
+        .links
+        .iter()
+        .enumerate()
+        .map(|(index, link)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("[{:>width$}] ", index + 1, width = digits),
+                    Style::default().fg(colors.metadata_date),
+                ),
+                Span::raw(link.clone()),
+            ]))
+        })
+        .collect();
+
+    let width = area.width.saturating_sub(4).min(100);
+    let height = (hints.links.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Links ")
+                .borders(Borders::ALL)
+                .border_style(border_style(true, colors)),
+        )
+        .highlight_style(selection_style(true, colors));
+    let mut state = ListState::default();
+    state.select(Some(hints.selected));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut state);
+}
+
 fn render_body(frame: &mut Frame, app: &mut TuiApp, area: Rect, colors: &ColorConfig) {
     if app.maximized {
         let selected = app.selected_item_for_active_tab().cloned();
@@ -163,22 +210,66 @@ fn render_feeds_pane(frame: &mut Frame, app: &mut TuiApp, area: Rect, colors: &C
     };
 
     let items: Vec<ListItem> = app
-        .feeds
+        .feed_rows
         .iter()
-        .map(|feed| {
-            let unread = app
-                .loaded_items()
-                .iter()
-                .filter(|item| item.feed_id == feed.id && !app.is_item_read(&item.id))
-                .count();
-
-            let content = if unread > 0 {
-                format!("{} ({})", feed.display_title(), unread)
-            } else {
-                feed.display_title().to_string()
-            };
+        .map(|row| match row {
+            FeedRow::SavedSearch { search_index } => {
+                let Some(search) = app.saved_searches.get(*search_index) else {
+                    return ListItem::new("");
+                };
+                let unread = app
+                    .saved_search_unread
+                    .get(&search.id)
+                    .copied()
+                    .unwrap_or(0);
+                let content = if unread > 0 {
+                    format!("⌕ {} ({})", search.name, unread)
+                } else {
+                    format!("⌕ {}", search.name)
+                };
+                ListItem::new(content).style(Style::default().add_modifier(Modifier::ITALIC))
+            }
+            FeedRow::Category {
+                name,
+                depth,
+                unread,
+                collapsed,
+                ..
+            } => {
+                let marker = if *collapsed { "▸" } else { "▾" };
+                let content = if *unread > 0 {
+                    format!("{}{} {} ({})", "  ".repeat(*depth), marker, name, unread)
+                } else {
+                    format!("{}{} {}", "  ".repeat(*depth), marker, name)
+                };
+                ListItem::new(content).style(Style::default().add_modifier(Modifier::BOLD))
+            }
+            FeedRow::Feed { feed_index, depth } => {
+                let Some(feed) = app.feeds.get(*feed_index) else {
+                    return ListItem::new("");
+                };
+                let unread = app.unread_counts.get(&feed.id).copied().unwrap_or(0);
+                let broken = app.is_feed_broken(feed);
+                let marker = if broken { "✗ " } else { "" };
+                let content = if unread > 0 {
+                    format!(
+                        "{}{}{} ({})",
+                        "  ".repeat(*depth),
+                        marker,
+                        feed.display_title(),
+                        unread
+                    )
+                } else {
+                    format!("{}{}{}", "  ".repeat(*depth), marker, feed.display_title())
+                };
 
-            ListItem::new(content)
+                if broken {
+                    ListItem::new(content)
+                        .style(Style::default().fg(ratatui::style::Color::LightRed))
+                } else {
+                    ListItem::new(content)
+                }
+            }
         })
         .collect();
 
@@ -186,7 +277,7 @@ fn render_feeds_pane(frame: &mut Frame, app: &mut TuiApp, area: Rect, colors: &C
         " Feeds ({}) [{}/{}] ",
         app.feeds.len(),
         app.feed_index + 1,
-        app.feeds.len().max(1)
+        app.feed_rows.len().max(1)
     );
 
     let list = List::new(items)
@@ -211,11 +302,11 @@ fn render_items_pane(frame: &mut Frame, app: &mut TuiApp, area: Rect, colors: &C
     let items: Vec<ListItem<'static>> = app
         .loaded_items()
         .iter()
-        .map(|item| render_item_row(app, item, false, colors))
+        .map(|item| render_item_row(app, item, false, 0, colors))
         .collect();
     let title = format!(
         " Items: {} ({}) [{}/{}] ",
-        app.item_view.label(),
+        app.view_label(),
         app.loaded_items().len(),
         app.loaded_item_index() + 1,
         app.loaded_items().len().max(1)
@@ -256,12 +347,20 @@ fn render_latest_items_pane(frame: &mut Frame, app: &mut TuiApp, area: Rect, col
     let items: Vec<ListItem> = app
         .latest_items
         .iter()
-        .map(|recent| render_item_row(app, &recent.item, recent.is_latest_refresh_item, colors))
+        .map(|recent| {
+            render_item_row(
+                app,
+                &recent.item,
+                recent.is_latest_refresh_item,
+                recent.also_in.len(),
+                colors,
+            )
+        })
         .collect();
 
     let title = format!(
         " Latest: {} ({}) [{}/{}] ",
-        app.item_view.label(),
+        app.view_label(),
         app.latest_items.len(),
         app.latest_index + 1,
         app.latest_items.len().max(1)
@@ -307,6 +406,7 @@ fn render_item_row(
     app: &TuiApp,
     item: &Item,
     is_latest_refresh_item: bool,
+    other_feeds: usize,
     colors: &ColorConfig,
 ) -> ListItem<'static> {
     let is_read = app.is_item_read(&item.id);
@@ -317,6 +417,8 @@ fn render_item_row(
 
     let marker = if is_latest_refresh_item {
         "NEW"
+    } else if app.is_item_updated(&item.id) {
+        "U  "
     } else if is_archived {
         "x  "
     } else if is_saved {
@@ -335,7 +437,11 @@ fn render_item_row(
         .published_at
         .map(|d| d.format("%m/%d").to_string())
         .unwrap_or_else(|| "     ".to_string());
-    let content = format!("{} {} {}", marker, date, item.display_title());
+    let mut content = format!("{} {} {}", marker, date, item.display_title());
+    if other_feeds > 0 {
+        let noun = if other_feeds == 1 { "feed" } else { "feeds" };
+        content.push_str(&format!(" (+{} {})", other_feeds, noun));
+    }
 
     let style = if is_latest_refresh_item {
         Style::default()
@@ -355,20 +461,31 @@ fn render_item_row(
 fn render_content_pane(
     frame: &mut Frame,
     item: Option<&Item>,
-    app: &TuiApp,
+    app: &mut TuiApp,
     area: Rect,
     colors: &ColorConfig,
     fallback_title: &str,
 ) {
     let is_active = app.active_pane == ActivePane::Preview;
+    let terms = app.search_terms();
+    // Indexes into `lines` that contain a search match
+    let mut match_lines = Vec::new();
     let (title, content) = if let Some(item) = item {
         let title_text = item.display_title().to_string();
         let mut lines = Vec::new();
 
-        lines.push(Line::from(Span::styled(
-            item.display_title(),
-            Style::default().add_modifier(Modifier::BOLD),
-        )));
+        let (title_line, matched) = highlight_line(
+            Line::from(Span::styled(
+                item.display_title().to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            &terms,
+            colors,
+        );
+        if matched {
+            match_lines.push(lines.len());
+        }
+        lines.push(title_line);
         lines.push(Line::from(""));
 
         if let Some(author) = &item.author {
@@ -389,15 +506,124 @@ fn render_content_pane(
                 Style::default().fg(colors.metadata_link),
             )));
         }
+        let previous = app.previous_revision(&item.id);
+        if previous.is_some() {
+            let date = item
+                .updated_at
+                .map(|d| d.format("%Y-%m-%d %H:%M").to_string());
+            let hint = if app.show_diff {
+                "D: article"
+            } else {
+                "D: changes"
+            };
+            lines.push(Line::from(Span::styled(
+                format!("Updated: {} ({})", date.as_deref().unwrap_or("yes"), hint),
+                Style::default().fg(colors.metadata_date),
+            )));
+        }
+        let also_in = app.latest_also_in(&item.id);
+        if !also_in.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("Also in: {}", also_in.join(", ")),
+                Style::default().fg(colors.metadata_date),
+            )));
+        }
+        if !item.categories.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("Categories: {}", item.categories.join(", ")),
+                Style::default().fg(colors.metadata_date),
+            )));
+        }
+        if !item.tags.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("Tags: {}", item.tags.join(", ")),
+                Style::default().fg(colors.metadata_date),
+            )));
+        }
+        if let Some(snippet) = app.search_snippet(&item.id) {
+            let style = Style::default().fg(colors.metadata_date);
+            let mut spans = vec![Span::styled("Match: ", style)];
+            spans.extend(snippet_parts(snippet).into_iter().map(|(text, hit)| {
+                Span::styled(
+                    text.to_string(),
+                    if hit { hit_style(style, colors) } else { style },
+                )
+            }));
+            lines.push(Line::from(spans));
+        }
+        for enclosure in &item.enclosures {
+            let details: Vec<String> = [
+                enclosure.mime_type.clone(),
+                enclosure.display_length(),
+                enclosure.display_duration(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let mut text = format!("Media: {}", enclosure.url);
+            if !details.is_empty() {
+                text.push_str(&format!(" ({})", details.join(", ")));
+            }
+            lines.push(Line::from(Span::styled(
+                text,
+                Style::default().fg(colors.metadata_link),
+            )));
+        }
         lines.push(Line::from(""));
         lines.push(Line::from(
             "-".repeat(area.width.saturating_sub(2) as usize),
         ));
         lines.push(Line::from(""));
 
-        let content_text = strip_html(item.display_content());
-        for line in content_text.lines() {
-            lines.push(Line::from(line.to_string()));
+        match previous.filter(|_| app.show_diff) {
+            Some(previous) => {
+                let removed = Style::default().fg(colors.diff_removed);
+                let added = Style::default().fg(colors.diff_added);
+                if previous.title.as_deref() != item.title.as_deref() {
+                    if let Some(title) = &previous.title {
+                        lines.push(Line::from(Span::styled(
+                            format!("- Title: {}", title),
+                            removed,
+                        )));
+                    }
+                    lines.push(Line::from(Span::styled(
+                        format!("+ Title: {}", item.display_title()),
+                        added,
+                    )));
+                    lines.push(Line::from(""));
+                }
+                let diff = app
+                    .content_diff
+                    .iter()
+                    .filter(|diff| diff.item_id == item.id)
+                    .flat_map(|diff| &diff.lines);
+                for line in diff {
+                    lines.push(match line {
+                        DiffLine::Same(text) => Line::from(format!("  {}", text)),
+                        DiffLine::Removed(text) => {
+                            Line::from(Span::styled(format!("- {}", text), removed))
+                        }
+                        DiffLine::Added(text) => {
+                            Line::from(Span::styled(format!("+ {}", text), added))
+                        }
+                    });
+                }
+            }
+            None => {
+                let rendered = render_html(
+                    item.display_content(),
+                    item.link.as_deref(),
+                    area.width.saturating_sub(2) as usize,
+                    colors,
+                );
+                for line in rendered.lines {
+                    let (line, matched) = highlight_line(line, &terms, colors);
+                    if matched {
+                        match_lines.push(lines.len());
+                    }
+                    lines.push(line);
+                }
+            }
         }
 
         (format!(" {} ", title_text), Text::from(lines))
@@ -405,6 +631,22 @@ fn render_content_pane(
         (fallback_title.to_string(), Text::from("No item selected"))
     };
 
+    // Rows after wrapping, which is what the scroll offset counts
+    let inner_width = area.width.saturating_sub(2);
+    let mut row = 0usize;
+    let mut match_rows = Vec::new();
+    for (index, line) in content.lines.iter().enumerate() {
+        if match_lines.first() == Some(&index) {
+            match_lines.remove(0);
+            match_rows.push(row.min(u16::MAX as usize) as u16);
+        }
+        row += Paragraph::new(line.clone())
+            .wrap(Wrap { trim: false })
+            .line_count(inner_width)
+            .max(1);
+    }
+    app.preview_match_rows = match_rows;
+
     let paragraph = Paragraph::new(content)
         .block(
             Block::default()
@@ -448,14 +690,24 @@ fn render_status_bar(frame: &mut Frame, app: &TuiApp, area: Rect, colors: &Color
             .use_unicode(true);
         frame.render_widget(gauge, chunks[1]);
     } else {
-        let status = if let Some((_, ref title)) = app.pending_delete {
+        let status = if let Some(ref prompt) = app.prompt {
+            format!("{}: {}█", prompt.label(), prompt.input)
+        } else if let Some(ref hints) = app.link_hints {
+            format!(
+                "Link {}/{}  o:Open  y:Copy  a:Subscribe  j/k/0-9:Choose  Esc:Cancel",
+                hints.selected + 1,
+                hints.links.len()
+            )
+        } else if let Some((_, ref title)) = app.pending_delete {
             format!("Delete \"{}\"? (y/n)", title)
+        } else if let Some(ref pending) = app.pending_mark_read {
+            format!("Mark {} as read? (y/n)", pending.description)
         } else if let Some(ref msg) = app.status_message {
             msg.clone()
         } else if app.maximized {
             "j/k:Scroll  g/G/%:Top/Bottom  n/p:Page  m:Exit maximize  [/]:Tabs  q:Quit".to_string()
         } else {
-            "[/]:Tabs  \\:Feeds  j/k/g/G/%:Nav  a/u/f/l/v/X:Views  r/s/L/S/x/o:Actions  R:Refresh  q:Quit"
+            "[/]:Tabs  \\:Feeds  j/k/g/G/%:Nav  a/u/f/l/v/X:Views  r/s/L/S/x/o/A/O:Actions  D:Diff  F:Links  t/T:Tags  /:Search  R:Refresh  q:Quit"
                 .to_string()
         };
 
@@ -487,29 +739,81 @@ fn selection_style(is_active: bool, colors: &ColorConfig) -> Style {
     }
 }
 
-fn strip_html(html: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
-    let mut last_was_space = false;
-
-    for c in html.chars() {
-        match c {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => {
-                if c.is_whitespace() {
-                    if !last_was_space {
-                        result.push(' ');
-                        last_was_space = true;
-                    }
-                } else {
-                    result.push(c);
-                    last_was_space = false;
-                }
+/// Restyles case-insensitive occurrences of `terms` in `line`, across span
+/// boundaries. Also returns whether anything matched.
+fn highlight_line(
+    line: Line<'static>,
+    terms: &[String],
+    colors: &ColorConfig,
+) -> (Line<'static>, bool) {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let mut ranges: Vec<(usize, usize)> = terms
+        .iter()
+        .flat_map(|term| find_case_insensitive(&text, term))
+        .collect();
+    if ranges.is_empty() {
+        return (line, false);
+    }
+    ranges.sort_unstable();
+
+    let mut spans = Vec::new();
+    let mut span_start = 0;
+    for span in &line.spans {
+        let span_end = span_start + span.content.len();
+        let mut pos = span_start;
+        for &(start, end) in &ranges {
+            let (start, end) = (start.max(pos), end.min(span_end));
+            if start >= end {
+                continue;
+            }
+            if start > pos {
+                spans.push(Span::styled(text[pos..start].to_string(), span.style));
             }
-            _ => {}
+            spans.push(Span::styled(
+                text[start..end].to_string(),
+                hit_style(span.style, colors),
+            ));
+            pos = end;
         }
+        if pos < span_end {
+            spans.push(Span::styled(text[pos..span_end].to_string(), span.style));
+        }
+        span_start = span_end;
     }
+    (Line::from(spans).style(line.style), true)
+}
 
-    result.trim().to_string()
+fn hit_style(style: Style, colors: &ColorConfig) -> Style {
+    style.fg(colors.search_match_fg).bg(colors.search_match_bg)
+}
+
+/// Byte ranges of `needle` (lowercase) in `haystack`, ignoring case.
+fn find_case_insensitive(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    if needle.is_empty() {
+        return ranges;
+    }
+    for (start, _) in haystack.char_indices() {
+        let mut needle_chars = needle.chars();
+        let mut end = start;
+        let mut matched = true;
+        for c in haystack[start..].chars() {
+            let Some(want) = needle_chars.next() else {
+                break;
+            };
+            if !c.to_lowercase().eq(want.to_lowercase()) {
+                matched = false;
+                break;
+            }
+            end += c.len_utf8();
+        }
+        if matched && needle_chars.next().is_none() {
+            ranges.push((start, end));
+        }
+    }
+    ranges
 }
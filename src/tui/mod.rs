@@ -1,28 +1,41 @@
 pub mod app;
+pub mod clipboard;
+pub mod diff;
 pub mod event;
 pub mod layout;
 
+use std::collections::HashMap;
 use std::io::{self, Stdout};
 use std::sync::Arc;
 use std::time::Duration;
 
 use crossterm::{
-    event::KeyCode,
+    event::{KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
-use crate::app::{AppContext, Result};
+use crate::app::subscribe::{subscribe, Subscription};
+use crate::app::{AppContext, Result, RivuletError};
 use crate::config::Config;
+use crate::domain::Item;
+use crate::html::article_links;
 use crate::scraper::{ChromeScraper, Scraper};
-use crate::store::{RefreshSource, Store};
+use crate::store::{FeedRefreshResult, RefreshSource, Store};
 
-use self::app::{ActivePane, AppTab, FeedPanelState, ItemView, LoadedFeed, PendingChord, TuiApp};
+use self::app::{
+    incremental_query, parse_tags, ActivePane, AppTab, FeedPanelState, ItemSource, ItemView,
+    LinkHints, LoadedFeed, MarkReadTarget, PendingChord, PendingMarkRead, PromptKind, SearchState,
+    TextPrompt, TuiApp,
+};
 use self::event::{Action, AppEvent, EventHandler};
 
 type Tui = Terminal<CrosstermBackend<Stdout>>;
 
+/// Most results the `/` search shows.
+const SEARCH_RESULT_LIMIT: usize = 200;
+
 pub async fn run(ctx: Arc<AppContext>, config: Arc<Config>) -> Result<()> {
     let mut terminal = setup_terminal()?;
     let event_handler = EventHandler::new(Duration::from_millis(100));
@@ -60,6 +73,7 @@ where
 {
     let mut tui_app = TuiApp::new();
     tui_app.recent_days = config.ui.latest.days;
+    tui_app.show_updates = config.ui.show_updates;
     tui_app.recent_limit = config.ui.latest.limit;
 
     // Load initial data
@@ -68,6 +82,7 @@ where
     load_latest_items(&mut tui_app, &ctx)?;
 
     loop {
+        tui_app.update_content_diff();
         terminal.draw(|frame| layout::render(frame, &mut tui_app, &config.colors))?;
 
         let event = match event_handler.next().await {
@@ -94,6 +109,30 @@ where
                     continue;
                 }
 
+                if let Some(pending) = tui_app.pending_mark_read.take() {
+                    match key.code {
+                        KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            let marked = apply_mark_read(&mut tui_app, &ctx, &pending)?;
+                            tui_app.set_status(format!("Marked {} as read", marked));
+                        }
+                        _ => {
+                            tui_app.set_status("Mark read cancelled".to_string());
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(prompt) = tui_app.prompt.take() {
+                    handle_prompt_key(&mut tui_app, &ctx, prompt, &key)?;
+                    continue;
+                }
+
+                if let Some(hints) = tui_app.link_hints.take() {
+                    let tx = event_handler.get_tx();
+                    handle_link_hint_key(&mut tui_app, &ctx, &tx, hints, &key);
+                    continue;
+                }
+
                 // Handle pending multi-key chord (e.g. Ctrl+W <h|l>)
                 if let Some(chord) = tui_app.pending_chord.take() {
                     match chord {
@@ -102,7 +141,12 @@ where
                     continue;
                 }
 
-                let action = config.keybindings.get_action(&key);
+                let mut action = config.keybindings.get_action(&key);
+                if tui_app.showing_search_results() {
+                    if let Some(search_action) = config.keybindings.get_search_action(&key) {
+                        action = search_action;
+                    }
+                }
                 let feed_index_before = tui_app.feed_index;
                 match action {
                     Action::Quit => {
@@ -143,14 +187,16 @@ where
                     }
                     Action::Select => match (tui_app.active_tab, tui_app.active_pane) {
                         (AppTab::Reader, ActivePane::Feeds) => {
-                            if load_items_for_highlighted_feed(&mut tui_app, &ctx)? {
+                            if let Some(category_id) = tui_app.selected_category_id() {
+                                tui_app.toggle_category(category_id);
+                            } else if load_items_for_highlighted_feed(&mut tui_app, &ctx)? {
                                 tui_app.active_pane = ActivePane::Items;
                             }
                         }
-                        (_, ActivePane::Items) => {
-                            if tui_app.selected_item_for_active_tab().is_some() {
-                                tui_app.active_pane = ActivePane::Preview;
-                            }
+                        (_, ActivePane::Items)
+                            if tui_app.selected_item_for_active_tab().is_some() =>
+                        {
+                            tui_app.active_pane = ActivePane::Preview;
                         }
                         _ => {}
                     },
@@ -159,9 +205,7 @@ where
                             let item_id = item.id.clone();
                             let is_read = tui_app.is_item_read(&item_id);
                             ctx.store.set_read(&item_id, !is_read)?;
-                            update_item_state(&mut tui_app, item_id, |state| {
-                                state.is_read = !is_read;
-                            });
+                            reload_after_read_change(&mut tui_app, &ctx)?;
                         }
                     }
                     Action::ToggleStar => {
@@ -174,6 +218,41 @@ where
                             });
                         }
                     }
+                    Action::ToggleDiff => {
+                        let has_revision = tui_app
+                            .selected_item_for_active_tab()
+                            .is_some_and(|item| tui_app.previous_revision(&item.id).is_some());
+                        if has_revision {
+                            tui_app.show_diff = !tui_app.show_diff;
+                            tui_app.preview_scroll = 0;
+                        } else {
+                            tui_app.set_status("No earlier version of this item".to_string());
+                        }
+                    }
+                    Action::EditTags => {
+                        if let Some(item) = tui_app.selected_item_for_active_tab() {
+                            let kind = PromptKind::Tags {
+                                item_id: item.id.clone(),
+                            };
+                            let input = item.tags.join(", ");
+                            tui_app.prompt = Some(TextPrompt::new(kind, input));
+                        }
+                    }
+                    Action::FilterTag => {
+                        let input = tui_app.tag_filter.clone().unwrap_or_default();
+                        tui_app.prompt = Some(TextPrompt::new(PromptKind::TagFilter, input));
+                    }
+                    Action::Search => open_search(&mut tui_app),
+                    Action::NextMatch | Action::PrevMatch => {
+                        match tui_app.jump_to_match(action == Action::NextMatch) {
+                            Some(number) => tui_app.set_status(format!(
+                                "Match {}/{}",
+                                number,
+                                tui_app.preview_match_rows.len()
+                            )),
+                            None => tui_app.set_status("No matches in this item".to_string()),
+                        }
+                    }
                     Action::ToggleQueued => {
                         if let Some(item) = tui_app.selected_item_for_active_tab() {
                             let item_id = item.id.clone();
@@ -199,6 +278,7 @@ where
                             let item_id = item.id.clone();
                             let is_archived = tui_app.is_item_archived(&item_id);
                             ctx.store.set_archived(&item_id, !is_archived)?;
+                            reload_unread_counts(&mut tui_app, &ctx)?;
                             update_item_state(&mut tui_app, item_id, |state| {
                                 state.is_archived = !is_archived;
                             });
@@ -234,13 +314,24 @@ where
                                     // Mark as read when opened
                                     let item_id = item.id.clone();
                                     ctx.store.set_read(&item_id, true)?;
-                                    update_item_state(&mut tui_app, item_id, |state| {
-                                        state.is_read = true;
-                                    });
+                                    reload_after_read_change(&mut tui_app, &ctx)?;
                                 }
                             }
                         }
                     }
+                    Action::FollowLink => {
+                        let links = tui_app
+                            .selected_item_for_active_tab()
+                            .map(|item| article_links(item.display_content(), item.link.as_deref()))
+                            .unwrap_or_default();
+                        match LinkHints::new(links) {
+                            Some(hints) => {
+                                tui_app.link_hints = Some(hints);
+                                tui_app.clear_status();
+                            }
+                            None => tui_app.set_status("No links in this item".to_string()),
+                        }
+                    }
                     Action::Refresh => {
                         if !tui_app.is_refreshing {
                             tui_app.is_refreshing = true;
@@ -261,7 +352,10 @@ where
 
                             tokio::spawn(async move {
                                 let feeds = match ctx_clone.store.get_all_feeds() {
-                                    Ok(f) => f,
+                                    Ok(f) => f
+                                        .into_iter()
+                                        .filter(|feed| feed.is_active)
+                                        .collect::<Vec<_>>(),
                                     Err(e) => {
                                         tracing::error!("Failed to get feeds: {}", e);
                                         return;
@@ -326,6 +420,20 @@ where
                             }
                         }
                     }
+                    Action::MarkAllRead => {
+                        match tui_app.plan_mark_all_read(|feed_ids, filter, tag| {
+                            ctx.store.count_unread_in_feeds(feed_ids, filter, tag)
+                        })? {
+                            Some(pending) => tui_app.pending_mark_read = Some(pending),
+                            None => tui_app.set_status("Nothing unread here".to_string()),
+                        }
+                    }
+                    Action::MarkOlderRead => match tui_app.plan_mark_older_read() {
+                        Some(pending) => tui_app.pending_mark_read = Some(pending),
+                        None => {
+                            tui_app.set_status("Nothing unread below the selection".to_string())
+                        }
+                    },
                     Action::WindowChord => {
                         tui_app.pending_chord = Some(PendingChord::Window);
                         tui_app.set_status(
@@ -349,55 +457,39 @@ where
             }
             AppEvent::Tick => {
                 // Clear status message after some time could be implemented here
+                let now = std::time::Instant::now();
+                if tui_app
+                    .search
+                    .as_ref()
+                    .is_some_and(|search| search.is_due(now))
+                {
+                    run_search(&mut tui_app, &ctx)?;
+                }
             }
             AppEvent::RefreshProgress(current, total) => {
                 tui_app.refresh_progress = (current, total);
             }
             AppEvent::RefreshComplete(run_id, results) => {
-                let mut total_new = 0;
-                let mut errors = 0;
-                let mut updated_feed_ids = Vec::new();
-                for (feed_id, result) in results {
-                    if let Ok(refresh) = result {
-                        total_new += refresh.new_count;
-                        ctx.store.record_refresh_run_items(
-                            run_id,
-                            feed_id,
-                            &refresh.inserted_item_ids,
-                        )?;
-                        if refresh.new_count > 0 {
-                            updated_feed_ids.push(feed_id);
-                        }
-                    } else {
-                        errors += 1;
-                    }
-                }
-                ctx.store.complete_refresh_run(run_id, total_new, errors)?;
-
-                // Queue items for background scraping (non-blocking)
-                if ctx.scraper_handle.is_some() && !updated_feed_ids.is_empty() {
-                    let mut items_to_scrape = Vec::new();
-                    for feed_id in updated_feed_ids {
-                        if let Ok(items) = ctx.store.get_items_by_feed(feed_id) {
-                            items_to_scrape
-                                .extend(items.into_iter().filter(ChromeScraper::needs_scraping));
-                        }
-                    }
-                    if !items_to_scrape.is_empty() {
-                        let ctx_clone = ctx.clone();
-                        tokio::spawn(async move {
-                            ctx_clone.queue_for_scraping(items_to_scrape).await;
-                        });
-                    }
-                }
-
-                load_feeds(&mut tui_app, &ctx)?;
-                load_reader_items(&mut tui_app, &ctx)?;
-                load_latest_items(&mut tui_app, &ctx)?;
-
+                let total_new = finish_refresh_run(&mut tui_app, &ctx, run_id, results)?;
                 tui_app.is_refreshing = false;
                 tui_app.set_status(format!("Refreshed: {} new items", total_new));
             }
+            AppEvent::Subscribed(result) => match result {
+                Ok(Subscription::Added { url, new_count, .. }) => {
+                    reload_lists(&mut tui_app, &ctx)?;
+                    tui_app.set_status(format!("Subscribed to {}: {} items", url, new_count));
+                }
+                Ok(Subscription::Existing { url, reactivated }) => {
+                    if reactivated {
+                        load_feeds(&mut tui_app, &ctx)?;
+                        tui_app.set_status(format!("Reactivated feed: {}", url));
+                    } else {
+                        tui_app.set_status(format!("Already subscribed to {}", url));
+                    }
+                }
+                Ok(Subscription::NoneChosen) => tui_app.clear_status(),
+                Err(e) => tui_app.set_status(format!("Subscribe failed: {}", e)),
+            },
         }
 
         if tui_app.should_quit {
@@ -408,39 +500,127 @@ where
     Ok(())
 }
 
+/// Records a refresh run's results, queues the feeds that got new items for
+/// scraping and reloads the lists. Returns how many items were new.
+fn finish_refresh_run(
+    tui_app: &mut TuiApp,
+    ctx: &Arc<AppContext>,
+    run_id: i64,
+    results: Vec<(i64, Result<FeedRefreshResult>)>,
+) -> Result<usize> {
+    let mut total_new = 0;
+    let mut errors = 0;
+    let mut updated_feed_ids = Vec::new();
+    for (feed_id, result) in results {
+        if let Ok(refresh) = result {
+            total_new += refresh.new_count;
+            ctx.store
+                .record_refresh_run_items(run_id, feed_id, &refresh.inserted_item_ids)?;
+            if refresh.new_count > 0 {
+                updated_feed_ids.push(feed_id);
+            }
+        } else {
+            errors += 1;
+        }
+    }
+    ctx.store.complete_refresh_run(run_id, total_new, errors)?;
+
+    // Queue items for background scraping (non-blocking)
+    if ctx.scraper_handle.is_some() && !updated_feed_ids.is_empty() {
+        let mut items_to_scrape = Vec::new();
+        for feed_id in updated_feed_ids {
+            if let Ok(items) = ctx.store.get_items_by_feed(feed_id) {
+                items_to_scrape.extend(items.into_iter().filter(ChromeScraper::needs_scraping));
+            }
+        }
+        if !items_to_scrape.is_empty() {
+            let ctx_clone = ctx.clone();
+            tokio::spawn(async move {
+                ctx_clone.queue_for_scraping(items_to_scrape).await;
+            });
+        }
+    }
+
+    reload_lists(tui_app, ctx)?;
+    Ok(total_new)
+}
+
+/// Reloads the rail and both item lists after feeds or items were added.
+fn reload_lists(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
+    load_feeds(tui_app, ctx)?;
+    load_reader_items(tui_app, ctx)?;
+    load_latest_items(tui_app, ctx)
+}
+
 fn load_feeds(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
     tui_app.feeds = ctx.store.get_all_feeds()?;
+    tui_app.categories = ctx.store.get_all_categories()?;
+    tui_app.feed_category_links = ctx.store.get_feed_category_links()?;
+    tui_app.unread_counts = ctx.store.get_unread_counts()?;
+    tui_app.feed_failures = ctx.store.get_consecutive_failures()?;
+    tui_app.saved_searches = ctx.store.get_saved_searches()?;
+    tui_app.saved_search_unread = ctx.store.get_saved_search_unread_counts()?;
+    tui_app.rebuild_feed_rows();
 
     if tui_app.feeds.is_empty() {
-        tui_app.feed_index = 0;
-        tui_app.feed_list_state.select(None);
         clear_reader_feed_selection(tui_app, ctx)?;
         return Ok(());
     }
 
     if let Some(feed_id) = tui_app.loaded_feed_id() {
-        if let Some(index) = tui_app.feeds.iter().position(|feed| feed.id == feed_id) {
-            tui_app.feed_index = index;
-        } else {
+        if !tui_app.feeds.iter().any(|feed| feed.id == feed_id) {
             clear_reader_feed_selection(tui_app, ctx)?;
+        } else if let Some(index) = tui_app.feed_row_position(feed_id) {
+            // The loaded feed may sit inside a collapsed folder; keep it
+            // loaded and leave the cursor where it is in that case.
+            tui_app.feed_index = index;
+            tui_app.feed_list_state.select(Some(index));
         }
     }
 
-    if tui_app.feed_index >= tui_app.feeds.len() {
-        tui_app.feed_index = tui_app.feeds.len() - 1;
+    if let Some(search_id) = tui_app.loaded_search_id() {
+        match tui_app.saved_search_row_position(search_id) {
+            Some(index) => {
+                tui_app.feed_index = index;
+                tui_app.feed_list_state.select(Some(index));
+            }
+            None => clear_reader_feed_selection(tui_app, ctx)?,
+        }
     }
-    tui_app.feed_list_state.select(Some(tui_app.feed_index));
+
     Ok(())
 }
 
+/// Recount per-feed and smart feed unread counts (and folder aggregates)
+/// after a state change, without reloading the whole rail.
+fn reload_unread_counts(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
+    tui_app.unread_counts = ctx.store.get_unread_counts()?;
+    tui_app.saved_search_unread = ctx.store.get_saved_search_unread_counts()?;
+    tui_app.rebuild_feed_rows();
+    Ok(())
+}
+
+/// Refreshes counts and loaded item states after an item was marked read or
+/// unread. The store marks every copy in the item's duplicate group, which
+/// may sit in other feeds and lists.
+fn reload_after_read_change(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
+    reload_unread_counts(tui_app, ctx)?;
+    reload_item_states(tui_app, ctx)
+}
+
 fn clear_reader_feed_selection(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
     tui_app.loaded_feed = None;
+    tui_app.search = None;
     reload_item_states(tui_app, ctx)
 }
 
 fn load_reader_items(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
     if let Some(feed_id) = tui_app.loaded_feed_id() {
         load_items_for_feed(tui_app, ctx, feed_id)?;
+    } else if let Some(search_id) = tui_app.loaded_search_id() {
+        load_items_for_saved_search(tui_app, ctx, search_id)?;
+    } else if tui_app.showing_search_results() {
+        run_search(tui_app, ctx)?;
     } else {
         clear_reader_feed_selection(tui_app, ctx)?;
     }
@@ -455,6 +635,20 @@ fn load_latest_items(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
         tui_app.recent_limit,
         tui_app.latest_run_id,
     )?;
+    let mut items: Vec<Item> = tui_app
+        .latest_items
+        .iter()
+        .map(|recent| recent.item.clone())
+        .collect();
+    ctx.store.load_item_media(&mut items)?;
+    for (recent, item) in tui_app.latest_items.iter_mut().zip(items) {
+        recent.item = item;
+    }
+    if tui_app.tag_filter.is_some() {
+        let mut items = std::mem::take(&mut tui_app.latest_items);
+        items.retain(|recent| tui_app.matches_tag_filter(&recent.item));
+        tui_app.latest_items = items;
+    }
     if tui_app.latest_index >= tui_app.latest_items.len() && !tui_app.latest_items.is_empty() {
         tui_app.latest_index = tui_app.latest_items.len() - 1;
     }
@@ -481,9 +675,20 @@ fn set_item_view(tui_app: &mut TuiApp, ctx: &AppContext, view: ItemView) -> Resu
 }
 
 /// Sync the loaded feed with the highlighted feed cursor. Returns `true` when
-/// the cursor points at a real feed (so callers know whether moving focus to
-/// Items / Preview is meaningful), `false` when there are no feeds.
+/// the cursor points at a real feed or smart feed (so callers know whether
+/// moving focus to Items / Preview is meaningful), `false` when there are no
+/// feeds or a folder row is highlighted (the previously loaded feed stays in
+/// place).
 fn load_items_for_highlighted_feed(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<bool> {
+    if tui_app.selected_category_id().is_some() {
+        return Ok(false);
+    }
+    if let Some(search_id) = tui_app.selected_saved_search().map(|search| search.id) {
+        if tui_app.loaded_search_id() != Some(search_id) {
+            load_items_for_saved_search(tui_app, ctx, search_id)?;
+        }
+        return Ok(true);
+    }
     let Some(feed_id) = tui_app.selected_feed().map(|feed| feed.id) else {
         clear_reader_feed_selection(tui_app, ctx)?;
         return Ok(false);
@@ -497,12 +702,60 @@ fn load_items_for_highlighted_feed(tui_app: &mut TuiApp, ctx: &AppContext) -> Re
 }
 
 fn load_items_for_feed(tui_app: &mut TuiApp, ctx: &AppContext, feed_id: i64) -> Result<()> {
-    if let Some(index) = tui_app.feeds.iter().position(|feed| feed.id == feed_id) {
-        tui_app.feed_index = index;
-        tui_app.feed_list_state.select(Some(index));
+    if tui_app.selected_feed().map(|feed| feed.id) != Some(feed_id) {
+        if let Some(index) = tui_app.feed_row_position(feed_id) {
+            tui_app.feed_index = index;
+            tui_app.feed_list_state.select(Some(index));
+        }
     }
 
     let items = ctx.store.get_items_by_feed(feed_id)?;
+    let filtered = filter_items_for_view(tui_app, ctx, items)?;
+
+    // Constructing a fresh LoadedFeed makes the four pieces of correlated
+    // state (source / items / item_index / item_list_state) atomic. There is
+    // no transient where they could disagree.
+    tui_app.loaded_feed = Some(LoadedFeed::new(feed_id, filtered));
+    tui_app.search = None;
+    reload_item_states(tui_app, ctx)
+}
+
+/// Loads a smart feed's matches. The current view narrows them further, on
+/// top of the filter saved with the search.
+fn load_items_for_saved_search(
+    tui_app: &mut TuiApp,
+    ctx: &AppContext,
+    search_id: i64,
+) -> Result<()> {
+    let Some(search) = tui_app
+        .saved_searches
+        .iter()
+        .find(|search| search.id == search_id)
+        .cloned()
+    else {
+        return clear_reader_feed_selection(tui_app, ctx);
+    };
+    if tui_app.loaded_search_id() != Some(search_id) {
+        if let Some(index) = tui_app.saved_search_row_position(search_id) {
+            tui_app.feed_index = index;
+            tui_app.feed_list_state.select(Some(index));
+        }
+    }
+
+    let items = search.items(ctx.store.as_ref())?;
+    let filtered = filter_items_for_view(tui_app, ctx, items)?;
+    tui_app.loaded_feed = Some(LoadedFeed::saved_search(search_id, filtered));
+    tui_app.search = None;
+    reload_item_states(tui_app, ctx)
+}
+
+/// Keeps the items the current view and tag filter show, with their media
+/// and tags attached.
+fn filter_items_for_view(
+    tui_app: &TuiApp,
+    ctx: &AppContext,
+    items: Vec<Item>,
+) -> Result<Vec<Item>> {
     let filter = tui_app.item_view.filter();
     let mut filtered = Vec::new();
     for item in items {
@@ -510,12 +763,33 @@ fn load_items_for_feed(tui_app: &mut TuiApp, ctx: &AppContext, feed_id: i64) ->
             filtered.push(item);
         }
     }
+    ctx.store.load_item_media(&mut filtered)?;
+    filtered.retain(|item| tui_app.matches_tag_filter(item));
+    Ok(filtered)
+}
 
-    // Constructing a fresh LoadedFeed makes the four pieces of correlated
-    // state (feed_id / items / item_index / item_list_state) atomic. There is
-    // no transient where they could disagree.
-    tui_app.loaded_feed = Some(LoadedFeed::new(feed_id, filtered));
-    reload_item_states(tui_app, ctx)
+/// Runs a confirmed bulk mark-read and refreshes the cached state.
+/// Returns a description like `12 items` for the status bar.
+fn apply_mark_read(
+    tui_app: &mut TuiApp,
+    ctx: &AppContext,
+    pending: &PendingMarkRead,
+) -> Result<String> {
+    let marked = match &pending.target {
+        MarkReadTarget::Items(ids) => ctx.store.set_read_many(ids, true)?,
+        MarkReadTarget::Feeds(feed_ids) => ctx.store.mark_feeds_read(
+            feed_ids,
+            tui_app.item_view.filter(),
+            tui_app.tag_filter.as_deref(),
+        )?,
+    };
+    reload_item_states(tui_app, ctx)?;
+    reload_unread_counts(tui_app, ctx)?;
+    Ok(format!(
+        "{} item{}",
+        marked,
+        if marked == 1 { "" } else { "s" }
+    ))
 }
 
 fn item_matches_filter(
@@ -529,6 +803,9 @@ fn item_matches_filter(
     let is_queued = state.as_ref().map(|s| s.is_queued).unwrap_or(false);
     let is_saved = state.as_ref().map(|s| s.is_saved).unwrap_or(false);
     let is_archived = state.as_ref().map(|s| s.is_archived).unwrap_or(false);
+    if state.as_ref().is_some_and(|s| s.is_hidden) {
+        return Ok(false);
+    }
 
     Ok(match filter {
         crate::store::ItemListFilter::All => !is_archived,
@@ -555,12 +832,12 @@ fn reload_item_states(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
     ids.sort();
     ids.dedup();
 
-    tui_app.item_states.clear();
-    for item_id in ids {
-        if let Some(state) = ctx.store.get_item_state(&item_id)? {
-            tui_app.item_states.insert(item_id, state);
-        }
-    }
+    tui_app.item_revisions = if tui_app.show_updates {
+        ctx.store.get_revisions_for_items(&ids)?
+    } else {
+        HashMap::new()
+    };
+    tui_app.item_states = ctx.store.get_item_states(&ids)?;
     Ok(())
 }
 
@@ -638,6 +915,185 @@ fn focus_pane_directional(
     }
 }
 
+/// Edits or submits the open text prompt. The prompt was taken out of
+/// `tui_app`; it is put back unless the key closes it.
+fn handle_prompt_key(
+    tui_app: &mut TuiApp,
+    ctx: &AppContext,
+    mut prompt: TextPrompt,
+    key: &crossterm::event::KeyEvent,
+) -> Result<()> {
+    match key.code {
+        KeyCode::Esc if prompt.kind == PromptKind::Search => close_search(tui_app, ctx)?,
+        KeyCode::Esc => tui_app.clear_status(),
+        KeyCode::Enter => submit_prompt(tui_app, ctx, prompt)?,
+        KeyCode::Backspace => {
+            prompt.input.pop();
+            schedule_search(tui_app, &prompt);
+            tui_app.prompt = Some(prompt);
+        }
+        KeyCode::Char(c)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            prompt.input.push(c);
+            schedule_search(tui_app, &prompt);
+            tui_app.prompt = Some(prompt);
+        }
+        _ => tui_app.prompt = Some(prompt),
+    }
+    Ok(())
+}
+
+/// Opens the `/` prompt, keeping the query of a search that is still open.
+fn open_search(tui_app: &mut TuiApp) {
+    let search = tui_app.search.get_or_insert_with(|| SearchState {
+        query: String::new(),
+        previous: tui_app.loaded_feed.as_ref().map(|loaded| loaded.source),
+        previous_tab: tui_app.active_tab,
+        pending_since: None,
+        snippets: HashMap::new(),
+    });
+    let query = search.query.clone();
+    tui_app.prompt = Some(TextPrompt::new(PromptKind::Search, query));
+}
+
+/// Debounces search-as-you-type: the search runs on the first tick after
+/// typing pauses for `SEARCH_DEBOUNCE`.
+fn schedule_search(tui_app: &mut TuiApp, prompt: &TextPrompt) {
+    if prompt.kind != PromptKind::Search {
+        return;
+    }
+    if let Some(search) = tui_app.search.as_mut() {
+        search.query = prompt.input.clone();
+        search.pending_since = Some(std::time::Instant::now());
+    }
+}
+
+/// Fills the Reader Items pane with results for the open search. An
+/// unfinished query (an open quote, a trailing operator) keeps the previous
+/// results.
+fn run_search(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
+    let Some(search) = tui_app.search.as_mut() else {
+        return Ok(());
+    };
+    search.pending_since = None;
+    let query = incremental_query(&search.query);
+
+    let hits = if query.is_empty() {
+        Vec::new()
+    } else {
+        match ctx
+            .store
+            .search_items(&query, tui_app.item_view.filter(), SEARCH_RESULT_LIMIT)
+        {
+            Ok(hits) => hits,
+            Err(e) => {
+                tui_app.set_status(format!("Search failed: {}", e));
+                return Ok(());
+            }
+        }
+    };
+    let mut items = Vec::with_capacity(hits.len());
+    search.snippets.clear();
+    for hit in hits {
+        if let Some(snippet) = hit.snippet {
+            search.snippets.insert(hit.item.id.clone(), snippet);
+        }
+        items.push(hit.item);
+    }
+    let items = filter_items_for_view(tui_app, ctx, items)?;
+    tui_app.set_status(format!(
+        "{} {} for \"{}\"",
+        items.len(),
+        if items.len() == 1 {
+            "result"
+        } else {
+            "results"
+        },
+        query
+    ));
+    tui_app.active_tab = AppTab::Reader;
+    tui_app.loaded_feed = Some(LoadedFeed::search_results(items));
+    tui_app.preview_scroll = 0;
+    reload_item_states(tui_app, ctx)
+}
+
+/// Ends the search, bringing back the tab and list it replaced.
+fn close_search(tui_app: &mut TuiApp, ctx: &AppContext) -> Result<()> {
+    let showing_results = tui_app.showing_search_results();
+    let Some(search) = tui_app.search.take() else {
+        return Ok(());
+    };
+    if showing_results {
+        tui_app.active_tab = search.previous_tab;
+        match search.previous {
+            Some(ItemSource::Feed(feed_id)) => load_items_for_feed(tui_app, ctx, feed_id)?,
+            Some(ItemSource::SavedSearch(search_id)) => {
+                load_items_for_saved_search(tui_app, ctx, search_id)?
+            }
+            Some(ItemSource::Search) | None => clear_reader_feed_selection(tui_app, ctx)?,
+        }
+        tui_app.preview_scroll = 0;
+        tui_app.active_pane =
+            if tui_app.active_tab == AppTab::Reader && tui_app.loaded_feed.is_none() {
+                ActivePane::Feeds
+            } else {
+                ActivePane::Items
+            };
+    }
+    tui_app.set_status("Search closed".to_string());
+    Ok(())
+}
+
+fn submit_prompt(tui_app: &mut TuiApp, ctx: &AppContext, prompt: TextPrompt) -> Result<()> {
+    match prompt.kind {
+        PromptKind::Tags { item_id } => {
+            let wanted = parse_tags(&prompt.input);
+            let current = ctx.store.get_item_tags(&item_id)?;
+            let ids = [item_id.clone()];
+            for tag in &current {
+                if !wanted.iter().any(|w| w.eq_ignore_ascii_case(tag)) {
+                    ctx.store.untag_items(&ids, tag)?;
+                }
+            }
+            for tag in &wanted {
+                ctx.store.tag_items(&ids, tag)?;
+            }
+            let tags = ctx.store.get_item_tags(&item_id)?;
+            tui_app.set_item_tags(&item_id, &tags);
+            tui_app.set_status(if tags.is_empty() {
+                "Tags cleared".to_string()
+            } else {
+                format!("Tags: {}", tags.join(", "))
+            });
+        }
+        PromptKind::Search => {
+            if let Some(search) = tui_app.search.as_mut() {
+                search.query = prompt.input;
+            }
+            run_search(tui_app, ctx)?;
+            if tui_app.showing_search_results() {
+                tui_app.active_pane = ActivePane::Items;
+            }
+        }
+        PromptKind::TagFilter => {
+            let tag = prompt.input.trim();
+            tui_app.tag_filter = (!tag.is_empty()).then(|| tag.to_string());
+            tui_app.latest_index = 0;
+            tui_app.preview_scroll = 0;
+            load_reader_items(tui_app, ctx)?;
+            load_latest_items(tui_app, ctx)?;
+            tui_app.set_status(match &tui_app.tag_filter {
+                Some(tag) => format!("Showing items tagged {}", tag),
+                None => "Tag filter cleared".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
 fn handle_window_chord_key(
     tui_app: &mut TuiApp,
     ctx: &AppContext,
@@ -680,6 +1136,61 @@ fn handle_window_chord_key(
     Ok(())
 }
 
+/// Keys while link hints are open: digits and j/k choose a link, then
+/// o/Enter opens it, y copies it and a subscribes to it. Other keys close
+/// the hints.
+fn handle_link_hint_key(
+    tui_app: &mut TuiApp,
+    ctx: &Arc<AppContext>,
+    tx: &tokio::sync::mpsc::UnboundedSender<AppEvent>,
+    mut hints: LinkHints,
+    key: &crossterm::event::KeyEvent,
+) {
+    let link = hints.selected_link().to_string();
+    match key.code {
+        KeyCode::Char(digit) if digit.is_ascii_digit() => {
+            hints.push_digit(digit);
+            tui_app.link_hints = Some(hints);
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            hints.move_selection(true);
+            tui_app.link_hints = Some(hints);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            hints.move_selection(false);
+            tui_app.link_hints = Some(hints);
+        }
+        KeyCode::Char('o') | KeyCode::Enter => match open::that(&link) {
+            Ok(()) => tui_app.set_status(format!("Opened {}", link)),
+            Err(e) => tui_app.set_status(format!("Failed to open browser: {}", e)),
+        },
+        KeyCode::Char('y') => match clipboard::copy(&link) {
+            Ok(()) => tui_app.set_status(format!("Copied {}", link)),
+            Err(e) => tui_app.set_status(format!("Failed to copy link: {}", e)),
+        },
+        KeyCode::Char('a') => {
+            tui_app.set_status(format!("Looking for a feed at {}...", link));
+            let ctx = ctx.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let result = subscribe_to_link(&ctx, &link).await;
+                let _ = tx.send(AppEvent::Subscribed(result));
+            });
+        }
+        _ => tui_app.clear_status(),
+    }
+}
+
+/// Subscribes to `url`, or to the first feed the page at `url` advertises.
+async fn subscribe_to_link(ctx: &AppContext, url: &str) -> Result<Subscription> {
+    subscribe(ctx, url, RefreshSource::Tui, |page_url, candidates| {
+        candidates.into_iter().next().map(Some).ok_or_else(|| {
+            RivuletError::FeedParse(format!("{} is not a feed and links to no feeds", page_url))
+        })
+    })
+    .await
+}
+
 fn focus_left_for_tab(tui_app: &TuiApp) -> ActivePane {
     match tui_app.active_tab {
         AppTab::Latest => match tui_app.active_pane {
@@ -750,6 +1261,8 @@ fn update_item_state(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::ItemListFilter;
+    use crate::tui::app::FeedRow;
     use crossterm::event::{KeyEvent, KeyModifiers};
 
     fn add_feed_with_items(ctx: &AppContext, title: &str, item_count: usize) -> i64 {
@@ -796,7 +1309,7 @@ mod tests {
         assert_eq!(tui_app.loaded_feed_id(), Some(feed_id));
         assert_eq!(tui_app.loaded_items().len(), 2);
         let loaded = tui_app.loaded_feed.as_ref().unwrap();
-        assert_eq!(loaded.feed_id, feed_id);
+        assert_eq!(loaded.source, ItemSource::Feed(feed_id));
         assert_eq!(loaded.item_list_state.selected(), Some(0));
     }
 
@@ -1179,6 +1692,463 @@ mod tests {
         assert_eq!(tui_app.loaded_items().len(), 1);
     }
 
+    /// Folders render ahead of unfiled feeds, aggregate unread counts across
+    /// nested folders, and collapse without unloading the open feed.
+    #[test]
+    fn feed_rail_groups_feeds_into_collapsible_folders() {
+        let ctx = AppContext::in_memory().unwrap();
+        let rust_feed = add_feed_with_items(&ctx, "alpha", 2);
+        let news_feed = add_feed_with_items(&ctx, "beta", 3);
+        let _loose_feed = add_feed_with_items(&ctx, "gamma", 1);
+
+        let tech = ctx
+            .store
+            .add_category(&crate::domain::Category::new("Tech".into(), None))
+            .unwrap();
+        let rust = ctx
+            .store
+            .add_category(&crate::domain::Category::new("Rust".into(), Some(tech)))
+            .unwrap();
+        ctx.store.add_feed_to_category(rust_feed, rust).unwrap();
+        ctx.store.add_feed_to_category(news_feed, tech).unwrap();
+
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        // Tech, Rust, alpha, beta, gamma
+        assert_eq!(tui_app.feed_rows.len(), 5);
+        assert_eq!(tui_app.selected_category_id(), Some(tech));
+        assert!(matches!(
+            tui_app.feed_rows[0],
+            FeedRow::Category {
+                unread: 5,
+                depth: 0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            tui_app.feed_rows[1],
+            FeedRow::Category {
+                unread: 2,
+                depth: 1,
+                ..
+            }
+        ));
+
+        // A folder row never loads items.
+        assert!(!load_items_for_highlighted_feed(&mut tui_app, &ctx).unwrap());
+        assert_eq!(tui_app.loaded_feed_id(), None);
+
+        load_items_for_feed(&mut tui_app, &ctx, rust_feed).unwrap();
+        assert_eq!(tui_app.feed_index, 2);
+
+        tui_app.toggle_category(tech);
+        // Tech (collapsed), gamma
+        assert_eq!(tui_app.feed_rows.len(), 2);
+        load_feeds(&mut tui_app, &ctx).unwrap();
+        assert_eq!(tui_app.loaded_feed_id(), Some(rust_feed));
+
+        tui_app.toggle_category(tech);
+        assert_eq!(tui_app.feed_rows.len(), 5);
+    }
+
+    /// Every failed refresh lands in the fetch log; after enough consecutive
+    /// failures the rail marks the feed as broken.
+    #[tokio::test]
+    async fn failing_feed_is_marked_broken_in_rail() {
+        let mock = Arc::new(crate::fetcher::testing::MockFetcher::new());
+        let ctx = AppContext::in_memory_with_fetcher(mock.clone()).unwrap();
+        let broken = add_feed_with_items(&ctx, "alpha", 0);
+        let feed = ctx.store.get_feed(broken).unwrap().unwrap();
+
+        for _ in 0..crate::domain::health::BROKEN_AFTER_FAILURES {
+            let results = ctx
+                .parallel_fetcher
+                .fetch_all(vec![feed.clone()], ctx.store.clone(), &ctx.normalizer, None)
+                .await;
+            assert!(results[0].1.is_err());
+        }
+
+        let log = ctx.store.get_fetch_log(broken, 10).unwrap();
+        assert_eq!(
+            log.len() as i64,
+            crate::domain::health::BROKEN_AFTER_FAILURES
+        );
+        assert_eq!(log[0].error_kind.as_deref(), Some("other"));
+
+        let tui_app = reader_app_with_expanded_feeds(&ctx);
+        assert!(tui_app.is_feed_broken(&ctx.store.get_feed(broken).unwrap().unwrap()));
+
+        mock.set_response(
+            feed.url.clone(),
+            crate::fetcher::FetchResult::NotModified { redirect: None },
+        );
+        ctx.parallel_fetcher
+            .fetch_all(vec![feed], ctx.store.clone(), &ctx.normalizer, None)
+            .await;
+        let tui_app = reader_app_with_expanded_feeds(&ctx);
+        assert!(!tui_app.is_feed_broken(&ctx.store.get_feed(broken).unwrap().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn subscribe_to_link_follows_page_to_its_feed() {
+        let content = |body: &str| crate::fetcher::FetchResult::Content {
+            status: 200,
+            body: body.as_bytes().to_vec(),
+            etag: None,
+            last_modified: None,
+            max_age_secs: None,
+            redirect: None,
+        };
+        let mock = Arc::new(crate::fetcher::testing::MockFetcher::new());
+        mock.set_response(
+            "https://blog.example.com/post",
+            content(
+                r#"<head><link rel="alternate" type="application/rss+xml" href="/rss"></head>"#,
+            ),
+        );
+        mock.set_response(
+            "https://blog.example.com/rss",
+            content(
+                r#"<rss version="2.0"><channel><title>Blog</title>
+<item><guid>a</guid><title>First</title></item></channel></rss>"#,
+            ),
+        );
+        mock.set_response(
+            "https://other.example.com/",
+            content(
+                r#"<head><link rel="alternate" type="application/rss+xml" href="/feed"></head>"#,
+            ),
+        );
+        mock.set_response(
+            "https://other.example.com/feed",
+            content("<html>Not a feed</html>"),
+        );
+        let ctx = AppContext::in_memory_with_fetcher(mock).unwrap();
+
+        let Subscription::Added { url, new_count, .. } =
+            subscribe_to_link(&ctx, "https://blog.example.com/post")
+                .await
+                .unwrap()
+        else {
+            panic!("expected a new subscription");
+        };
+        assert_eq!(url, "https://blog.example.com/rss");
+        assert_eq!(new_count, 1);
+        let feed = ctx.store.get_feed_by_url(&url).unwrap().unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Blog"));
+
+        assert!(matches!(
+            subscribe_to_link(&ctx, "https://blog.example.com/post").await,
+            Ok(Subscription::Existing {
+                reactivated: false,
+                ..
+            })
+        ));
+        assert!(subscribe_to_link(&ctx, "https://nowhere.example.com/")
+            .await
+            .is_err());
+
+        // A candidate that is not a feed is not subscribed
+        assert!(subscribe_to_link(&ctx, "https://other.example.com/")
+            .await
+            .is_err());
+        assert_eq!(ctx.store.get_all_feeds().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn loaded_items_carry_enclosures_and_categories() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "podcast", 0);
+        let mut item = Item::new(feed_id, "https://example.com/podcast.xml", "ep-1");
+        item.enclosures = vec![crate::domain::Enclosure::new(
+            "https://cdn.example.com/ep1.mp3".into(),
+        )];
+        item.categories = vec!["Rust".into()];
+        ctx.store.add_item(&item).unwrap();
+
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        load_items_for_feed(&mut tui_app, &ctx, feed_id).unwrap();
+
+        let loaded = tui_app.selected_item().unwrap();
+        assert_eq!(loaded.enclosures, item.enclosures);
+        assert_eq!(loaded.categories, item.categories);
+    }
+
+    #[test]
+    fn mark_all_read_on_folder_covers_subfolders_and_respects_view() {
+        let ctx = AppContext::in_memory().unwrap();
+        let rust_feed = add_feed_with_items(&ctx, "alpha", 2);
+        let news_feed = add_feed_with_items(&ctx, "beta", 3);
+        let loose_feed = add_feed_with_items(&ctx, "gamma", 1);
+        let tech = ctx
+            .store
+            .add_category(&crate::domain::Category::new("Tech".into(), None))
+            .unwrap();
+        let rust = ctx
+            .store
+            .add_category(&crate::domain::Category::new("Rust".into(), Some(tech)))
+            .unwrap();
+        ctx.store.add_feed_to_category(rust_feed, rust).unwrap();
+        ctx.store.add_feed_to_category(news_feed, tech).unwrap();
+        let starred = ctx.store.get_items_by_feed(news_feed).unwrap()[0]
+            .id
+            .clone();
+        ctx.store.set_starred(&starred, true).unwrap();
+
+        let plan = |tui_app: &TuiApp| {
+            tui_app
+                .plan_mark_all_read(|feed_ids, filter, tag| {
+                    ctx.store.count_unread_in_feeds(feed_ids, filter, tag)
+                })
+                .unwrap()
+        };
+
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        assert_eq!(tui_app.selected_category_id(), Some(tech));
+        tui_app.item_view = ItemView::Starred;
+        let pending = plan(&tui_app).unwrap();
+        assert_eq!(
+            pending.target,
+            MarkReadTarget::Feeds(vec![rust_feed, news_feed])
+        );
+        assert_eq!(
+            pending.description,
+            "everything in \"Tech\" shown in Starred"
+        );
+
+        assert_eq!(
+            apply_mark_read(&mut tui_app, &ctx, &pending).unwrap(),
+            "1 item"
+        );
+        assert_eq!(tui_app.unread_counts.get(&news_feed), Some(&2));
+        // The folder still has unread items, just none the view shows.
+        assert_eq!(plan(&tui_app), None);
+
+        tui_app.item_view = ItemView::All;
+        let pending = plan(&tui_app).unwrap();
+        apply_mark_read(&mut tui_app, &ctx, &pending).unwrap();
+        assert_eq!(tui_app.unread_counts.get(&rust_feed), None);
+        assert_eq!(tui_app.unread_counts.get(&news_feed), None);
+        assert_eq!(tui_app.unread_counts.get(&loose_feed), Some(&1));
+        assert_eq!(plan(&tui_app), None);
+    }
+
+    #[test]
+    fn mark_older_read_marks_only_items_below_the_cursor() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "alpha", 4);
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        load_items_for_feed(&mut tui_app, &ctx, feed_id).unwrap();
+        tui_app.active_pane = ActivePane::Items;
+        tui_app.move_down();
+
+        let below: Vec<String> = tui_app.loaded_items()[2..]
+            .iter()
+            .map(|item| item.id.clone())
+            .collect();
+        ctx.store.set_read(&below[0], true).unwrap();
+        reload_item_states(&mut tui_app, &ctx).unwrap();
+
+        let pending = tui_app.plan_mark_older_read().unwrap();
+        assert_eq!(pending.target, MarkReadTarget::Items(below[1..].to_vec()));
+        apply_mark_read(&mut tui_app, &ctx, &pending).unwrap();
+
+        let loaded = tui_app.loaded_items().to_vec();
+        assert!(!tui_app.is_item_read(&loaded[0].id));
+        assert!(!tui_app.is_item_read(&loaded[1].id));
+        assert!(tui_app.is_item_read(&loaded[2].id));
+        assert!(tui_app.is_item_read(&loaded[3].id));
+
+        tui_app.active_pane = ActivePane::Feeds;
+        assert_eq!(tui_app.plan_mark_older_read(), None);
+    }
+
+    #[test]
+    fn changed_items_are_flagged_until_read_again() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "alpha", 2);
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        load_items_for_feed(&mut tui_app, &ctx, feed_id).unwrap();
+
+        let loaded = tui_app.loaded_items().to_vec();
+        ctx.store.set_read(&loaded[0].id, true).unwrap();
+        let mut changed = loaded[0].clone();
+        changed.title = Some("corrected".into());
+        ctx.store.add_items_with_report(&[changed]).unwrap();
+        reload_item_states(&mut tui_app, &ctx).unwrap();
+
+        assert!(tui_app.is_item_updated(&loaded[0].id));
+        assert!(!tui_app.is_item_updated(&loaded[1].id));
+        assert_eq!(
+            tui_app
+                .previous_revision(&loaded[0].id)
+                .and_then(|rev| rev.title.as_deref()),
+            loaded[0].title.as_deref()
+        );
+
+        ctx.store.set_read(&loaded[0].id, true).unwrap();
+        reload_item_states(&mut tui_app, &ctx).unwrap();
+        assert!(!tui_app.is_item_updated(&loaded[0].id));
+
+        tui_app.show_updates = false;
+        assert!(tui_app.previous_revision(&loaded[0].id).is_none());
+    }
+
+    #[test]
+    fn tag_prompt_sets_tags_and_tag_filter_narrows_lists() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "alpha", 3);
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        load_items_for_feed(&mut tui_app, &ctx, feed_id).unwrap();
+        let item_id = tui_app.loaded_items()[1].id.clone();
+        let type_and_submit = |tui_app: &mut TuiApp, kind: PromptKind, text: &str| {
+            let mut prompt = TextPrompt::new(kind, String::new());
+            prompt.input = text.to_string();
+            let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+            handle_prompt_key(tui_app, &ctx, prompt, &enter).unwrap();
+        };
+
+        let tags_prompt = PromptKind::Tags {
+            item_id: item_id.clone(),
+        };
+        type_and_submit(&mut tui_app, tags_prompt.clone(), "work, Rust,, work ");
+        assert_eq!(
+            ctx.store.get_item_tags(&item_id).unwrap(),
+            vec!["Rust", "work"]
+        );
+        assert_eq!(tui_app.loaded_items()[1].tags, vec!["Rust", "work"]);
+
+        type_and_submit(&mut tui_app, PromptKind::TagFilter, "rust");
+        assert_eq!(tui_app.view_label(), "All #rust");
+        assert_eq!(tui_app.loaded_items().len(), 1);
+        assert_eq!(tui_app.loaded_items()[0].id, item_id);
+
+        type_and_submit(&mut tui_app, tags_prompt, "work");
+        assert_eq!(ctx.store.get_item_tags(&item_id).unwrap(), vec!["work"]);
+
+        type_and_submit(&mut tui_app, PromptKind::TagFilter, "");
+        assert_eq!(tui_app.tag_filter, None);
+        assert_eq!(tui_app.loaded_items().len(), 3);
+    }
+
+    #[test]
+    fn mark_all_read_on_a_rail_feed_respects_the_tag_filter() {
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "alpha", 3);
+        let tagged = ctx.store.get_items_by_feed(feed_id).unwrap()[0].id.clone();
+        ctx.store
+            .tag_items(std::slice::from_ref(&tagged), "work")
+            .unwrap();
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        tui_app.tag_filter = Some("work".to_string());
+        let plan = |tui_app: &TuiApp| {
+            tui_app
+                .plan_mark_all_read(|feed_ids, filter, tag| {
+                    ctx.store.count_unread_in_feeds(feed_ids, filter, tag)
+                })
+                .unwrap()
+        };
+
+        let pending = plan(&tui_app).unwrap();
+        assert_eq!(
+            pending.description,
+            "everything in \"alpha\" tagged \"work\""
+        );
+        assert_eq!(
+            apply_mark_read(&mut tui_app, &ctx, &pending).unwrap(),
+            "1 item"
+        );
+        assert_eq!(tui_app.unread_counts.get(&feed_id), Some(&2));
+        assert_eq!(plan(&tui_app), None);
+    }
+
+    #[test]
+    fn saved_searches_sit_atop_the_rail_and_load_matches_across_feeds() {
+        let ctx = AppContext::in_memory().unwrap();
+        add_feed_with_items(&ctx, "alpha", 2);
+        add_feed_with_items(&ctx, "beta", 2);
+        let search_id = ctx
+            .store
+            .save_search("item zero", "\"item 0\"", ItemListFilter::All)
+            .unwrap();
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+
+        assert_eq!(
+            tui_app.feed_rows[0],
+            FeedRow::SavedSearch { search_index: 0 }
+        );
+        assert_eq!(tui_app.saved_search_unread[&search_id], 2);
+
+        assert!(load_items_for_highlighted_feed(&mut tui_app, &ctx).unwrap());
+        assert_eq!(tui_app.loaded_search_id(), Some(search_id));
+        assert_eq!(tui_app.loaded_feed_id(), None);
+        let titles: Vec<_> = tui_app
+            .loaded_items()
+            .iter()
+            .filter_map(|item| item.title.clone())
+            .collect();
+        assert_eq!(titles.len(), 2);
+        assert!(titles.iter().all(|title| title.ends_with("item 0")));
+
+        ctx.store
+            .set_read(&tui_app.loaded_items()[0].id, true)
+            .unwrap();
+        reload_unread_counts(&mut tui_app, &ctx).unwrap();
+        assert_eq!(tui_app.saved_search_unread[&search_id], 1);
+
+        set_item_view(&mut tui_app, &ctx, ItemView::Unread).unwrap();
+        assert_eq!(tui_app.loaded_items().len(), 1);
+
+        ctx.store.delete_saved_search("item zero").unwrap();
+        load_feeds(&mut tui_app, &ctx).unwrap();
+        assert_eq!(tui_app.loaded_search_id(), None);
+        assert!(tui_app.loaded_items().is_empty());
+    }
+
+    #[test]
+    fn search_prompt_shows_results_as_you_type_and_esc_restores_the_feed() {
+        assert_eq!(incremental_query("rust asy"), "rust asy*");
+        assert_eq!(incremental_query("\"rust asy"), "\"rust asy");
+        assert_eq!(incremental_query("rust AND"), "rust AND");
+        assert_eq!(incremental_query("rust is:unread"), "rust is:unread");
+        assert_eq!(incremental_query("title:asy"), "title:asy*");
+
+        let ctx = AppContext::in_memory().unwrap();
+        let feed_id = add_feed_with_items(&ctx, "alpha", 2);
+        add_feed_with_items(&ctx, "beta", 3);
+        let mut tui_app = reader_app_with_expanded_feeds(&ctx);
+        load_items_for_feed(&mut tui_app, &ctx, feed_id).unwrap();
+
+        open_search(&mut tui_app);
+        for c in "bet".chars() {
+            let prompt = tui_app.prompt.take().unwrap();
+            let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            handle_prompt_key(&mut tui_app, &ctx, prompt, &key).unwrap();
+        }
+        let search = tui_app.search.as_ref().unwrap();
+        assert_eq!(search.query, "bet");
+        assert!(!search.is_due(std::time::Instant::now()));
+        assert!(search.is_due(std::time::Instant::now() + app::SEARCH_DEBOUNCE));
+
+        run_search(&mut tui_app, &ctx).unwrap();
+        assert!(tui_app.showing_search_results());
+        assert_eq!(tui_app.loaded_items().len(), 3);
+        assert_eq!(tui_app.search_terms(), vec!["bet"]);
+
+        tui_app.preview_match_rows = vec![0, 12, 30];
+        assert_eq!(tui_app.jump_to_match(true), Some(2));
+        assert_eq!(tui_app.preview_scroll, 12);
+        assert_eq!(tui_app.jump_to_match(true), Some(3));
+        assert_eq!(tui_app.jump_to_match(true), Some(1));
+        assert_eq!(tui_app.jump_to_match(false), Some(3));
+
+        let prompt = tui_app.prompt.take().unwrap();
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        handle_prompt_key(&mut tui_app, &ctx, prompt, &esc).unwrap();
+        assert!(tui_app.search.is_none());
+        assert_eq!(tui_app.loaded_feed_id(), Some(feed_id));
+        assert_eq!(tui_app.loaded_items().len(), 2);
+    }
+
     // Toggle-action coverage lives in tests/tui_e2e.rs::test_toggle_actions_persist_through_dispatch
     // — that test drives the actual Action::ToggleX dispatch via key presses,
     // which is what users hit and what the unit-level test was claiming to
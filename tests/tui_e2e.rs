@@ -269,6 +269,62 @@ async fn test_layout_maximize_preview() {
     harness.quit().await;
 }
 
+#[tokio::test]
+async fn test_link_hints_pick_a_link_from_the_article() {
+    let ctx = Arc::new(AppContext::in_memory().unwrap());
+    let feed_id = add_feed_with_items(&ctx, "alpha", 0);
+    let mut item = Item::new(feed_id, "https://example.com/alpha.xml", "linked");
+    item.title = Some("Linked item".into());
+    item.link = Some("https://example.com/posts/1".into());
+    item.content = Some(
+        "<p>See <a href=\"/docs\">the docs</a> and <a href=\"https://example.org/\">this</a>.</p>"
+            .into(),
+    );
+    ctx.store.add_item(&item).unwrap();
+    let mut harness = Harness::setup(ctx).await;
+
+    harness.send_key(KeyCode::Char('F'));
+    assert!(
+        harness
+            .step_until(
+                |b| {
+                    let s = buffer_to_string(b);
+                    s.contains(" Links ")
+                        && s.contains("[1] https://example.com/docs")
+                        && s.contains("[2] https://example.org/")
+                        && s.contains("Link 1/2")
+                },
+                Duration::from_secs(2),
+            )
+            .await,
+        "F should list the article's links"
+    );
+
+    harness.send_key(KeyCode::Char('2'));
+    assert!(
+        harness
+            .step_until(
+                |b| buffer_to_string(b).contains("Link 2/2"),
+                Duration::from_secs(2),
+            )
+            .await,
+        "typing a number should select that link"
+    );
+
+    harness.send_key(KeyCode::Esc);
+    assert!(
+        harness
+            .step_until(
+                |b| !buffer_to_string(b).contains(" Links "),
+                Duration::from_secs(2),
+            )
+            .await,
+        "Esc should close the link picker"
+    );
+
+    harness.quit().await;
+}
+
 #[tokio::test]
 async fn test_visual_markers() {
     let ctx = Arc::new(AppContext::in_memory().unwrap());
@@ -325,6 +381,7 @@ async fn test_refresh_progress_visuals() {
     mock.set_response(
         feed.url.clone(),
         FetchResult::Content {
+            status: 200,
             body: br#"<?xml version="1.0" encoding="UTF-8"?>
 <rss version="2.0"><channel><title>Alpha</title>
 <item><title>New Item</title><link>http://example.com/1</link></item>
@@ -332,6 +389,8 @@ async fn test_refresh_progress_visuals() {
                 .to_vec(),
             etag: None,
             last_modified: None,
+            max_age_secs: None,
+            redirect: None,
         },
     );
 
@@ -457,6 +516,67 @@ async fn test_toggle_actions_persist_through_dispatch() {
     harness.quit().await;
 }
 
+#[tokio::test]
+async fn test_toggle_read_updates_duplicate_copies_in_other_feeds() {
+    // The same article in two feeds shares a duplicate group; reading one
+    // copy reads the other, and the rail must show that without a reload.
+    let ctx = Arc::new(AppContext::in_memory().unwrap());
+    let alpha = add_feed_with_items(&ctx, "alpha", 0);
+    let beta = add_feed_with_items(&ctx, "beta", 1);
+    for (feed_id, title) in [(alpha, "alpha"), (beta, "beta")] {
+        let feed_url = format!("https://example.com/{title}.xml");
+        let mut item = Item::new(feed_id, &feed_url, &format!("{title}-shared"));
+        item.title = Some(format!("shared story in {title}"));
+        item.link = Some("https://news.example.com/shared-story".to_string());
+        ctx.store.add_item(&item).unwrap();
+    }
+    let beta_copy = ctx
+        .store
+        .get_items_by_feed(beta)
+        .unwrap()
+        .into_iter()
+        .find(|item| item.link.is_some())
+        .unwrap()
+        .id;
+    let mut harness = Harness::setup(ctx.clone()).await;
+
+    harness.send_key(KeyCode::Char(']')); // Reader, loads alpha.
+    harness.send_key(KeyCode::Char('l')); // Focus Items.
+    assert!(
+        harness
+            .step_until(
+                |b| {
+                    let screen = buffer_to_string(b);
+                    screen.contains("shared story in alpha") && screen.contains("beta (2)")
+                },
+                Duration::from_secs(2),
+            )
+            .await,
+        "alpha's items and beta's count should render"
+    );
+
+    harness.send_key(KeyCode::Char('r'));
+    assert!(
+        harness
+            .step_until(
+                |b| {
+                    let screen = buffer_to_string(b);
+                    screen.contains("beta (1)") && !screen.contains("alpha (1)")
+                },
+                Duration::from_secs(2),
+            )
+            .await,
+        "reading the alpha copy should also count beta's copy as read"
+    );
+    assert!(ctx
+        .store
+        .get_item_state(&beta_copy)
+        .unwrap()
+        .is_some_and(|state| state.is_read));
+
+    harness.quit().await;
+}
+
 /// Poll the store every ~25ms for up to 2s, returning true once `predicate`
 /// is satisfied. Used when the rendered marker is empty/ambiguous (read) or
 /// the item leaves the view (archived).
@@ -490,6 +610,7 @@ async fn test_new_marker_after_refresh() {
     mock.set_response(
         feed.url.clone(),
         FetchResult::Content {
+            status: 200,
             body: br#"<?xml version="1.0" encoding="UTF-8"?>
 <rss version="2.0"><channel><title>Alpha</title>
 <item><title>Brand New Item</title><link>http://example.com/new-1</link></item>
@@ -497,6 +618,8 @@ async fn test_new_marker_after_refresh() {
                 .to_vec(),
             etag: None,
             last_modified: None,
+            max_age_secs: None,
+            redirect: None,
         },
     );
 